@@ -0,0 +1,453 @@
+use crate::export::{is_requirement, purl::purl, unique_soups};
+use crate::soup::model::{Ecosystem, Soup, SoupContexts, SouperIoError};
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use serde::Serialize;
+use serde_json::Value;
+use std::io;
+
+const SPEC_VERSION: &str = "1.5";
+const XML_NAMESPACE: &str = "http://cyclonedx.org/schema/bom/1.5";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+}
+
+#[derive(Serialize)]
+struct Metadata {
+    tools: Tools,
+}
+
+#[derive(Serialize)]
+struct Tools {
+    components: Vec<Tool>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    #[serde(rename = "type")]
+    component_type: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct Component {
+    #[serde(rename = "type")]
+    component_type: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<Property>,
+    evidence: Evidence,
+}
+
+#[derive(Serialize)]
+struct Property {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+struct Evidence {
+    occurrences: Vec<Occurrence>,
+}
+
+#[derive(Serialize)]
+struct Occurrence {
+    location: String,
+}
+
+pub fn to_json(contexts: &SoupContexts) -> Result<String, SouperIoError> {
+    match serde_json::to_string_pretty(&bom(contexts)) {
+        Ok(json) => Ok(json),
+        Err(e) => Err(SouperIoError {
+            message: format!("Not able to serialize CycloneDX BOM to json: {}", e),
+        }),
+    }
+}
+
+pub fn to_xml(contexts: &SoupContexts) -> Result<String, SouperIoError> {
+    let bom = bom(contexts);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    if let Err(e) = write_xml(&mut writer, &bom) {
+        return Err(SouperIoError {
+            message: format!("Not able to serialize CycloneDX BOM to xml: {}", e),
+        });
+    }
+    match String::from_utf8(writer.into_inner()) {
+        Ok(xml) => Ok(xml),
+        Err(e) => Err(SouperIoError {
+            message: format!("Not able to serialize CycloneDX BOM to xml: {}", e),
+        }),
+    }
+}
+
+fn bom(contexts: &SoupContexts) -> Bom {
+    // SOUPs that only differ in case of their name, e.g. `Django` and `django`, share a package
+    // URL, which references have to be unique by
+    let mut components: Vec<Component> = Vec::new();
+    for (soup, paths) in unique_soups(contexts) {
+        let component = component(soup, &paths);
+        match components
+            .iter_mut()
+            .find(|existing| existing.bom_ref == component.bom_ref)
+        {
+            Some(existing) => {
+                for occurrence in component.evidence.occurrences {
+                    if !existing
+                        .evidence
+                        .occurrences
+                        .iter()
+                        .any(|existing| existing.location == occurrence.location)
+                    {
+                        existing.evidence.occurrences.push(occurrence);
+                    }
+                }
+            }
+            None => components.push(component),
+        }
+    }
+    Bom {
+        bom_format: "CycloneDX",
+        spec_version: SPEC_VERSION,
+        version: 1,
+        metadata: Metadata {
+            tools: Tools {
+                components: vec![Tool {
+                    component_type: "application",
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                }],
+            },
+        },
+        components,
+    }
+}
+
+fn component(soup: &Soup, paths: &[&String]) -> Component {
+    let purl = purl(soup);
+    let is_requirement = is_requirement(soup);
+    // Package URLs leave out requirements, which would make them ambiguous as reference
+    let bom_ref = match &purl {
+        Some(purl) if !is_requirement => purl.to_owned(),
+        _ => format!("{}@{}", soup.name, soup.version),
+    };
+    let mut properties = soup
+        .meta
+        .iter()
+        .map(|(key, value)| Property {
+            name: key.to_owned(),
+            value: match value {
+                Value::String(value) => value.to_owned(),
+                value => value.to_string(),
+            },
+        })
+        .collect::<Vec<_>>();
+    if is_requirement {
+        properties.push(Property {
+            name: "requirement".to_owned(),
+            value: soup.version.to_owned(),
+        });
+    }
+    Component {
+        component_type: match soup.ecosystem {
+            Some(Ecosystem::Docker) => "container",
+            _ => "library",
+        },
+        bom_ref,
+        name: soup.name.to_owned(),
+        version: soup.version.to_owned(),
+        purl,
+        properties,
+        evidence: Evidence {
+            occurrences: paths
+                .iter()
+                .map(|path| Occurrence {
+                    location: path.to_string(),
+                })
+                .collect(),
+        },
+    }
+}
+
+fn write_xml(writer: &mut Writer<Vec<u8>>, bom: &Bom) -> io::Result<()> {
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer
+        .create_element("bom")
+        .with_attribute(("xmlns", XML_NAMESPACE))
+        .with_attribute(("version", bom.version.to_string().as_str()))
+        .write_inner_content(|writer| {
+            writer
+                .create_element("metadata")
+                .write_inner_content(|writer| {
+                    writer
+                        .create_element("tools")
+                        .write_inner_content(|writer| {
+                            writer
+                                .create_element("components")
+                                .write_inner_content(|writer| {
+                                    for tool in &bom.metadata.tools.components {
+                                        writer
+                                            .create_element("component")
+                                            .with_attribute(("type", tool.component_type))
+                                            .write_inner_content(|writer| {
+                                                write_text(writer, "name", tool.name)?;
+                                                write_text(writer, "version", tool.version)
+                                            })?;
+                                    }
+                                    Ok(())
+                                })?;
+                            Ok(())
+                        })?;
+                    Ok(())
+                })?;
+            writer
+                .create_element("components")
+                .write_inner_content(|writer| {
+                    for component in &bom.components {
+                        write_component(writer, component)?;
+                    }
+                    Ok(())
+                })?;
+            Ok(())
+        })?;
+    Ok(())
+}
+
+fn write_component(writer: &mut Writer<Vec<u8>>, component: &Component) -> io::Result<()> {
+    writer
+        .create_element("component")
+        .with_attribute(("type", component.component_type))
+        .with_attribute(("bom-ref", component.bom_ref.as_str()))
+        .write_inner_content(|writer| {
+            write_text(writer, "name", &component.name)?;
+            write_text(writer, "version", &component.version)?;
+            if let Some(purl) = &component.purl {
+                write_text(writer, "purl", purl)?;
+            }
+            if !component.properties.is_empty() {
+                writer
+                    .create_element("properties")
+                    .write_inner_content(|writer| {
+                        for property in &component.properties {
+                            writer
+                                .create_element("property")
+                                .with_attribute(("name", property.name.as_str()))
+                                .write_text_content(BytesText::new(&property.value))?;
+                        }
+                        Ok(())
+                    })?;
+            }
+            writer
+                .create_element("evidence")
+                .write_inner_content(|writer| {
+                    writer
+                        .create_element("occurrences")
+                        .write_inner_content(|writer| {
+                            for occurrence in &component.evidence.occurrences {
+                                writer.create_element("occurrence").write_inner_content(
+                                    |writer| write_text(writer, "location", &occurrence.location),
+                                )?;
+                            }
+                            Ok(())
+                        })?;
+                    Ok(())
+                })?;
+            Ok(())
+        })?;
+    Ok(())
+}
+
+fn write_text(writer: &mut Writer<Vec<u8>>, name: &str, text: &str) -> io::Result<()> {
+    writer
+        .create_element(name)
+        .write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+    use test_case::test_case;
+
+    fn create_contexts() -> SoupContexts {
+        let postgres = Soup {
+            name: "postgres".to_owned(),
+            version: "14.4".to_owned(),
+            ecosystem: Some(Ecosystem::Docker),
+            meta: json!({"rationale": "Store data"})
+                .as_object()
+                .unwrap()
+                .clone(),
+            ..Default::default()
+        };
+        let curl = Soup {
+            name: "curl".to_owned(),
+            version: "7.81.0".to_owned(),
+            ecosystem: Some(Ecosystem::Apt),
+            ..Default::default()
+        };
+        SoupContexts {
+            contexts: vec![
+                (
+                    "src/Dockerfile".to_owned(),
+                    vec![postgres.clone(), curl]
+                        .into_iter()
+                        .collect::<BTreeSet<Soup>>(),
+                ),
+                (
+                    "test/Dockerfile".to_owned(),
+                    vec![postgres].into_iter().collect::<BTreeSet<Soup>>(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test_case(Ecosystem::Npm, "some-lib", "^1.0.0", "pkg:npm/some-lib")]
+    #[test_case(Ecosystem::Cargo, "winapi", "0.3", "pkg:cargo/winapi")]
+    fn requirement_as_property(ecosystem: Ecosystem, name: &str, version: &str, expected: &str) {
+        let soup = Soup {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            ecosystem: Some(ecosystem),
+            ..Default::default()
+        };
+        let component = component(&soup, &[]);
+        assert_eq!(Some(expected.to_owned()), component.purl);
+        assert_eq!(format!("{}@{}", name, version), component.bom_ref);
+        assert_eq!(
+            vec![("requirement", version)],
+            component
+                .properties
+                .iter()
+                .map(|property| (property.name.as_str(), property.value.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unique_references() {
+        let soup = |name: &str| Soup {
+            name: name.to_owned(),
+            version: "4.2".to_owned(),
+            ecosystem: Some(Ecosystem::PyPI),
+            ..Default::default()
+        };
+        let contexts = SoupContexts {
+            contexts: vec![
+                (
+                    "requirements.txt".to_owned(),
+                    BTreeSet::from([soup("Django")]),
+                ),
+                (
+                    "src/requirements.txt".to_owned(),
+                    BTreeSet::from([soup("django")]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let bom = bom(&contexts);
+        assert_eq!(1, bom.components.len());
+        assert_eq!("pkg:pypi/django@4.2", bom.components[0].bom_ref);
+        assert_eq!(
+            vec!["requirements.txt", "src/requirements.txt"],
+            bom.components[0]
+                .evidence
+                .occurrences
+                .iter()
+                .map(|occurrence| occurrence.location.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn json() {
+        let result = to_json(&create_contexts());
+        assert_eq!(true, result.is_ok());
+        let bom: Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!("CycloneDX", bom["bomFormat"]);
+        assert_eq!("1.5", bom["specVersion"]);
+        assert_eq!(
+            json!([
+                {
+                    "type": "container",
+                    "bom-ref": "pkg:docker/postgres@14.4",
+                    "name": "postgres",
+                    "version": "14.4",
+                    "purl": "pkg:docker/postgres@14.4",
+                    "properties": [{"name": "rationale", "value": "Store data"}],
+                    "evidence": {
+                        "occurrences": [
+                            {"location": "src/Dockerfile"},
+                            {"location": "test/Dockerfile"}
+                        ]
+                    }
+                },
+                {
+                    "type": "library",
+                    "bom-ref": "pkg:deb/curl@7.81.0",
+                    "name": "curl",
+                    "version": "7.81.0",
+                    "purl": "pkg:deb/curl@7.81.0",
+                    "evidence": {
+                        "occurrences": [{"location": "src/Dockerfile"}]
+                    }
+                }
+            ]),
+            bom["components"]
+        );
+    }
+
+    #[test]
+    fn xml() {
+        let result = to_xml(&create_contexts());
+        assert_eq!(true, result.is_ok());
+        let xml = result.unwrap();
+        assert_eq!(
+            true,
+            xml.starts_with(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<bom xmlns="http://cyclonedx.org/schema/bom/1.5" version="1">"#
+            )
+        );
+        assert_eq!(
+            true,
+            xml.contains(
+                r#"    <component type="container" bom-ref="pkg:docker/postgres@14.4">
+      <name>postgres</name>
+      <version>14.4</version>
+      <purl>pkg:docker/postgres@14.4</purl>
+      <properties>
+        <property name="rationale">Store data</property>
+      </properties>
+      <evidence>
+        <occurrences>
+          <occurrence>
+            <location>src/Dockerfile</location>
+          </occurrence>
+          <occurrence>
+            <location>test/Dockerfile</location>
+          </occurrence>
+        </occurrences>
+      </evidence>
+    </component>"#
+            )
+        );
+    }
+}
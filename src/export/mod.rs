@@ -0,0 +1,81 @@
+use crate::soup::model::{Ecosystem, Soup, SoupContexts, SouperIoError};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub mod cyclonedx;
+pub mod purl;
+pub mod spdx;
+
+pub fn write_to_file(file_path: &PathBuf, content: &str) -> Result<(), SouperIoError> {
+    match fs::write(file_path, content) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(SouperIoError {
+            message: format!("Not able to write file: {} ({})", file_path.display(), e),
+        }),
+    }
+}
+
+const UNKNOWN_VERSION: &str = "unknown";
+
+/// Characters of version requirements, which no single version contains
+const REQUIREMENT_CHARS: [char; 12] = ['^', '~', '<', '>', '=', '!', '*', '|', ',', '[', '(', ' '];
+
+/// Version of a SOUP if it names a single release, rather than a requirement such as `^1.0.0`,
+/// `>=0.30`, `~> 5.0` or `1.x`, as reported for dependencies that no lockfile resolves
+pub fn exact_version(soup: &Soup) -> Option<&str> {
+    let version = soup.version.as_str();
+    if version.is_empty() || version == UNKNOWN_VERSION {
+        return None;
+    }
+    match soup.ecosystem {
+        // Versions in Cargo.toml are caret requirements unless pinned with `=`, so only the ones
+        // resolved in Cargo.lock, which records the requirement of direct dependencies and the
+        // path to transitive ones, name a single release
+        Some(Ecosystem::Cargo)
+            if soup.attributes.contains_key("requirement")
+                || soup.attributes.contains_key("dependency_path") =>
+        {
+            Some(version)
+        }
+        Some(Ecosystem::Cargo) => version
+            .strip_prefix('=')
+            .map(|version| version.trim())
+            .filter(|version| is_release(version)),
+        // npm matches partial versions such as `1.0` to any patch release
+        Some(Ecosystem::Npm) => {
+            Some(version).filter(|version| is_release(version) && version.split('.').count() >= 3)
+        }
+        _ => Some(version).filter(|version| is_release(version)),
+    }
+}
+
+/// Whether the version of a SOUP is a requirement, rather than a single or unknown release
+pub fn is_requirement(soup: &Soup) -> bool {
+    exact_version(soup).is_none() && !soup.version.is_empty() && soup.version != UNKNOWN_VERSION
+}
+
+fn is_release(version: &str) -> bool {
+    !version.contains(REQUIREMENT_CHARS)
+        && !version
+            .split('.')
+            .any(|segment| segment.eq_ignore_ascii_case("x"))
+}
+
+type SoupKey<'a> = (Option<Ecosystem>, &'a String, &'a String);
+
+/// SOUPs from all contexts, de-duplicated by ecosystem, name and version, together with the
+/// paths of the contexts that they were found in.
+pub fn unique_soups(contexts: &SoupContexts) -> Vec<(&Soup, Vec<&String>)> {
+    let mut soups: BTreeMap<SoupKey, (&Soup, Vec<&String>)> = BTreeMap::new();
+    for (path, context_soups) in contexts.contexts() {
+        for soup in context_soups {
+            soups
+                .entry((soup.ecosystem, &soup.name, &soup.version))
+                .or_insert_with(|| (soup, Vec::new()))
+                .1
+                .push(path);
+        }
+    }
+    soups.into_values().collect()
+}
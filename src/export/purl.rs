@@ -0,0 +1,291 @@
+use crate::export::exact_version;
+use crate::soup::model::{Ecosystem, Soup};
+
+/// Creates a package URL (https://github.com/package-url/purl-spec) for the given SOUP,
+/// provided that it is known which ecosystem the SOUP originates from.
+pub fn purl(soup: &Soup) -> Option<String> {
+    // Packages installed from a git repository or URL are not found in the registry of their
+    // ecosystem. Composer is the exception, as composer.lock records the repository of every
+    // package, including the ones from Packagist.
+    if soup.ecosystem != Some(Ecosystem::Composer) && soup.attributes.contains_key("source") {
+        return None;
+    }
+    let (purl_type, namespace, name, qualifiers) = match soup.ecosystem? {
+        Ecosystem::Npm => match soup.name.strip_prefix('@') {
+            Some(scoped_name) => match scoped_name.split_once('/') {
+                Some((scope, name)) => ("npm", Some(format!("@{}", scope)), name.to_owned(), None),
+                None => ("npm", None, soup.name.to_owned(), None),
+            },
+            None => ("npm", None, soup.name.to_owned(), None),
+        },
+        Ecosystem::Cargo => ("cargo", None, soup.name.to_owned(), None),
+        Ecosystem::NuGet => ("nuget", None, soup.name.to_owned(), None),
+        Ecosystem::Apt => ("deb", None, soup.name.to_owned(), None),
+        Ecosystem::Apk => ("apk", None, soup.name.to_owned(), None),
+        Ecosystem::Rpm => ("rpm", None, soup.name.to_owned(), None),
+        Ecosystem::Gem => ("gem", None, soup.name.to_owned(), None),
+        Ecosystem::Conan => ("conan", None, soup.name.to_owned(), None),
+        Ecosystem::Pub => ("pub", None, soup.name.to_owned(), None),
+        Ecosystem::Hex => ("hex", None, soup.name.to_owned(), None),
+        // Packages are named `vendor/package`, which are case insensitive
+        Ecosystem::Composer => match soup.name.to_lowercase().split_once('/') {
+            Some((vendor, name)) => ("composer", Some(vendor.to_owned()), name.to_owned(), None),
+            None => ("composer", None, soup.name.to_lowercase(), None),
+        },
+        // There's no package URL type for Helm charts, Terraform providers and modules or vcpkg
+        // ports
+        Ecosystem::Helm | Ecosystem::Terraform | Ecosystem::Vcpkg => return None,
+        // PyPI names are case insensitive and treat underscores as dashes
+        Ecosystem::PyPI => (
+            "pypi",
+            None,
+            soup.name.to_lowercase().replace('_', "-"),
+            None,
+        ),
+        // Module paths are split into namespace and name at the last slash
+        Ecosystem::Go => match soup.name.rsplit_once('/') {
+            Some((namespace, name)) => {
+                ("golang", Some(namespace.to_owned()), name.to_owned(), None)
+            }
+            None => ("golang", None, soup.name.to_owned(), None),
+        },
+        Ecosystem::Maven => match soup.name.split_once(':') {
+            Some((group_id, artifact_id)) => (
+                "maven",
+                Some(group_id.to_owned()),
+                artifact_id.to_owned(),
+                None,
+            ),
+            None => ("maven", None, soup.name.to_owned(), None),
+        },
+        // Actions and reusable workflows are identified by their repository, e.g.
+        // `owner/repo/.github/workflows/build.yml` by `owner/repo`
+        Ecosystem::GitHubActions => {
+            let mut segments = soup.name.splitn(3, '/');
+            match (segments.next(), segments.next()) {
+                (Some(owner), Some(repository)) => (
+                    "github",
+                    Some(owner.to_owned()),
+                    repository.to_owned(),
+                    None,
+                ),
+                _ => ("github", None, soup.name.to_owned(), None),
+            }
+        }
+        Ecosystem::Docker => {
+            let (registry, path) = split_registry(&soup.name);
+            let (namespace, name) = match path.rsplit_once('/') {
+                Some((namespace, name)) => (Some(namespace.to_owned()), name.to_owned()),
+                None => (None, path.to_owned()),
+            };
+            let qualifiers = registry.map(|registry| format!("repository_url={}", registry));
+            ("docker", namespace, name, qualifiers)
+        }
+    };
+
+    let mut purl = format!("pkg:{}/", purl_type);
+    if let Some(namespace) = namespace {
+        let segments = namespace.split('/').map(encode).collect::<Vec<String>>();
+        purl.push_str(&segments.join("/"));
+        purl.push('/');
+    }
+    purl.push_str(&encode(&name));
+    // Requirements name no version of the package
+    if let Some(version) = exact_version(soup) {
+        purl.push('@');
+        purl.push_str(&encode(version));
+    }
+    if let Some(qualifiers) = qualifiers {
+        purl.push('?');
+        purl.push_str(&qualifiers);
+    }
+    Some(purl)
+}
+
+fn split_registry(image: &str) -> (Option<&str>, &str) {
+    match image.split_once('/') {
+        Some((first, rest))
+            if first.contains('.') || first.contains(':') || first == "localhost" =>
+        {
+            (Some(first), rest)
+        }
+        _ => (None, image),
+    }
+}
+
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' | b':' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Map, Value};
+    use test_case::test_case;
+
+    #[test_case(Ecosystem::Npm, "some-lib", "1.0.0", "pkg:npm/some-lib@1.0.0")]
+    #[test_case(
+        Ecosystem::Npm,
+        "@angular/core",
+        "16.0.0",
+        "pkg:npm/%40angular/core@16.0.0"
+    )]
+    #[test_case(Ecosystem::Cargo, "serde", "1.0.137", "pkg:cargo/serde")]
+    #[test_case(Ecosystem::Cargo, "winapi", "0.3", "pkg:cargo/winapi")]
+    #[test_case(Ecosystem::Cargo, "serde", "=1.0.137", "pkg:cargo/serde@1.0.137")]
+    #[test_case(
+        Ecosystem::NuGet,
+        "Azure.Messaging.ServiceBus",
+        "7.2.1",
+        "pkg:nuget/Azure.Messaging.ServiceBus@7.2.1"
+    )]
+    #[test_case(Ecosystem::Apt, "curl", "unknown", "pkg:deb/curl")]
+    #[test_case(Ecosystem::Npm, "some-lib", "^1.0.0", "pkg:npm/some-lib")]
+    #[test_case(Ecosystem::PyPI, "uvicorn", ">=0.30", "pkg:pypi/uvicorn")]
+    #[test_case(Ecosystem::Apk, "musl", "~1.2", "pkg:apk/musl")]
+    #[test_case(Ecosystem::Npm, "other-lib", "1.x", "pkg:npm/other-lib")]
+    #[test_case(Ecosystem::Npm, "other-lib", "1.0", "pkg:npm/other-lib")]
+    #[test_case(Ecosystem::Apk, "curl", "8.5.0-r0", "pkg:apk/curl@8.5.0-r0")]
+    #[test_case(Ecosystem::Rpm, "httpd", "2.4.57", "pkg:rpm/httpd@2.4.57")]
+    #[test_case(Ecosystem::Gem, "rails", "7.1.3", "pkg:gem/rails@7.1.3")]
+    #[test_case(Ecosystem::Conan, "zlib", "1.3.1", "pkg:conan/zlib@1.3.1")]
+    #[test_case(
+        Ecosystem::Composer,
+        "Monolog/Monolog",
+        "3.6.0",
+        "pkg:composer/monolog/monolog@3.6.0"
+    )]
+    #[test_case(Ecosystem::Pub, "http", "1.2.1", "pkg:pub/http@1.2.1")]
+    #[test_case(Ecosystem::Hex, "phoenix", "1.7.14", "pkg:hex/phoenix@1.7.14")]
+    #[test_case(
+        Ecosystem::PyPI,
+        "Typing_Extensions",
+        "4.12.2",
+        "pkg:pypi/typing-extensions@4.12.2"
+    )]
+    #[test_case(
+        Ecosystem::Go,
+        "github.com/google/uuid",
+        "v1.6.0",
+        "pkg:golang/github.com/google/uuid@v1.6.0"
+    )]
+    #[test_case(
+        Ecosystem::Maven,
+        "org.slf4j:slf4j-api",
+        "2.0.13",
+        "pkg:maven/org.slf4j/slf4j-api@2.0.13"
+    )]
+    #[test_case(
+        Ecosystem::GitHubActions,
+        "actions/checkout",
+        "v4",
+        "pkg:github/actions/checkout@v4"
+    )]
+    #[test_case(
+        Ecosystem::GitHubActions,
+        "owner/pipelines/.github/workflows/release.yml",
+        "v1.2.0",
+        "pkg:github/owner/pipelines@v1.2.0"
+    )]
+    #[test_case(Ecosystem::Docker, "postgres", "14.4", "pkg:docker/postgres@14.4")]
+    #[test_case(
+        Ecosystem::Docker,
+        "fedora/httpd",
+        "v1.6.2",
+        "pkg:docker/fedora/httpd@v1.6.2"
+    )]
+    #[test_case(
+        Ecosystem::Docker,
+        "mcr.microsoft.com/dotnet/sdk",
+        "6.0",
+        "pkg:docker/dotnet/sdk@6.0?repository_url=mcr.microsoft.com"
+    )]
+    fn purl_from_ecosystem(ecosystem: Ecosystem, name: &str, version: &str, expected: &str) {
+        let soup = Soup {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            ecosystem: Some(ecosystem),
+            ..Default::default()
+        };
+        assert_eq!(Some(expected.to_owned()), purl(&soup));
+    }
+
+    #[test_case("requirement", "1.0" ; "direct")]
+    #[test_case("dependency_path", "[\"serde\"]" ; "transitive")]
+    fn locked_crate(key: &str, value: &str) {
+        let soup = Soup {
+            name: "serde".to_owned(),
+            version: "1.0.137".to_owned(),
+            ecosystem: Some(Ecosystem::Cargo),
+            attributes: Map::from_iter([(key.to_owned(), Value::String(value.to_owned()))]),
+            ..Default::default()
+        };
+        assert_eq!(Some("pkg:cargo/serde@1.0.137".to_owned()), purl(&soup));
+    }
+
+    #[test_case(Ecosystem::Npm, "gh", "v1.0.0", "github:owner/gh")]
+    #[test_case(Ecosystem::Cargo, "git", "v2", "https://github.com/owner/git")]
+    #[test_case(
+        Ecosystem::PyPI,
+        "tool",
+        "1.0.0",
+        "https://example.org/tool-1.0.0.tar.gz"
+    )]
+    fn git_or_url_source(ecosystem: Ecosystem, name: &str, version: &str, source: &str) {
+        let soup = Soup {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            ecosystem: Some(ecosystem),
+            attributes: Map::from_iter([("source".to_owned(), Value::String(source.to_owned()))]),
+            ..Default::default()
+        };
+        assert_eq!(None, purl(&soup));
+    }
+
+    #[test]
+    fn composer_repository() {
+        let soup = Soup {
+            name: "monolog/monolog".to_owned(),
+            version: "3.6.0".to_owned(),
+            ecosystem: Some(Ecosystem::Composer),
+            attributes: Map::from_iter([(
+                "source".to_owned(),
+                Value::String("https://github.com/Seldaek/monolog.git".to_owned()),
+            )]),
+            ..Default::default()
+        };
+        assert_eq!(
+            Some("pkg:composer/monolog/monolog@3.6.0".to_owned()),
+            purl(&soup)
+        );
+    }
+
+    #[test]
+    fn unknown_ecosystem() {
+        let soup = Soup {
+            name: "some-lib".to_owned(),
+            version: "1.0.0".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(None, purl(&soup));
+    }
+
+    #[test]
+    fn helm_chart() {
+        let soup = Soup {
+            name: "postgresql".to_owned(),
+            version: "12.5.6".to_owned(),
+            ecosystem: Some(Ecosystem::Helm),
+            ..Default::default()
+        };
+        assert_eq!(None, purl(&soup));
+    }
+}
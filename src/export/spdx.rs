@@ -0,0 +1,457 @@
+use crate::export::{purl::purl, unique_soups};
+use crate::soup::model::{Soup, SoupContexts, SouperIoError};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+const SPDX_VERSION: &str = "SPDX-2.3";
+const DATA_LICENSE: &str = "CC0-1.0";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+const REPOSITORY_ID: &str = "SPDXRef-Repository";
+const NO_ASSERTION: &str = "NOASSERTION";
+const LICENSE_KEYS: [&str; 1] = ["license"];
+const SUPPLIER_KEYS: [&str; 2] = ["supplier", "manufacturer"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<Package>,
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version_info: Option<String>,
+    supplier: String,
+    download_location: &'static str,
+    files_analyzed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_info: Option<String>,
+    license_concluded: &'static str,
+    license_declared: String,
+    copyright_text: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Annotation {
+    annotation_date: String,
+    annotation_type: &'static str,
+    annotator: String,
+    comment: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+pub fn to_json(
+    contexts: &SoupContexts,
+    name: &str,
+    created: &str,
+) -> Result<String, SouperIoError> {
+    match serde_json::to_string_pretty(&document(contexts, name, created)) {
+        Ok(json) => Ok(json),
+        Err(e) => Err(SouperIoError {
+            message: format!("Not able to serialize SPDX document to json: {}", e),
+        }),
+    }
+}
+
+pub fn to_tag_value(
+    contexts: &SoupContexts,
+    name: &str,
+    created: &str,
+) -> Result<String, SouperIoError> {
+    match write_tag_value(&document(contexts, name, created)) {
+        Ok(tag_value) => Ok(tag_value),
+        Err(e) => Err(SouperIoError {
+            message: format!("Not able to serialize SPDX document to tag-value: {}", e),
+        }),
+    }
+}
+
+fn document(contexts: &SoupContexts, name: &str, created: &str) -> Document {
+    let creator = format!(
+        "Tool: {}-{}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let mut packages = vec![Package {
+        name: name.to_owned(),
+        spdx_id: REPOSITORY_ID.to_owned(),
+        version_info: None,
+        supplier: NO_ASSERTION.to_owned(),
+        download_location: NO_ASSERTION,
+        files_analyzed: false,
+        source_info: None,
+        license_concluded: NO_ASSERTION,
+        license_declared: NO_ASSERTION.to_owned(),
+        copyright_text: NO_ASSERTION,
+        external_refs: Vec::new(),
+        annotations: Vec::new(),
+    }];
+    let mut relationships = vec![Relationship {
+        spdx_element_id: DOCUMENT_ID.to_owned(),
+        relationship_type: "DESCRIBES",
+        related_spdx_element: REPOSITORY_ID.to_owned(),
+    }];
+    for (index, (soup, paths)) in unique_soups(contexts).into_iter().enumerate() {
+        let package = package(soup, &paths, index + 1, &creator, created);
+        relationships.push(Relationship {
+            spdx_element_id: REPOSITORY_ID.to_owned(),
+            relationship_type: "DEPENDS_ON",
+            related_spdx_element: package.spdx_id.to_owned(),
+        });
+        packages.push(package);
+    }
+
+    Document {
+        spdx_version: SPDX_VERSION,
+        data_license: DATA_LICENSE,
+        spdx_id: DOCUMENT_ID,
+        name: name.to_owned(),
+        document_namespace: format!(
+            "{}/spdxdocs/{}-{}",
+            env!("CARGO_PKG_HOMEPAGE"),
+            id_string(name),
+            created.replace([':', '-'], "")
+        ),
+        creation_info: CreationInfo {
+            created: created.to_owned(),
+            creators: vec![creator],
+        },
+        packages,
+        relationships,
+    }
+}
+
+fn package(soup: &Soup, paths: &[&String], index: usize, creator: &str, created: &str) -> Package {
+    let mut license = None;
+    let mut supplier = None;
+    let mut annotations = Vec::new();
+    for (key, value) in &soup.meta {
+        let value = match value {
+            Value::String(value) => value.trim().to_owned(),
+            Value::Null => String::new(),
+            value => value.to_string(),
+        };
+        if value.is_empty() {
+            continue;
+        }
+        // Free-form licenses such as "MIT License" are no valid license expression, so they are
+        // kept as annotation instead
+        if LICENSE_KEYS.contains(&key.to_lowercase().as_str())
+            && spdx::Expression::parse(&value).is_ok()
+        {
+            license.get_or_insert(value);
+        } else if SUPPLIER_KEYS.contains(&key.to_lowercase().as_str()) {
+            supplier.get_or_insert(value);
+        } else {
+            annotations.push(Annotation {
+                annotation_date: created.to_owned(),
+                annotation_type: "OTHER",
+                annotator: creator.to_owned(),
+                comment: format!("{}: {}", key, value),
+            });
+        }
+    }
+    let external_refs = purl(soup)
+        .into_iter()
+        .map(|purl| ExternalRef {
+            reference_category: "PACKAGE-MANAGER",
+            reference_type: "purl",
+            reference_locator: purl,
+        })
+        .collect();
+    let paths = paths
+        .iter()
+        .map(|path| path.as_str())
+        .collect::<Vec<&str>>();
+
+    Package {
+        name: soup.name.to_owned(),
+        spdx_id: format!("SPDXRef-Package-{}-{}", index, id_string(&soup.name)),
+        version_info: Some(soup.version.to_owned()),
+        supplier: match supplier {
+            Some(supplier) => format!("Organization: {}", supplier),
+            None => NO_ASSERTION.to_owned(),
+        },
+        download_location: NO_ASSERTION,
+        files_analyzed: false,
+        source_info: Some(format!("Found in: {}", paths.join(", "))),
+        license_concluded: NO_ASSERTION,
+        license_declared: license.unwrap_or_else(|| NO_ASSERTION.to_owned()),
+        copyright_text: NO_ASSERTION,
+        external_refs,
+        annotations,
+    }
+}
+
+fn id_string(value: &str) -> String {
+    value
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                true => c,
+                false => '-',
+            },
+        )
+        .collect()
+}
+
+fn write_tag_value(document: &Document) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "SPDXVersion: {}", document.spdx_version)?;
+    writeln!(out, "DataLicense: {}", document.data_license)?;
+    writeln!(out, "SPDXID: {}", document.spdx_id)?;
+    writeln!(out, "DocumentName: {}", document.name)?;
+    writeln!(out, "DocumentNamespace: {}", document.document_namespace)?;
+    for creator in &document.creation_info.creators {
+        writeln!(out, "Creator: {}", creator)?;
+    }
+    writeln!(out, "Created: {}", document.creation_info.created)?;
+
+    for package in &document.packages {
+        writeln!(out)?;
+        writeln!(out, "PackageName: {}", package.name)?;
+        writeln!(out, "SPDXID: {}", package.spdx_id)?;
+        if let Some(version) = &package.version_info {
+            writeln!(out, "PackageVersion: {}", version)?;
+        }
+        writeln!(out, "PackageSupplier: {}", package.supplier)?;
+        writeln!(
+            out,
+            "PackageDownloadLocation: {}",
+            package.download_location
+        )?;
+        writeln!(out, "FilesAnalyzed: {}", package.files_analyzed)?;
+        if let Some(source_info) = &package.source_info {
+            writeln!(out, "PackageSourceInfo: <text>{}</text>", source_info)?;
+        }
+        writeln!(
+            out,
+            "PackageLicenseConcluded: {}",
+            package.license_concluded
+        )?;
+        writeln!(out, "PackageLicenseDeclared: {}", package.license_declared)?;
+        writeln!(out, "PackageCopyrightText: {}", package.copyright_text)?;
+        for external_ref in &package.external_refs {
+            writeln!(
+                out,
+                "ExternalRef: {} {} {}",
+                external_ref.reference_category,
+                external_ref.reference_type,
+                external_ref.reference_locator
+            )?;
+        }
+        for annotation in &package.annotations {
+            writeln!(out, "Annotator: {}", annotation.annotator)?;
+            writeln!(out, "AnnotationDate: {}", annotation.annotation_date)?;
+            writeln!(out, "AnnotationType: {}", annotation.annotation_type)?;
+            writeln!(out, "SPDXREF: {}", package.spdx_id)?;
+            writeln!(
+                out,
+                "AnnotationComment: <text>{}</text>",
+                annotation.comment
+            )?;
+        }
+    }
+
+    writeln!(out)?;
+    for relationship in &document.relationships {
+        writeln!(
+            out,
+            "Relationship: {} {} {}",
+            relationship.spdx_element_id,
+            relationship.relationship_type,
+            relationship.related_spdx_element
+        )?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup::model::Ecosystem;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+    use test_case::test_case;
+
+    const CREATED: &str = "2022-08-01T12:30:00Z";
+
+    fn create_contexts() -> SoupContexts {
+        SoupContexts {
+            contexts: vec![(
+                "src/package.json".to_owned(),
+                vec![Soup {
+                    name: "@angular/core".to_owned(),
+                    version: "16.0.0".to_owned(),
+                    ecosystem: Some(Ecosystem::Npm),
+                    meta: json!({
+                        "license": "MIT",
+                        "manufacturer": "Google",
+                        "rationale": "Web framework",
+                        "requirements": ""
+                    })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+                    ..Default::default()
+                }]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            )]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn json() {
+        let result = to_json(&create_contexts(), "my-repo", CREATED);
+        assert_eq!(true, result.is_ok());
+        let document: Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!("SPDX-2.3", document["spdxVersion"]);
+        assert_eq!("my-repo", document["name"]);
+        assert_eq!(
+            format!(
+                "{}/spdxdocs/my-repo-20220801T123000Z",
+                env!("CARGO_PKG_HOMEPAGE")
+            ),
+            document["documentNamespace"]
+        );
+        assert_eq!(CREATED, document["creationInfo"]["created"]);
+        assert_eq!(2, document["packages"].as_array().unwrap().len());
+        assert_eq!(
+            json!({
+                "name": "@angular/core",
+                "SPDXID": "SPDXRef-Package-1--angular-core",
+                "versionInfo": "16.0.0",
+                "supplier": "Organization: Google",
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "sourceInfo": "Found in: src/package.json",
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": "MIT",
+                "copyrightText": "NOASSERTION",
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": "pkg:npm/%40angular/core@16.0.0"
+                }],
+                "annotations": [{
+                    "annotationDate": CREATED,
+                    "annotationType": "OTHER",
+                    "annotator": format!("Tool: souper-{}", env!("CARGO_PKG_VERSION")),
+                    "comment": "rationale: Web framework"
+                }]
+            }),
+            document["packages"][1]
+        );
+        assert_eq!(
+            json!([
+                {
+                    "spdxElementId": "SPDXRef-DOCUMENT",
+                    "relationshipType": "DESCRIBES",
+                    "relatedSpdxElement": "SPDXRef-Repository"
+                },
+                {
+                    "spdxElementId": "SPDXRef-Repository",
+                    "relationshipType": "DEPENDS_ON",
+                    "relatedSpdxElement": "SPDXRef-Package-1--angular-core"
+                }
+            ]),
+            document["relationships"]
+        );
+    }
+
+    #[test_case("MIT", "MIT", 0)]
+    #[test_case("Apache-2.0 OR MIT", "Apache-2.0 OR MIT", 0)]
+    #[test_case("MIT License", "NOASSERTION", 1)]
+    #[test_case("Apache 2.0", "NOASSERTION", 1)]
+    fn declared_license(license: &str, declared: &str, annotations: usize) {
+        let soup = Soup {
+            name: "some-lib".to_owned(),
+            version: "1.0.0".to_owned(),
+            meta: json!({ "license": license }).as_object().unwrap().clone(),
+            ..Default::default()
+        };
+        let package = package(&soup, &[], 1, "Tool: souper", CREATED);
+        assert_eq!(declared, package.license_declared);
+        assert_eq!(annotations, package.annotations.len());
+    }
+
+    #[test]
+    fn tag_value() {
+        let result = to_tag_value(&create_contexts(), "my-repo", CREATED);
+        assert_eq!(true, result.is_ok());
+        let tag_value = result.unwrap();
+        assert_eq!(true, tag_value.starts_with("SPDXVersion: SPDX-2.3\n"));
+        assert_eq!(
+            true,
+            tag_value.contains(
+                r#"PackageName: @angular/core
+SPDXID: SPDXRef-Package-1--angular-core
+PackageVersion: 16.0.0
+PackageSupplier: Organization: Google
+PackageDownloadLocation: NOASSERTION
+FilesAnalyzed: false
+PackageSourceInfo: <text>Found in: src/package.json</text>
+PackageLicenseConcluded: NOASSERTION
+PackageLicenseDeclared: MIT
+PackageCopyrightText: NOASSERTION
+ExternalRef: PACKAGE-MANAGER purl pkg:npm/%40angular/core@16.0.0
+"#
+            )
+        );
+        assert_eq!(
+            true,
+            tag_value.contains(
+                "AnnotationType: OTHER\nSPDXREF: SPDXRef-Package-1--angular-core\nAnnotationComment: <text>rationale: Web framework</text>\n"
+            )
+        );
+        assert_eq!(
+            true,
+            tag_value.ends_with(
+                "Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Repository\nRelationship: SPDXRef-Repository DEPENDS_ON SPDXRef-Package-1--angular-core\n"
+            )
+        );
+    }
+}
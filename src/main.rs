@@ -1,8 +1,10 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde_json::{Map, Value, json};
-use std::{env, path, process};
+use std::{env, path, process, time::SystemTime};
 
+mod export;
 mod parse;
+mod report;
 mod scan;
 mod soup;
 mod utils;
@@ -12,11 +14,18 @@ use soup::model::SoupContexts;
 
 /// Scans a given repository for software of unknown provenance (SOUP) and outputs them in a file.
 #[derive(Parser)]
-#[clap(version)]
+#[clap(
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Output file to print report in
-    #[clap(short = 'o', long = "output-file", value_parser)]
-    file: path::PathBuf,
+    #[clap(short = 'o', long = "output-file", value_parser, required = true)]
+    file: Option<path::PathBuf>,
 
     /// Directory to scan
     #[clap(short = 'd', long = "directory", value_parser)]
@@ -29,11 +38,107 @@ struct Cli {
     // Key to add in meta property
     #[clap(short = 'm', long = "meta-key")]
     meta_keys: Vec<String>,
+
+    /// Verify that output file is up to date, without writing to it
+    #[clap(long = "check")]
+    check: bool,
+
+    /// Include transitive dependencies resolved in lock files
+    #[clap(long = "transitive")]
+    transitive: bool,
+
+    /// Build argument to evaluate Dockerfiles with, as KEY=VALUE or KEY to take the value of an
+    /// environment variable
+    #[clap(long = "build-arg", value_parser = parse_build_arg)]
+    build_args: Vec<(String, String)>,
+
+    /// Name of directories that hold vendored third-party libraries, one subdirectory per
+    /// library, replacing the defaults when given
+    #[clap(
+        long = "vendor-dir",
+        default_values_t = ["third_party".to_owned(), "vendor".to_owned(), "external".to_owned()]
+    )]
+    vendor_dirs: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Exports SOUPs in a file created by souper to a software bill of materials
+    Export(ExportArgs),
+    /// Renders SOUPs in a file created by souper as a human readable report
+    Report(ReportArgs),
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// File created by souper to read SOUPs from
+    #[clap(short = 'i', long = "input-file", value_parser)]
+    input_file: path::PathBuf,
+
+    /// Output file to write the exported document to
+    #[clap(short = 'o', long = "output-file", value_parser)]
+    output_file: path::PathBuf,
+
+    /// Format of the exported document
+    #[clap(short = 'f', long = "format", value_enum)]
+    format: ExportFormat,
+
+    /// Name of the scanned repository, defaults to the directory of the input file
+    #[clap(short = 'n', long = "name")]
+    name: Option<String>,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// File created by souper to read SOUPs from
+    #[clap(short = 'i', long = "input-file", value_parser)]
+    input_file: path::PathBuf,
+
+    /// Output file to write the report to
+    #[clap(short = 'o', long = "output-file", value_parser)]
+    output_file: path::PathBuf,
+
+    /// Format of the report
+    #[clap(short = 'f', long = "format", value_enum, default_value = "markdown")]
+    format: ReportFormat,
+
+    /// Meta key to include as a column, defaults to all meta keys found
+    #[clap(short = 'c', long = "column")]
+    columns: Vec<String>,
+
+    /// List each SOUP once, instead of one table per file that SOUPs were found in
+    #[clap(long = "deduplicate")]
+    deduplicate: bool,
+
+    /// Scope of SOUPs to leave out of the report, e.g. dev
+    #[clap(long = "exclude-scope")]
+    excluded_scopes: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(ValueEnum, Clone)]
+enum ExportFormat {
+    CyclonedxJson,
+    CyclonedxXml,
+    SpdxJson,
+    SpdxTagValue,
 }
 
 fn main() {
     let args = Cli::parse();
+    match args.command {
+        Some(Command::Export(export_args)) => export(export_args),
+        Some(Command::Report(report_args)) => report(report_args),
+        None => scan(args),
+    }
+}
 
+fn scan(args: Cli) {
     let output_file = parse_output_file(args.file);
     let mut current_contexts = match output_file.is_file() {
         true => match SoupContexts::read_from_file(&output_file) {
@@ -57,7 +162,12 @@ fn main() {
         .into_iter()
         .map(|meta_key| (meta_key, json!("")))
         .collect::<Map<String, Value>>();
-    let scanned_contexts = match dir_scan::scan(&root_dir, &exclude_dirs, default_meta) {
+    let options = dir_scan::ScanOptions {
+        include_transitive: args.transitive,
+        build_args: args.build_args.into_iter().collect(),
+        vendor_dirs: args.vendor_dirs,
+    };
+    let scanned_contexts = match dir_scan::scan(&root_dir, &exclude_dirs, default_meta, &options) {
         Ok(result) => result,
         Err(e) => {
             eprintln!(
@@ -69,6 +179,11 @@ fn main() {
         }
     };
 
+    if args.check {
+        check_output_file(&output_file, &current_contexts, scanned_contexts);
+        return;
+    }
+
     current_contexts.apply(scanned_contexts);
     if let Err(e) = current_contexts.write_to_file(&output_file) {
         eprintln!("Error while writing to file: {}", e);
@@ -94,10 +209,140 @@ fn parse_root_dir(dir: Option<path::PathBuf>) -> path::PathBuf {
     root_dir
 }
 
-fn parse_output_file(file_path: path::PathBuf) -> path::PathBuf {
+fn parse_build_arg(build_arg: &str) -> Result<(String, String), String> {
+    match build_arg.split_once('=') {
+        Some((key, value)) => Ok((key.to_owned(), value.to_owned())),
+        None => match env::var(build_arg) {
+            Ok(value) => Ok((build_arg.to_owned(), value)),
+            Err(_) => Err(format!("no value given for {}", build_arg)),
+        },
+    }
+}
+
+fn export(args: ExportArgs) {
+    let contexts = parse_input_file(&args.input_file);
+    let name = match args.name {
+        Some(name) => name,
+        None => repository_name(&args.input_file),
+    };
+    let created = utils::utc_timestamp(SystemTime::now());
+    let document = match args.format {
+        ExportFormat::CyclonedxJson => export::cyclonedx::to_json(&contexts),
+        ExportFormat::CyclonedxXml => export::cyclonedx::to_xml(&contexts),
+        ExportFormat::SpdxJson => export::spdx::to_json(&contexts, &name, &created),
+        ExportFormat::SpdxTagValue => export::spdx::to_tag_value(&contexts, &name, &created),
+    };
+    let document = match document {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("Error while exporting: {}", e);
+            process::exit(1);
+        }
+    };
+    let output_file = parse_output_file(Some(args.output_file));
+    if let Err(e) = export::write_to_file(&output_file, &document) {
+        eprintln!("Error while writing to file: {}", e);
+        process::exit(1);
+    }
+}
+
+fn report(args: ReportArgs) {
+    let contexts = parse_input_file(&args.input_file);
+    let options = report::ReportOptions {
+        columns: match args.columns.is_empty() {
+            true => None,
+            false => Some(args.columns),
+        },
+        deduplicate: args.deduplicate,
+        excluded_scopes: args.excluded_scopes,
+    };
+    let report = report::Report::new(&contexts, &options);
+    let document = match args.format {
+        ReportFormat::Markdown => report::markdown::render(&report),
+        ReportFormat::Html => report::html::render(&report),
+    };
+    let document = match document {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("Error while rendering report: {}", e);
+            process::exit(1);
+        }
+    };
+    let output_file = parse_output_file(Some(args.output_file));
+    if let Err(e) = export::write_to_file(&output_file, &document) {
+        eprintln!("Error while writing to file: {}", e);
+        process::exit(1);
+    }
+}
+
+fn parse_input_file(input_file: &path::PathBuf) -> SoupContexts {
+    match SoupContexts::read_from_file(input_file) {
+        Ok(contexts) => contexts,
+        Err(e) => {
+            eprintln!(
+                "Not able to parse input file: {} ({})",
+                input_file.display(),
+                e
+            );
+            process::exit(1);
+        }
+    }
+}
+
+fn repository_name(input_file: &path::Path) -> String {
+    let directory = match input_file.canonicalize() {
+        Ok(input_file) => input_file.parent().map(|parent| parent.to_path_buf()),
+        Err(_e) => None,
+    };
+    match directory
+        .as_ref()
+        .and_then(|directory| directory.file_name())
+        .and_then(|file_name| file_name.to_str())
+    {
+        Some(name) => name.to_owned(),
+        None => "repository".to_owned(),
+    }
+}
+
+fn parse_output_file(file_path: Option<path::PathBuf>) -> path::PathBuf {
+    let file_path = match file_path {
+        Some(file_path) => file_path,
+        None => {
+            eprintln!("Missing output file");
+            process::exit(1);
+        }
+    };
     if file_path.exists() && !file_path.is_file() {
         eprintln!("Invalid output file: {}", file_path.display());
         process::exit(1);
     }
     file_path
 }
+
+fn check_output_file(
+    output_file: &path::PathBuf,
+    current_contexts: &SoupContexts,
+    scanned_contexts: SoupContexts,
+) {
+    let mut updated_contexts = current_contexts.clone();
+    updated_contexts.apply(scanned_contexts);
+    let is_up_to_date = match updated_contexts.is_written_to_file(output_file) {
+        Ok(is_up_to_date) => is_up_to_date,
+        Err(e) => {
+            eprintln!("Error while reading file: {}", e);
+            process::exit(1);
+        }
+    };
+    if is_up_to_date {
+        println!("{} is up to date", output_file.display());
+        return;
+    }
+
+    let diff = current_contexts.diff(&updated_contexts);
+    match diff.is_empty() {
+        true => println!("SOUP details, meta properties or formatting differ from scan result"),
+        false => print!("{}", diff),
+    }
+    eprintln!("{} is out of date", output_file.display());
+    process::exit(1);
+}
@@ -0,0 +1,97 @@
+use super::{SoupParse, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses `apk add` commands of Alpine based images
+pub struct Apk {}
+
+static OPTIONS_WITH_VALUE: [&str; 11] = [
+    "-t",
+    "--virtual",
+    "-X",
+    "--repository",
+    "--repositories-file",
+    "-p",
+    "--root",
+    "--arch",
+    "--cache-dir",
+    "--keys-dir",
+    "--cache-max-age",
+];
+lazy_static! {
+    static ref PACKAGE: Regex = Regex::new(
+        r"^(?P<name>[a-zA-Z0-9][a-zA-Z0-9+\._\-]*)(?:@[\w\-]+)?(?:(?P<operator>[=~<>]+)(?P<version>\S+))?$"
+    )
+    .unwrap();
+}
+
+impl SoupParse for Apk {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for command in shell::commands(content) {
+            let words = match shell::subcommand_words(
+                &command,
+                |program| program == "apk",
+                &["add"],
+                &OPTIONS_WITH_VALUE,
+            ) {
+                Some(words) => words,
+                None => continue,
+            };
+            for package in shell::operands(words, &OPTIONS_WITH_VALUE) {
+                if let Some(captures) = PACKAGE.captures(package) {
+                    // Exact versions are given with `=`, whereas e.g. `~` and `>=` constrain them
+                    let version = match (captures.name("operator"), captures.name("version")) {
+                        (Some(operator), Some(version)) if operator.as_str() == "=" => {
+                            version.as_str().to_owned()
+                        }
+                        (Some(operator), Some(version)) => {
+                            format!("{}{}", operator.as_str(), version.as_str())
+                        }
+                        _ => "unknown".to_owned(),
+                    };
+                    result.insert(Soup {
+                        name: captures["name"].to_owned(),
+                        version,
+                        ecosystem: Some(Ecosystem::Apk),
+                        meta: default_meta.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+    use test_case::test_case;
+
+    #[test_case("apk add curl=8.5.0-r0", &[("curl", "8.5.0-r0")])]
+    #[test_case("RUN apk add --no-cache curl=8.5.0-r0 git", &[("curl", "8.5.0-r0"), ("git", "unknown")])]
+    #[test_case("apk add --virtual .build-deps gcc~13.2", &[("gcc", "~13.2")])]
+    #[test_case("apk -U add -X https://example.org/alpine py3-pip>=23.3", &[("py3-pip", ">=23.3")])]
+    #[test_case("apk add ruby@edge=3.3.0-r1", &[("ruby", "3.3.0-r1")])]
+    #[test_case("apk update && apk add \\\n\tbash=5.2.21-r0", &[("bash", "5.2.21-r0")])]
+    fn packages(input: &str, expected: &[(&str, &str)]) {
+        let result = Apk {}.soups(input, &Map::new()).unwrap();
+        assert_eq!(expected, versions(&result));
+    }
+
+    #[test_case("apk update")]
+    #[test_case("apk del curl")]
+    #[test_case("apt-get install curl")]
+    fn no_add_statement(input: &str) {
+        assert_eq!(0, Apk {}.soups(input, &Map::new()).unwrap().len());
+    }
+}
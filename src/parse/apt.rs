@@ -1,25 +1,27 @@
-use super::SoupParse;
-use crate::soup::model::{Soup, SoupSourceParseError};
+use super::{SoupParse, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
 use lazy_static::lazy_static;
-use regex::{Regex, RegexSet};
+use regex::Regex;
 use serde_json::{Map, Value};
 use std::collections::BTreeSet;
 
 pub struct Apt {}
 
-static PATTERNS: [&str; 2] = [
-    r"apt(?:\-get)? install (?:(?:\-[a-zA-Z] )|(?:\-\-[a-zA-Z\-]+ ))*(?P<name>[a-zA-Z0-9\._\-]+)=(?P<version>[a-zA-Z0-9\.\-_]+)",
-    r"apt(?:\-get)? install (?:(?:\-[a-zA-Z] )|(?:\-\-[a-zA-Z\-]+ ))*(?P<name>[a-zA-Z0-9\._\-]+)",
+static OPTIONS_WITH_VALUE: [&str; 8] = [
+    "-o",
+    "--option",
+    "-c",
+    "--config-file",
+    "-t",
+    "--target-release",
+    "--default-release",
+    "--host-architecture",
 ];
 lazy_static! {
-    static ref PATTERN_SET: RegexSet = RegexSet::new(PATTERNS).unwrap();
-    static ref REGEXES: Vec<Regex> = PATTERN_SET
-        .patterns()
-        .iter()
-        .map(|pat| Regex::new(pat).unwrap())
-        .collect();
-    static ref LINE_CONTINUATION: Regex = Regex::new(r"\\.*\n|\r\n").unwrap();
-    static ref MULTI_SPACE: Regex = Regex::new(r"[ \t]+").unwrap();
+    static ref PACKAGE: Regex = Regex::new(
+        r"^(?P<name>[a-zA-Z0-9][a-zA-Z0-9+\._\-]*(?::[a-z0-9\-]+)?)(?:/[\w\.\-]+)?(?:=(?P<version>\S+))?$"
+    )
+    .unwrap();
 }
 
 impl SoupParse for Apt {
@@ -29,22 +31,18 @@ impl SoupParse for Apt {
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
         let mut result: BTreeSet<Soup> = BTreeSet::new();
-        let content = normalize(content);
-        for line in content.lines() {
-            let matching_patterns = PATTERN_SET
-                .matches(line)
-                .into_iter()
-                .map(|match_id| &REGEXES[match_id])
-                .collect::<Vec<&Regex>>();
-            if let Some(pattern) = matching_patterns.first() {
-                if let Some(captures) = pattern.captures(line) {
+        for command in shell::commands(content) {
+            for package in install_arguments(&command) {
+                if let Some(captures) = PACKAGE.captures(package) {
                     result.insert(Soup {
                         name: named_capture(&captures, "name")?,
                         version: match named_capture(&captures, "version") {
                             Ok(version) => version,
                             Err(_e) => "unknown".to_owned(),
                         },
+                        ecosystem: Some(Ecosystem::Apt),
                         meta: default_meta.clone(),
+                        ..Default::default()
                     });
                 }
             }
@@ -53,10 +51,16 @@ impl SoupParse for Apt {
     }
 }
 
-fn normalize(input: &str) -> String {
-    let result = LINE_CONTINUATION.replace_all(input, " ");
-    let result = MULTI_SPACE.replace_all(&result, " ");
-    result.to_string()
+/// Package arguments of an `apt install` or `apt-get install` command, skipping options.
+fn install_arguments(command: &[String]) -> Vec<&String> {
+    match shell::subcommand_words(command, is_apt, &["install"], &OPTIONS_WITH_VALUE) {
+        Some(words) => shell::operands(words, &OPTIONS_WITH_VALUE),
+        None => Vec::new(),
+    }
+}
+
+fn is_apt(program: &str) -> bool {
+    program == "apt" || program == "apt-get"
 }
 
 fn named_capture(captures: &regex::Captures, name: &str) -> Result<String, SoupSourceParseError> {
@@ -93,7 +97,8 @@ mod tests {
             Soup {
                 name: "curl".to_owned(),
                 version: "7.81.0-1ubuntu1.3".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             },
             soup
         )
@@ -119,7 +124,8 @@ mod tests {
             Soup {
                 name: "curl".to_owned(),
                 version: "unknown".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             },
             soup
         )
@@ -139,9 +145,75 @@ mod tests {
             Soup {
                 name: "curl".to_owned(),
                 version: "7.81.0-1ubuntu1.3".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             },
             soup
         );
     }
+
+    #[test_case(
+        "apt-get install -y curl=7.81.0-1ubuntu1.3 git=1:2.34.1-1ubuntu1.4 ca-certificates"
+    )]
+    #[test_case(
+        "RUN apt-get install -y \\
+\tcurl=7.81.0-1ubuntu1.3 \\
+\tgit=1:2.34.1-1ubuntu1.4 \\
+\tca-certificates"
+    )]
+    #[test_case(
+        "RUN apt-get update && apt-get install -y curl=7.81.0-1ubuntu1.3 git=1:2.34.1-1ubuntu1.4 && apt-get install ca-certificates"
+    )]
+    #[test_case(
+        "RUN apt-get update; apt-get install curl=7.81.0-1ubuntu1.3 git=1:2.34.1-1ubuntu1.4; apt install ca-certificates"
+    )]
+    #[test_case(
+        "apt-get -y -o Dpkg::Options::=--force-confdef install --no-install-recommends curl=7.81.0-1ubuntu1.3 -t jammy-backports git=1:2.34.1-1ubuntu1.4 ca-certificates"
+    )]
+    #[test_case(
+        "RUN apt-get install -y \\
+    # Some comment\n    curl=7.81.0-1ubuntu1.3 \\
+    git=1:2.34.1-1ubuntu1.4 ca-certificates && rm -rf /var/lib/apt/lists/*"
+    )]
+    fn multiple_packages(input: &str) {
+        let result = Apt {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![
+                Soup {
+                    name: "ca-certificates".to_owned(),
+                    version: "unknown".to_owned(),
+                    meta: Map::new(),
+                    ..Default::default()
+                },
+                Soup {
+                    name: "curl".to_owned(),
+                    version: "7.81.0-1ubuntu1.3".to_owned(),
+                    meta: Map::new(),
+                    ..Default::default()
+                },
+                Soup {
+                    name: "git".to_owned(),
+                    version: "1:2.34.1-1ubuntu1.4".to_owned(),
+                    meta: Map::new(),
+                    ..Default::default()
+                },
+            ]
+            .into_iter()
+            .collect::<BTreeSet<Soup>>(),
+            soups
+        );
+    }
+
+    #[test_case("apt-get update")]
+    #[test_case("apt-get remove curl")]
+    #[test_case("apt-get -y autoremove && rm -rf /var/lib/apt/lists/*")]
+    #[test_case("echo \"apt-get install curl\" > script.sh")]
+    fn no_install_statement(input: &str) {
+        let result = Apt {}.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(0, soups.len());
+    }
 }
@@ -1,14 +1,75 @@
-use super::SoupParse;
-use crate::soup::model::{Soup, SoupSourceParseError};
+use super::cargo_lock::{CargoLock, LockedPackage};
+use super::{SoupParse, insert_strongest};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
 use serde::Deserialize;
 use serde_json::{Map, Value};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
-pub struct Cargo {}
+const RUNTIME_SCOPE: &str = "runtime";
+const BUILD_SCOPE: &str = "build";
+const DEV_SCOPE: &str = "dev";
+
+#[derive(Default)]
+pub struct Cargo {
+    /// Content of the root manifest of the workspace that the parsed manifest belongs to
+    pub workspace_manifest: Option<String>,
+    /// Content of the Cargo.lock file next to the manifest or at the root of its workspace
+    pub lockfile: Option<String>,
+    /// Whether to include crates that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
 
 #[derive(Deserialize)]
 struct Content {
-    dependencies: Option<HashMap<String, toml::Value>>,
+    package: Option<Package>,
+    #[serde(flatten)]
+    tables: DependencyTables,
+    target: Option<BTreeMap<String, DependencyTables>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DependencyTables {
+    dependencies: Option<BTreeMap<String, toml::Value>>,
+    #[serde(rename = "build-dependencies", alias = "build_dependencies")]
+    build_dependencies: Option<BTreeMap<String, toml::Value>>,
+    #[serde(rename = "dev-dependencies", alias = "dev_dependencies")]
+    dev_dependencies: Option<BTreeMap<String, toml::Value>>,
+}
+
+impl DependencyTables {
+    fn by_scope(self) -> Vec<(&'static str, BTreeMap<String, toml::Value>)> {
+        [
+            (RUNTIME_SCOPE, self.dependencies),
+            (BUILD_SCOPE, self.build_dependencies),
+            (DEV_SCOPE, self.dev_dependencies),
+        ]
+        .into_iter()
+        .filter_map(|(scope, dependencies)| dependencies.map(|d| (scope, d)))
+        .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct WorkspaceContent {
+    workspace: Option<Workspace>,
+}
+
+#[derive(Deserialize)]
+struct Workspace {
+    dependencies: Option<BTreeMap<String, toml::Value>>,
+}
+
+/// Whether the given manifest is the root manifest of a workspace
+pub fn is_workspace_manifest(content: &str) -> bool {
+    match toml::from_str::<WorkspaceContent>(content) {
+        Ok(content) => content.workspace.is_some(),
+        Err(_e) => false,
+    }
 }
 
 impl SoupParse for Cargo {
@@ -25,39 +86,189 @@ impl SoupParse for Cargo {
                 });
             }
         };
-        match content.dependencies {
-            None => Ok(BTreeSet::new()),
-            Some(dependencies) => dependencies
-                .into_iter()
-                .map(|(dependency, value)| match value {
-                    toml::Value::String(version) => Ok(Soup {
-                        name: dependency,
+        let workspace_dependencies = self.workspace_dependencies()?;
+        let lockfile = match &self.lockfile {
+            Some(lockfile) => Some(CargoLock::parse(lockfile)?),
+            None => None,
+        };
+        let root = content
+            .package
+            .as_ref()
+            .map(|package| package.name.as_str());
+
+        let mut tables = content
+            .tables
+            .by_scope()
+            .into_iter()
+            .map(|(scope, dependencies)| (scope, None, dependencies))
+            .collect::<Vec<_>>();
+        for (target, target_tables) in content.target.unwrap_or_default() {
+            for (scope, dependencies) in target_tables.by_scope() {
+                tables.push((scope, Some(target.to_owned()), dependencies));
+            }
+        }
+
+        let mut soups = BTreeSet::new();
+        let mut direct_packages: Vec<(&LockedPackage, &str)> = Vec::new();
+        for (scope, target, dependencies) in tables {
+            for (dependency, value) in dependencies {
+                let value = match is_workspace_inherited(&value) {
+                    true => match workspace_dependencies.get(&dependency) {
+                        Some(value) => value.to_owned(),
+                        None => {
+                            return Err(SoupSourceParseError {
+                                message: format!(
+                                    "Missing workspace dependency for: {}",
+                                    dependency
+                                ),
+                            });
+                        }
+                    },
+                    false => value,
+                };
+                let requirement = match version(&dependency, &value)? {
+                    Some(requirement) => requirement,
+                    None => continue,
+                };
+                let name = package_name(&dependency, &value);
+                let mut attributes = Map::new();
+                if name != dependency {
+                    attributes.insert("alias".to_owned(), Value::String(dependency.to_owned()));
+                }
+                if let Some(target) = &target {
+                    attributes.insert("target".to_owned(), Value::String(target.to_owned()));
+                }
+                // Cargo.lock refines the source of git dependencies with the exact commit
+                if let Some(git) = value.get("git").and_then(|git| git.as_str()) {
+                    attributes.insert("source".to_owned(), Value::String(git.to_owned()));
+                }
+                let locked_package = lockfile
+                    .as_ref()
+                    .and_then(|lockfile| lockfile.resolve(root, name, &requirement));
+                let version = match locked_package {
+                    Some(package) => {
+                        attributes.insert(
+                            "requirement".to_owned(),
+                            Value::String(requirement.to_owned()),
+                        );
+                        insert_lock_attributes(&mut attributes, package);
+                        direct_packages.push((package, scope));
+                        package.version.to_owned()
+                    }
+                    None => requirement,
+                };
+                // Crates of the same version in several tables, e.g. a target-specific runtime
+                // dependency that is also a dev dependency, are reported in the strongest scope
+                insert_strongest(
+                    &mut soups,
+                    Soup {
+                        name: name.to_owned(),
                         version,
+                        ecosystem: Some(Ecosystem::Cargo),
+                        scope: Some(scope.to_owned()),
+                        attributes,
                         meta: default_meta.clone(),
-                    }),
-                    toml::Value::Table(table) => match table.get("version") {
-                        Some(version) => Ok(Soup {
-                            name: dependency.to_owned(),
-                            version: match version.as_str() {
-                                Some(v) => v.to_owned(),
-                                None => {
-                                    return Err(SoupSourceParseError {
-                                        message: format!("Invalid version for: {}", dependency),
-                                    });
-                                }
-                            },
-                            meta: default_meta.clone(),
-                        }),
-                        None => Err(SoupSourceParseError {
-                            message: format!("Missing version for: {}", dependency),
-                        }),
                     },
-                    _ => Err(SoupSourceParseError {
-                        message: format!("Malformed dependency: {}", dependency),
-                    }),
-                })
-                .collect::<Result<BTreeSet<Soup>, _>>(),
+                );
+            }
+        }
+
+        if let (Some(lockfile), true) = (&lockfile, self.include_transitive) {
+            for transitive in lockfile.transitive(&direct_packages) {
+                let package = transitive.package;
+                let mut attributes = Map::new();
+                insert_lock_attributes(&mut attributes, package);
+                transitive.insert_dependency_path(&mut attributes);
+                soups.insert(Soup {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Cargo),
+                    scope: Some(transitive.scope.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
         }
+        Ok(soups)
+    }
+}
+
+impl Cargo {
+    fn workspace_dependencies(
+        &self,
+    ) -> Result<BTreeMap<String, toml::Value>, SoupSourceParseError> {
+        let workspace_manifest = match &self.workspace_manifest {
+            Some(workspace_manifest) => workspace_manifest,
+            None => return Ok(BTreeMap::new()),
+        };
+        let content: WorkspaceContent = match toml::from_str(workspace_manifest) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid workspace Cargo.toml ({})", e),
+                });
+            }
+        };
+        Ok(content
+            .workspace
+            .and_then(|workspace| workspace.dependencies)
+            .unwrap_or_default())
+    }
+}
+
+fn is_workspace_inherited(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Table(table) => table.get("workspace") == Some(&toml::Value::Boolean(true)),
+        _ => false,
+    }
+}
+
+/// Name of the package on crates.io, which differs from the dependency name when renamed
+fn package_name<'a>(dependency: &'a str, value: &'a toml::Value) -> &'a str {
+    match value.get("package").and_then(|package| package.as_str()) {
+        Some(package) => package,
+        None => dependency,
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    if let Some(checksum) = &package.checksum {
+        attributes.insert("checksum".to_owned(), Value::String(checksum.to_owned()));
+    }
+    // The exact commit of git dependencies is only found in the source
+    if let Some(source) = package.source.as_ref().filter(|s| s.starts_with("git+")) {
+        attributes.insert("source".to_owned(), Value::String(source.to_owned()));
+    }
+}
+
+/// Version of the given dependency, or `None` for path dependencies within the repository
+fn version(dependency: &str, value: &toml::Value) -> Result<Option<String>, SoupSourceParseError> {
+    match value {
+        toml::Value::String(version) => Ok(Some(version.to_owned())),
+        toml::Value::Table(table) if table.contains_key("path") => Ok(None),
+        toml::Value::Table(table) if table.contains_key("git") => {
+            let git_ref = ["rev", "tag", "branch"]
+                .iter()
+                .find_map(|key| table.get(*key).and_then(|value| value.as_str()));
+            match git_ref {
+                Some(git_ref) => Ok(Some(git_ref.to_owned())),
+                None => Ok(Some("unknown".to_owned())),
+            }
+        }
+        toml::Value::Table(table) => match table.get("version") {
+            Some(version) => match version.as_str() {
+                Some(v) => Ok(Some(v.to_owned())),
+                None => Err(SoupSourceParseError {
+                    message: format!("Invalid version for: {}", dependency),
+                }),
+            },
+            None => Err(SoupSourceParseError {
+                message: format!("Missing version for: {}", dependency),
+            }),
+        },
+        _ => Err(SoupSourceParseError {
+            message: format!("Malformed dependency: {}", dependency),
+        }),
     }
 }
 
@@ -79,7 +290,7 @@ serde = { version = "1.0.137", features = ["derive"] }
     "#
     )]
     fn simple_dependency(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(
@@ -87,7 +298,8 @@ serde = { version = "1.0.137", features = ["derive"] }
             soups.contains(&Soup {
                 name: "serde".to_owned(),
                 version: "1.0.137".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             })
         );
     }
@@ -99,7 +311,7 @@ serde = { version = "1.0.137", features = ["derive"] }
 serde_json = { version = "1.0.82", features = ["preserve_order"] }
 quick-xml = "0.23.0"
         "#;
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -108,12 +320,14 @@ quick-xml = "0.23.0"
                 Soup {
                     name: "serde_json".to_owned(),
                     version: "1.0.82".to_owned(),
-                    meta: Map::new()
+                    meta: Map::new(),
+                    ..Default::default()
                 },
                 Soup {
                     name: "quick-xml".to_owned(),
                     version: "0.23.0".to_owned(),
-                    meta: Map::new()
+                    meta: Map::new(),
+                    ..Default::default()
                 }
             ]
             .into_iter()
@@ -125,7 +339,7 @@ quick-xml = "0.23.0"
     #[test_case("[dependencies]")]
     #[test_case("")]
     fn no_dependencies(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
@@ -157,7 +371,320 @@ serde_json = { version = { verzion = "1.0.82" }, features = ["preserve_order"] }
     )]
     #[test_case(r#"dependencies = "serde""#)]
     fn missing_or_invalid_version(input: &str) {
-        let result = Cargo {}.soups(input, &Map::new());
+        let result = Cargo::default().soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
+    #[test_case(
+        r#"
+[dependencies]
+serde = { workspace = true }
+    "#
+    ; "inline table"
+    )]
+    #[test_case(
+        r#"
+[dependencies]
+serde = { workspace = true, features = ["derive"] }
+    "#
+    ; "inline table with features"
+    )]
+    #[test_case(
+        r#"
+[dependencies.serde]
+workspace = true
+    "#
+    ; "table"
+    )]
+    fn workspace_dependency(input: &str) {
+        let workspace_manifest = r#"
+[workspace]
+members = ["some-crate"]
+
+[workspace.dependencies]
+serde = { version = "1.0.137", default-features = false }
+        "#;
+        let cargo = Cargo {
+            workspace_manifest: Some(workspace_manifest.to_owned()),
+            ..Default::default()
+        };
+        let result = cargo.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(
+            vec![Soup {
+                name: "serde".to_owned(),
+                version: "1.0.137".to_owned(),
+                meta: Map::new(),
+                ..Default::default()
+            }]
+            .into_iter()
+            .collect::<BTreeSet<_>>(),
+            soups
+        );
+    }
+
+    #[test_case(None)]
+    #[test_case(Some("[workspace]"))]
+    fn missing_workspace_dependency(workspace_manifest: Option<&str>) {
+        let input = r#"
+[dependencies]
+serde = { workspace = true }
+        "#;
+        let cargo = Cargo {
+            workspace_manifest: workspace_manifest.map(|manifest| manifest.to_owned()),
+            ..Default::default()
+        };
+        let result = cargo.soups(input, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
+    #[test]
+    fn path_dependency() {
+        let input = r#"
+[dependencies]
+some-crate = { path = "../some-crate" }
+another-crate = { path = "../another-crate", version = "0.1.0" }
+serde = "1.0.137"
+        "#;
+        let result = Cargo::default().soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        assert_eq!("serde", soups.into_iter().next().unwrap().name);
+    }
+
+    #[test_case(
+        r#"{ git = "https://github.com/serde-rs/serde", rev = "a1b2c3d" }"#,
+        "a1b2c3d"
+    )]
+    #[test_case(
+        r#"{ git = "https://github.com/serde-rs/serde", tag = "v1.0.137" }"#,
+        "v1.0.137"
+    )]
+    #[test_case(
+        r#"{ git = "https://github.com/serde-rs/serde", branch = "master" }"#,
+        "master"
+    )]
+    #[test_case(r#"{ git = "https://github.com/serde-rs/serde" }"#, "unknown")]
+    fn git_dependency(dependency: &str, expected_version: &str) {
+        let input = format!("[dependencies]\nserde = {}", dependency);
+        let result = Cargo::default().soups(&input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("serde", soup.name);
+        assert_eq!(expected_version, soup.version);
+        assert_eq!(
+            Some(&Value::String(
+                "https://github.com/serde-rs/serde".to_owned()
+            )),
+            soup.attributes.get("source")
+        );
+    }
+
+    #[test_case("[workspace]\nmembers = []", true)]
+    #[test_case("[package]\nname = \"some-crate\"", false)]
+    #[test_case("[workspace", false)]
+    fn workspace_manifest(input: &str, expected: bool) {
+        assert_eq!(expected, is_workspace_manifest(input));
+    }
+
+    #[test]
+    fn dependency_scopes() {
+        let input = r#"
+[dependencies]
+serde = "1.0.137"
+
+[dev-dependencies]
+test-case = "3.3.1"
+serde = "1.0.137"
+
+[build-dependencies]
+cc = "1.0.79"
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3.9"
+
+[target.'cfg(unix)'.dev-dependencies]
+nix = "0.26.2"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2.155"
+
+[dev-dependencies.libc]
+version = "0.2.155"
+        "#;
+        let result = Cargo::default().soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        let scopes = soups
+            .iter()
+            .map(|soup| {
+                (
+                    soup.name.as_str(),
+                    soup.scope.as_deref().unwrap(),
+                    soup.attributes.get("target").and_then(|t| t.as_str()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("cc", "build", None),
+                ("libc", "runtime", Some("cfg(unix)")),
+                ("nix", "dev", Some("cfg(unix)")),
+                ("serde", "runtime", None),
+                ("test-case", "dev", None),
+                ("winapi", "runtime", Some("cfg(windows)")),
+            ],
+            scopes
+        );
+    }
+
+    const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "my-crate"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "test-case",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.137"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "61ea8d54c77f8315140a05f4c7237403bf38b72704d031543aa1d16abbf517d1"
+dependencies = [
+ "serde_derive",
+]
+
+[[package]]
+name = "serde_derive"
+version = "1.0.137"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "1f26faba0c3959972377d3b2d306ee9f71faee9714294e41bb777f83f88578be"
+
+[[package]]
+name = "test-case"
+version = "3.3.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "eb2550dd13afcd286853192af8601920d959b14c401fcece38071d53bf0768a8"
+dependencies = [
+ "test-case-macros",
+]
+
+[[package]]
+name = "test-case-macros"
+version = "3.3.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "adcb7fd841cd518e279be3d5a3eb0636409487998a4aff22f3de87b81e88384f"
+    "#;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "my-crate"
+
+[dependencies]
+serde = "1.0"
+
+[dev-dependencies]
+test-case = "3"
+    "#;
+
+    #[test]
+    fn locked_versions() {
+        let cargo = Cargo {
+            lockfile: Some(LOCKFILE.to_owned()),
+            ..Default::default()
+        };
+        let result = cargo.soups(MANIFEST, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(2, soups.len());
+        assert_eq!("serde", soups[0].name);
+        assert_eq!("1.0.137", soups[0].version);
+        assert_eq!(
+            Some(&Value::String("1.0".to_owned())),
+            soups[0].attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "61ea8d54c77f8315140a05f4c7237403bf38b72704d031543aa1d16abbf517d1".to_owned()
+            )),
+            soups[0].attributes.get("checksum")
+        );
+        assert_eq!("test-case", soups[1].name);
+        assert_eq!("3.3.1", soups[1].version);
+    }
+
+    #[test]
+    fn transitive_dependencies() {
+        let cargo = Cargo {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: true,
+            ..Default::default()
+        };
+        let result = cargo.soups(MANIFEST, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        let transitive = soups
+            .iter()
+            .filter_map(|soup| {
+                soup.attributes.get("dependency_path").map(|path| {
+                    (
+                        soup.name.as_str(),
+                        soup.scope.as_deref().unwrap(),
+                        path.to_string(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("serde_derive", "runtime", r#"["serde"]"#.to_owned()),
+                ("test-case-macros", "dev", r#"["test-case"]"#.to_owned()),
+            ],
+            transitive
+        );
+    }
+
+    #[test_case(None, "1.0")]
+    #[test_case(Some(LOCKFILE), "1.0.137")]
+    fn renamed_dependency(lockfile: Option<&str>, expected_version: &str) {
+        let cargo = Cargo {
+            lockfile: lockfile.map(|lockfile| lockfile.to_owned()),
+            ..Default::default()
+        };
+        let input = r#"
+[package]
+name = "my-crate"
+
+[dependencies]
+serde_crate = { package = "serde", version = "1.0" }
+        "#;
+        let result = cargo.soups(input, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(1, soups.len());
+        assert_eq!("serde", soups[0].name);
+        assert_eq!(expected_version, soups[0].version);
+        assert_eq!(
+            Some(&Value::String("serde_crate".to_owned())),
+            soups[0].attributes.get("alias")
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let cargo = Cargo {
+            lockfile: Some("[[package]]\nname = 4".to_owned()),
+            ..Default::default()
+        };
+        let result = cargo.soups(MANIFEST, &Map::new());
         assert_eq!(true, result.is_err());
     }
 }
@@ -0,0 +1,279 @@
+use super::{dependency_graph, strongest_scope};
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+
+/// Packages resolved by cargo, as listed in a Cargo.lock file
+#[derive(Deserialize)]
+pub struct CargoLock {
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub source: Option<String>,
+    pub checksum: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+pub type TransitivePackage<'a> =
+    dependency_graph::TransitivePackage<'a, &'a LockedPackage, &'a str>;
+
+impl CargoLock {
+    pub fn parse(content: &str) -> Result<CargoLock, SoupSourceParseError> {
+        match toml::from_str(content) {
+            Ok(lockfile) => Ok(lockfile),
+            Err(e) => Err(SoupSourceParseError {
+                message: format!("Invalid Cargo.lock ({})", e),
+            }),
+        }
+    }
+
+    /// Resolved package of a dependency, declared with the given version requirement by the
+    /// local package `root`.
+    ///
+    /// Without a root package, e.g. when the lockfile is out of date, the dependency is looked up
+    /// among all packages from a registry or git repository.
+    pub fn resolve(
+        &self,
+        root: Option<&str>,
+        dependency: &str,
+        requirement: &str,
+    ) -> Option<&LockedPackage> {
+        let root = root.and_then(|root| {
+            self.packages
+                .iter()
+                .find(|package| package.source.is_none() && package.name == root)
+        });
+        let candidates = match root {
+            Some(root) => self
+                .dependencies(root)
+                .into_iter()
+                .filter(|package| package.name == dependency)
+                .collect::<Vec<_>>(),
+            None => self
+                .packages
+                .iter()
+                .filter(|package| package.source.is_some() && package.name == dependency)
+                .collect::<Vec<_>>(),
+        };
+        match candidates.len() {
+            1 => candidates.into_iter().next(),
+            _ => candidates
+                .into_iter()
+                .find(|package| matches_requirement(&package.version, requirement)),
+        }
+    }
+
+    /// Packages from a registry or git repository that the given packages depend on, directly or
+    /// indirectly, with the strongest scope of the given packages that they are reached from
+    pub fn transitive<'a>(
+        &'a self,
+        direct: &[(&'a LockedPackage, &'a str)],
+    ) -> Vec<TransitivePackage<'a>> {
+        dependency_graph::transitive(
+            direct,
+            |package| package.name.as_str(),
+            |package| (package.name.as_str(), package.version.as_str()),
+            |package| {
+                self.dependencies(package)
+                    .into_iter()
+                    .filter(|dependency| dependency.source.is_some())
+                    .collect()
+            },
+            strongest_scope,
+        )
+    }
+
+    /// Packages that the given package depends on. Entries are formatted as `name`,
+    /// `name version` or `name version (source)`, depending on what is needed to be unambiguous.
+    fn dependencies(&self, package: &LockedPackage) -> Vec<&LockedPackage> {
+        package
+            .dependencies
+            .iter()
+            .filter_map(|entry| {
+                let mut parts = entry.splitn(3, ' ');
+                let name = parts.next()?;
+                let version = parts.next();
+                let source = parts
+                    .next()
+                    .map(|source| source.trim_start_matches('(').trim_end_matches(')'));
+                self.packages.iter().find(|candidate| {
+                    candidate.name == name
+                        && version.is_none_or(|version| candidate.version == version)
+                        && source.is_none_or(|source| candidate.source.as_deref() == Some(source))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether the version satisfies a caret, tilde or exact requirement such as `1.0`, `~1.2.3` or
+/// `=0.4.1`. Other requirements are not considered to be satisfied.
+fn matches_requirement(version: &str, requirement: &str) -> bool {
+    let requirement = requirement.trim();
+    let (operator, requirement) = match requirement.find(|c: char| c.is_ascii_digit()) {
+        Some(index) => (requirement[..index].trim(), &requirement[index..]),
+        None => return false,
+    };
+    let (version, requirement) = match (numeric_parts(version), numeric_parts(requirement)) {
+        (Some(version), Some(requirement)) if version.len() >= requirement.len() => {
+            (version, requirement)
+        }
+        _ => return false,
+    };
+    let compatible_parts = match operator {
+        "=" => requirement.len(),
+        "~" => requirement.len().min(2),
+        "" | "^" => match requirement.iter().position(|part| *part != 0) {
+            Some(index) => index + 1,
+            None => requirement.len(),
+        },
+        _ => return false,
+    };
+    version[..compatible_parts] == requirement[..compatible_parts]
+        && version[..requirement.len()] >= requirement[..]
+}
+
+fn numeric_parts(version: &str) -> Option<Vec<u64>> {
+    let version = version.split(['-', '+']).next()?;
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCKFILE: &str = r#"
+version = 3
+
+[[package]]
+name = "my-crate"
+version = "0.1.0"
+dependencies = [
+ "rand 0.7.3",
+ "rand 0.8.5",
+ "serde",
+]
+
+[[package]]
+name = "rand"
+version = "0.7.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "6a6b1679d49b24bbfe0c803429aa1874472f50d9b363131f0e89fc356b544d03"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "34af8d1a0e25924bc5b7c43c079c942339d8f0a8b57c39049bef581b46327404"
+dependencies = [
+ "rand_core",
+]
+
+[[package]]
+name = "rand_core"
+version = "0.6.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "ec0be4795e2f6a28069bec0b5ff3e2ac9bafc99e6a9a7dc3547996c5c816922c"
+
+[[package]]
+name = "serde"
+version = "1.0.137"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "61ea8d54c77f8315140a05f4c7237403bf38b72704d031543aa1d16abbf517d1"
+dependencies = [
+ "serde_derive",
+]
+
+[[package]]
+name = "serde_derive"
+version = "1.0.137"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "1f26faba0c3959972377d3b2d306ee9f71faee9714294e41bb777f83f88578be"
+dependencies = [
+ "rand_core",
+]
+    "#;
+
+    #[test_case(Some("my-crate"), "serde", "1.0", "1.0.137" ; "root_package")]
+    #[test_case(None, "serde", "1.0", "1.0.137" ; "missing_root_package")]
+    #[test_case(Some("my-crate"), "rand", "0.7", "0.7.3" ; "several_versions")]
+    #[test_case(Some("my-crate"), "rand", "^0.8.1", "0.8.5" ; "several_versions_caret")]
+    fn resolved_version(root: Option<&str>, dependency: &str, requirement: &str, expected: &str) {
+        let lockfile = CargoLock::parse(LOCKFILE).unwrap();
+        let package = lockfile.resolve(root, dependency, requirement);
+        assert_eq!(true, package.is_some());
+        assert_eq!(expected, package.unwrap().version);
+    }
+
+    #[test_case("1.0.137", "1.0", true)]
+    #[test_case("1.0.137", "^1.0.138", false)]
+    #[test_case("1.2.0", "1", true)]
+    #[test_case("0.8.5", "0.7", false)]
+    #[test_case("0.8.5", "~0.8.1", true)]
+    #[test_case("0.8.5", "=0.8.4", false)]
+    #[test_case("0.8.5-alpha.1", "=0.8.5", true)]
+    #[test_case("0.8.5", ">=0.8", false)]
+    fn requirement(version: &str, requirement: &str, expected: bool) {
+        assert_eq!(expected, matches_requirement(version, requirement));
+    }
+
+    #[test]
+    fn unresolved_dependency() {
+        let lockfile = CargoLock::parse(LOCKFILE).unwrap();
+        assert_eq!(
+            true,
+            lockfile
+                .resolve(Some("my-crate"), "serde_json", "1.0")
+                .is_none()
+        );
+        assert_eq!(
+            true,
+            lockfile.resolve(Some("my-crate"), "rand", "0.9").is_none()
+        );
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let lockfile = CargoLock::parse(LOCKFILE).unwrap();
+        let direct = vec![
+            (
+                lockfile.resolve(Some("my-crate"), "serde", "1.0").unwrap(),
+                "runtime",
+            ),
+            (
+                lockfile.resolve(Some("my-crate"), "rand", "0.8").unwrap(),
+                "dev",
+            ),
+        ];
+        let transitive = lockfile
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| {
+                (
+                    transitive.package.name.as_str(),
+                    transitive.path,
+                    transitive.scope,
+                )
+            })
+            .collect::<Vec<_>>();
+        // `rand_core` is reached from `serde` too, through `serde_derive`
+        assert_eq!(
+            vec![
+                ("serde_derive", vec!["serde"], "runtime"),
+                ("rand_core", vec!["rand"], "runtime"),
+            ],
+            transitive
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        assert_eq!(true, CargoLock::parse("[[package]]\nname = 4").is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use super::{SoupParse, docker_download};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Commands that download third-party sources at configure or build time
+const DOWNLOAD_COMMANDS: [&str; 2] = ["fetchcontent_declare", "externalproject_add"];
+
+lazy_static! {
+    static ref COMMAND: Regex = Regex::new(r"(?m)^[ \t]*(?P<name>[A-Za-z_]\w*)[ \t]*\(").unwrap();
+    static ref VARIABLE: Regex = Regex::new(r"\$\{(?P<name>\w+)\}").unwrap();
+}
+
+/// Parses the sources that `FetchContent_Declare` and `ExternalProject_Add` calls of a
+/// CMakeLists.txt or `*.cmake` script download, either from a Git repository at a `GIT_TAG` or
+/// as archive from a `URL`
+pub struct CMake {}
+
+impl SoupParse for CMake {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut variables = HashMap::new();
+        let mut soups = BTreeSet::new();
+        for (name, arguments) in commands(content)? {
+            let arguments = arguments
+                .iter()
+                .map(|argument| substitute(argument, &variables))
+                .collect::<Vec<String>>();
+            // Variables set to a single value, e.g. `set(FMT_VERSION 10.2.1)` or
+            // `set(FMT_VERSION 10.2.1 CACHE STRING "")`
+            let is_single_value =
+                arguments.len() == 2 || arguments.get(2).is_some_and(|word| word == "CACHE");
+            if name == "set" && is_single_value {
+                variables.insert(arguments[0].to_owned(), arguments[1].to_owned());
+                continue;
+            }
+            if !DOWNLOAD_COMMANDS.contains(&name.as_str()) || arguments.is_empty() {
+                continue;
+            }
+            let option = |option: &str| {
+                arguments
+                    .iter()
+                    .position(|argument| argument == option)
+                    .and_then(|index| arguments.get(index + 1))
+            };
+            let mut attributes = Map::new();
+            let version = match (option("GIT_REPOSITORY"), option("URL")) {
+                (Some(repository), _) => {
+                    attributes.insert("source".to_owned(), Value::String(repository.to_owned()));
+                    option("GIT_TAG").map(|tag| tag.to_owned())
+                }
+                (None, Some(url)) => {
+                    attributes.insert("source".to_owned(), Value::String(url.to_owned()));
+                    let checksum = match (option("URL_HASH"), option("URL_MD5")) {
+                        (Some(hash), _) => hash
+                            .split_once('=')
+                            .map(|(algorithm, hash)| (algorithm.to_lowercase(), hash)),
+                        (None, Some(hash)) => Some(("md5".to_owned(), hash.as_str())),
+                        (None, None) => None,
+                    };
+                    if let Some((algorithm, hash)) = checksum {
+                        attributes.insert(
+                            "checksum".to_owned(),
+                            Value::String(format!("{}:{}", algorithm, hash)),
+                        );
+                    }
+                    Some(docker_download::name_and_version(url).1)
+                }
+                (None, None) => None,
+            };
+            soups.insert(Soup {
+                name: arguments[0].to_owned(),
+                version: version.unwrap_or("unknown".to_owned()),
+                ecosystem: None,
+                scope: None,
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// Substitutes `${NAME}` with the values of variables set before, leaving references to unknown
+/// variables as they are
+fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    VARIABLE
+        .replace_all(text, |captures: &Captures| {
+            match variables.get(&captures["name"]) {
+                Some(value) => value.to_owned(),
+                None => captures[0].to_owned(),
+            }
+        })
+        .to_string()
+}
+
+/// Commands of a script, with lowercase names as they are case insensitive, and their
+/// arguments, unquoted
+pub fn commands(content: &str) -> Result<Vec<(String, Vec<String>)>, SoupSourceParseError> {
+    let mut commands = Vec::new();
+    let mut position = 0;
+    while let Some(captures) = COMMAND.captures_at(content, position) {
+        let start = captures.get(0).unwrap();
+        let (arguments, length) =
+            arguments(&content[start.end()..]).ok_or(SoupSourceParseError {
+                message: format!(
+                    "Invalid CMake script (unclosed arguments of {})",
+                    &captures["name"]
+                ),
+            })?;
+        commands.push((captures["name"].to_lowercase(), arguments));
+        position = start.end() + length;
+    }
+    Ok(commands)
+}
+
+/// Arguments up to the closing parenthesis, along with the length of the text they span
+fn arguments(text: &str) -> Option<(Vec<String>, usize)> {
+    let mut arguments = Vec::new();
+    let mut argument: Option<String> = None;
+    let mut depth = 0;
+    let mut characters = text.char_indices();
+    while let Some((index, character)) = characters.next() {
+        match character {
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match characters.next()?.1 {
+                        '"' => break,
+                        '\\' => quoted.push(characters.next()?.1),
+                        character => quoted.push(character),
+                    }
+                }
+                argument.get_or_insert_default().push_str(&quoted);
+            }
+            '#' => {
+                for (_, character) in characters.by_ref() {
+                    if character == '\n' {
+                        break;
+                    }
+                }
+                arguments.extend(argument.take());
+            }
+            ')' if depth == 0 => {
+                arguments.extend(argument.take());
+                return Some((arguments, index + 1));
+            }
+            character if character.is_whitespace() => arguments.extend(argument.take()),
+            character => {
+                match character {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                argument.get_or_insert_default().push(character);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downloaded_sources() {
+        let content = r#"
+cmake_minimum_required(VERSION 3.24)
+project(firmware C)
+
+include(FetchContent)
+
+set(CMSIS_VERSION "5.9.0" CACHE STRING "Version of CMSIS")
+
+FetchContent_Declare(
+  cmsis
+  GIT_REPOSITORY https://github.com/ARM-software/CMSIS_5.git
+  GIT_TAG        ${CMSIS_VERSION} # release tag
+  GIT_SHALLOW    TRUE
+)
+fetchcontent_declare(unity
+  URL "https://github.com/ThrowTheSwitch/Unity/archive/refs/tags/v2.6.0.tar.gz"
+  URL_HASH SHA256=aa4c9cd3a2b3e4d2f1e0f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6
+)
+# FetchContent_Declare(disabled GIT_REPOSITORY https://example.com/disabled.git)
+
+include(ExternalProject)
+ExternalProject_Add(mbedtls
+  URL https://example.com/mbedtls-3.5.2.tar.bz2
+  URL_MD5 0f1e2d3c4b5a69788796a5b4c3d2e1f0
+  CMAKE_ARGS -DENABLE_TESTING=Off
+)
+ExternalProject_Add(tools
+  SOURCE_DIR ${CMAKE_CURRENT_SOURCE_DIR}/tools
+)
+FetchContent_MakeAvailable(cmsis unity)
+"#;
+        let soups = CMake {}
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("cmsis", "5.9.0"),
+                ("mbedtls", "3.5.2"),
+                ("tools", "unknown"),
+                ("unity", "v2.6.0"),
+            ],
+            soups
+                .iter()
+                .map(|soup| (soup.name.as_str(), soup.version.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "https://github.com/ARM-software/CMSIS_5.git".to_owned()
+            )),
+            soups[0].attributes.get("source")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "md5:0f1e2d3c4b5a69788796a5b4c3d2e1f0".to_owned()
+            )),
+            soups[1].attributes.get("checksum")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "sha256:aa4c9cd3a2b3e4d2f1e0f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6"
+                    .to_owned()
+            )),
+            soups[3].attributes.get("checksum")
+        );
+    }
+
+    #[test]
+    fn unclosed_arguments() {
+        assert_eq!(
+            true,
+            CMake {}
+                .soups("FetchContent_Declare(fmt\n", &Map::new())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const DEV_SCOPE: &str = "dev";
+
+/// Parses the packages required in a composer.json, with versions resolved in the composer.lock
+/// next to it
+#[derive(Default)]
+pub struct Composer {
+    /// Content of the composer.lock next to the composer.json
+    pub lockfile: Option<String>,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    require: BTreeMap<String, String>,
+    #[serde(rename = "require-dev", default)]
+    require_dev: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    packages: Vec<LockedPackage>,
+    #[serde(rename = "packages-dev", default)]
+    packages_dev: Option<Vec<LockedPackage>>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<Source>,
+    dist: Option<Dist>,
+}
+
+#[derive(Deserialize)]
+struct Source {
+    url: String,
+    reference: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Dist {
+    shasum: Option<String>,
+}
+
+impl SoupParse for Composer {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = parse(content, "composer.json")?;
+        let lockfile: Option<Lockfile> = match &self.lockfile {
+            Some(lockfile) => Some(parse(lockfile, "composer.lock")?),
+            None => None,
+        };
+        let (packages, packages_dev) = match &lockfile {
+            Some(lockfile) => (
+                lockfile.packages.as_slice(),
+                lockfile.packages_dev.as_deref().unwrap_or_default(),
+            ),
+            None => (Default::default(), Default::default()),
+        };
+        let groups = [
+            (RUNTIME_SCOPE, &content.require, packages),
+            (DEV_SCOPE, &content.require_dev, packages_dev),
+        ];
+        let mut soups = BTreeSet::new();
+        let mut visited = HashSet::new();
+        for (scope, requirements, locked) in &groups {
+            // Platform packages such as `php` or `ext-json` have no vendor
+            for (name, requirement) in requirements.iter().filter(|(name, _)| name.contains('/')) {
+                let mut attributes = Map::new();
+                let locked_package = locked
+                    .iter()
+                    .find(|package| package.name.eq_ignore_ascii_case(name));
+                let version = match locked_package {
+                    Some(package) => {
+                        attributes.insert(
+                            "requirement".to_owned(),
+                            Value::String(requirement.to_owned()),
+                        );
+                        insert_lock_attributes(&mut attributes, package);
+                        package.version.to_owned()
+                    }
+                    None => requirement.to_owned(),
+                };
+                visited.insert(name.to_lowercase());
+                soups.insert(Soup {
+                    name: name.to_owned(),
+                    version,
+                    ecosystem: Some(Ecosystem::Composer),
+                    scope: Some(scope.to_string()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+
+        if self.include_transitive {
+            for (scope, _, locked) in &groups {
+                for package in locked.iter() {
+                    if !visited.insert(package.name.to_lowercase()) {
+                        continue;
+                    }
+                    let mut attributes = Map::new();
+                    insert_lock_attributes(&mut attributes, package);
+                    soups.insert(Soup {
+                        name: package.name.to_owned(),
+                        version: package.version.to_owned(),
+                        ecosystem: Some(Ecosystem::Composer),
+                        scope: Some(scope.to_string()),
+                        attributes,
+                        meta: default_meta.clone(),
+                    });
+                }
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    if let Some(source) = &package.source {
+        attributes.insert("source".to_owned(), Value::String(source.url.to_owned()));
+        if let Some(reference) = &source.reference {
+            attributes.insert("revision".to_owned(), Value::String(reference.to_owned()));
+        }
+    }
+    let shasum = package
+        .dist
+        .as_ref()
+        .and_then(|dist| dist.shasum.as_ref())
+        .filter(|shasum| !shasum.is_empty());
+    if let Some(shasum) = shasum {
+        attributes.insert(
+            "checksum".to_owned(),
+            Value::String(format!("sha1:{}", shasum)),
+        );
+    }
+}
+
+fn parse<T: DeserializeOwned>(content: &str, file_name: &str) -> Result<T, SoupSourceParseError> {
+    match serde_json::from_str(content) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid {} ({})", file_name, e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::scopes;
+    use test_case::test_case;
+
+    const MANIFEST: &str = r#"{
+    "name": "acme/portal",
+    "require": {
+        "php": "^8.2",
+        "ext-json": "*",
+        "symfony/http-kernel": "^6.4",
+        "monolog/monolog": "^3.6"
+    },
+    "require-dev": {
+        "phpunit/phpunit": "^11.2"
+    }
+}"#;
+
+    const LOCKFILE: &str = r#"{
+    "content-hash": "0d1f2e3c4b5a69788796a5b4c3d2e1f0",
+    "packages": [
+        {
+            "name": "monolog/monolog",
+            "version": "3.6.0",
+            "source": {
+                "type": "git",
+                "url": "https://github.com/Seldaek/monolog.git",
+                "reference": "4b18b21a5527a3d5ffdac2fd35d3ab25a9597654"
+            },
+            "dist": {
+                "type": "zip",
+                "url": "https://api.github.com/repos/Seldaek/monolog/zipball/4b18b21a5527a3d5ffdac2fd35d3ab25a9597654",
+                "shasum": ""
+            }
+        },
+        {
+            "name": "psr/log",
+            "version": "3.0.0",
+            "dist": {
+                "type": "zip",
+                "url": "https://example.com/psr-log-3.0.0.zip",
+                "shasum": "fe5ea303b0887d5caefd3d431c3e61ad47037001"
+            }
+        },
+        {
+            "name": "symfony/http-kernel",
+            "version": "v6.4.8"
+        }
+    ],
+    "packages-dev": [
+        {
+            "name": "phpunit/phpunit",
+            "version": "11.2.5"
+        },
+        {
+            "name": "sebastian/diff",
+            "version": "6.0.1"
+        }
+    ]
+}"#;
+
+    #[test]
+    fn declared_versions() {
+        let result = Composer::default().soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("monolog/monolog", "^3.6", "runtime"),
+                ("phpunit/phpunit", "^11.2", "dev"),
+                ("symfony/http-kernel", "^6.4", "runtime"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case(false, &[
+        ("monolog/monolog", "3.6.0", "runtime"),
+        ("phpunit/phpunit", "11.2.5", "dev"),
+        ("symfony/http-kernel", "v6.4.8", "runtime"),
+    ])]
+    #[test_case(true, &[
+        ("monolog/monolog", "3.6.0", "runtime"),
+        ("phpunit/phpunit", "11.2.5", "dev"),
+        ("psr/log", "3.0.0", "runtime"),
+        ("sebastian/diff", "6.0.1", "dev"),
+        ("symfony/http-kernel", "v6.4.8", "runtime"),
+    ])]
+    fn locked_versions(include_transitive: bool, soups: &[(&str, &str, &str)]) {
+        let composer = Composer {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive,
+        };
+        let result = composer.soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(soups, scopes(&result));
+    }
+
+    #[test]
+    fn locked_attributes() {
+        let composer = Composer {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: true,
+        };
+        let soups = composer.soups(MANIFEST, &Map::new()).unwrap();
+        let monolog = soups
+            .iter()
+            .find(|soup| soup.name == "monolog/monolog")
+            .unwrap();
+        assert_eq!(
+            Some(&Value::String(
+                "4b18b21a5527a3d5ffdac2fd35d3ab25a9597654".to_owned()
+            )),
+            monolog.attributes.get("revision")
+        );
+        assert_eq!(None, monolog.attributes.get("checksum"));
+        let psr_log = soups.iter().find(|soup| soup.name == "psr/log").unwrap();
+        assert_eq!(
+            Some(&Value::String(
+                "sha1:fe5ea303b0887d5caefd3d431c3e61ad47037001".to_owned()
+            )),
+            psr_log.attributes.get("checksum")
+        );
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        assert_eq!(
+            true,
+            Composer::default()
+                .soups("{\"require\": [", &Map::new())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const BUILD_SCOPE: &str = "build";
+const TEST_SCOPE: &str = "test";
+
+lazy_static! {
+    /// `name/version[@user/channel][#revision[%timestamp]]`, where the version may be a range
+    /// such as `[>=1.2 <2]`
+    static ref REFERENCE: Regex = Regex::new(
+        r"^(?P<name>[\w.+\-]+)/(?P<version>\[[^\]]*\]|[^@#\s/]+)(?:@(?P<channel>[^#\s]+))?(?:#(?P<revision>[0-9a-f]+))?"
+    )
+    .unwrap();
+    /// Requirements of a recipe, either as `requires = ...` attribute or as `self.requires(...)`
+    /// call in its `requirements` or `build_requirements` method
+    static ref RECIPE_REQUIREMENT: Regex =
+        Regex::new(r"\b(?P<kind>(?:tool_|build_|test_)?requires)\s*(?P<start>[=(])").unwrap();
+    static ref QUOTED: Regex = Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap();
+}
+
+/// Format of the file that a Conan package or consumer declares its requirements in
+pub enum ConanFormat {
+    /// conanfile.txt
+    Text,
+    /// conanfile.py
+    Recipe,
+}
+
+/// Parses the requirements of a conanfile.txt or conanfile.py
+pub struct Conan {
+    pub format: ConanFormat,
+    /// Content of the conan.lock next to the conanfile
+    pub lockfile: Option<String>,
+    /// Whether to include packages that requirements depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+struct Reference {
+    name: String,
+    version: String,
+    channel: Option<String>,
+    revision: Option<String>,
+}
+
+/// Lockfiles of Conan 2, listing the references of all packages of the dependency graph
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    requires: Vec<String>,
+    #[serde(default)]
+    build_requires: Vec<String>,
+    /// Lockfiles of Conan 1 list the nodes of the dependency graph instead
+    graph_lock: Option<GraphLock>,
+}
+
+#[derive(Deserialize)]
+struct GraphLock {
+    nodes: BTreeMap<String, Node>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+}
+
+impl SoupParse for Conan {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let requirements = match self.format {
+            ConanFormat::Text => text_requirements(content),
+            ConanFormat::Recipe => recipe_requirements(content),
+        };
+        let locked = match &self.lockfile {
+            Some(lockfile) => locked_references(lockfile)?,
+            None => Vec::new(),
+        };
+        let mut soups = BTreeSet::new();
+        let mut direct = HashSet::new();
+        for (scope, reference) in requirements {
+            let mut attributes = Map::new();
+            let locked_reference = locked
+                .iter()
+                .find(|(_, locked)| locked.name == reference.name);
+            let version = match locked_reference {
+                Some((_, locked)) => {
+                    attributes.insert(
+                        "requirement".to_owned(),
+                        Value::String(reference.version.to_owned()),
+                    );
+                    insert_reference_attributes(&mut attributes, locked);
+                    locked.version.to_owned()
+                }
+                None => {
+                    insert_reference_attributes(&mut attributes, &reference);
+                    reference.version.to_owned()
+                }
+            };
+            direct.insert(reference.name.to_owned());
+            soups.insert(Soup {
+                name: reference.name,
+                version,
+                ecosystem: Some(Ecosystem::Conan),
+                scope: Some(scope.to_owned()),
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+
+        if self.include_transitive {
+            for (scope, reference) in &locked {
+                if direct.contains(&reference.name) {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                insert_reference_attributes(&mut attributes, reference);
+                soups.insert(Soup {
+                    name: reference.name.to_owned(),
+                    version: reference.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Conan),
+                    scope: Some(scope.to_string()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn insert_reference_attributes(attributes: &mut Map<String, Value>, reference: &Reference) {
+    if let Some(channel) = &reference.channel {
+        attributes.insert("channel".to_owned(), Value::String(channel.to_owned()));
+    }
+    if let Some(revision) = &reference.revision {
+        attributes.insert("revision".to_owned(), Value::String(revision.to_owned()));
+    }
+}
+
+fn reference(text: &str) -> Option<Reference> {
+    let captures = REFERENCE.captures(text.trim())?;
+    Some(Reference {
+        name: captures["name"].to_owned(),
+        version: captures["version"].to_owned(),
+        channel: captures
+            .name("channel")
+            .map(|channel| channel.as_str().to_owned()),
+        revision: captures
+            .name("revision")
+            .map(|revision| revision.as_str().to_owned()),
+    })
+}
+
+fn scope(kind: &str) -> &'static str {
+    match kind {
+        "tool_requires" | "build_requires" => BUILD_SCOPE,
+        "test_requires" => TEST_SCOPE,
+        _ => RUNTIME_SCOPE,
+    }
+}
+
+/// Requirements listed in the `[requires]`, `[tool_requires]`, `[build_requires]` and
+/// `[test_requires]` sections of a conanfile.txt
+fn text_requirements(content: &str) -> Vec<(&'static str, Reference)> {
+    let mut section = "";
+    let mut requirements = Vec::new();
+    for line in content.lines() {
+        // Comments start lines or follow whitespace, as `#` also precedes revisions
+        let line = line.trim();
+        let line = match line.starts_with('#') {
+            true => "",
+            false => line.split(" #").next().unwrap_or(line).trim(),
+        };
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            section = name;
+            continue;
+        }
+        if !section.ends_with("requires") || line.is_empty() {
+            continue;
+        }
+        requirements.extend(reference(line).map(|reference| (scope(section), reference)));
+    }
+    requirements
+}
+
+/// Requirements of a conanfile.py, e.g. `requires = "zlib/1.3", "fmt/10.2.1"` or
+/// `self.tool_requires("cmake/[>=3.25]")`
+fn recipe_requirements(content: &str) -> Vec<(&'static str, Reference)> {
+    let mut requirements = Vec::new();
+    for captures in RECIPE_REQUIREMENT.captures_iter(content) {
+        let start = captures.name("start").unwrap();
+        let rest = &content[start.end()..];
+        let arguments = match start.as_str() {
+            "(" => enclosed(rest, '(', ')'),
+            _ => {
+                let rest = rest.trim_start();
+                match rest.chars().next() {
+                    Some(open @ ('(' | '[')) => {
+                        let close = if open == '(' { ')' } else { ']' };
+                        enclosed(&rest[1..], open, close)
+                    }
+                    _ => rest.lines().next().unwrap_or(rest),
+                }
+            }
+        };
+        let references = QUOTED
+            .captures_iter(arguments)
+            .filter_map(|quoted| quoted.get(1).or(quoted.get(2)))
+            .filter_map(|quoted| reference(quoted.as_str()));
+        let scope = scope(&captures["kind"]);
+        requirements.extend(references.map(|reference| (scope, reference)));
+    }
+    requirements
+}
+
+/// Text up to the bracket that closes an already opened one
+fn enclosed(text: &str, open: char, close: char) -> &str {
+    let mut depth = 1;
+    for (index, character) in text.char_indices() {
+        if character == open {
+            depth += 1;
+        } else if character == close {
+            depth -= 1;
+            if depth == 0 {
+                return &text[..index];
+            }
+        }
+    }
+    text
+}
+
+fn locked_references(
+    lockfile: &str,
+) -> Result<Vec<(&'static str, Reference)>, SoupSourceParseError> {
+    let lockfile: Lockfile = match serde_json::from_str(lockfile) {
+        Ok(lockfile) => lockfile,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid Conan lockfile ({})", e),
+            });
+        }
+    };
+    let nodes = lockfile
+        .graph_lock
+        .map(|graph_lock| {
+            graph_lock
+                .nodes
+                .into_values()
+                .filter_map(|node| node.reference)
+        })
+        .into_iter()
+        .flatten();
+    Ok(lockfile
+        .requires
+        .into_iter()
+        .chain(nodes)
+        .map(|reference| (RUNTIME_SCOPE, reference))
+        .chain(
+            lockfile
+                .build_requires
+                .into_iter()
+                .map(|reference| (BUILD_SCOPE, reference)),
+        )
+        .filter_map(|(scope, text)| Some((scope, reference(&text)?)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::scopes;
+    use test_case::test_case;
+
+    const TEXT: &str = r#"
+# Libraries linked into the firmware
+[requires]
+zlib/1.3.1#f52e03ae3d251dec704634230cd806a2
+openssl/[>=3.0 <4]  # any 3.x release
+mbedtls/3.5.2@acme/stable
+
+[tool_requires]
+cmake/3.28.1
+
+[generators]
+CMakeDeps
+CMakeToolchain
+"#;
+
+    const LOCKFILE: &str = r#"{
+    "version": "0.5",
+    "requires": [
+        "zlib/1.3.1#f52e03ae3d251dec704634230cd806a2%1708593606.497",
+        "openssl/3.2.1#ad3ab1fc7e1a9a1ed7d4ba94f8f1c8d5%1708520487.389",
+        "mbedtls/3.5.2@acme/stable#9e6aa4ef4f3e9c44a1d0c5ae33f1e7b1%1705000000.0"
+    ],
+    "build_requires": [
+        "cmake/3.28.1#92f79424d7b65b12a84a2180866c3a78%1705499279.39",
+        "ninja/1.11.1#77587f8c8318662ac8e5a7867eb4be21%1684431244.21"
+    ],
+    "python_requires": []
+}"#;
+
+    #[test]
+    fn text_requirements() {
+        let conan = Conan {
+            format: ConanFormat::Text,
+            lockfile: None,
+            include_transitive: false,
+        };
+        let result = conan.soups(TEXT, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("cmake", "3.28.1", "build"),
+                ("mbedtls", "3.5.2", "runtime"),
+                ("openssl", "[>=3.0 <4]", "runtime"),
+                ("zlib", "1.3.1", "runtime"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test]
+    fn recipe_requirements() {
+        let content = r#"
+from conan import ConanFile
+
+class FirmwareConan(ConanFile):
+    settings = "os", "arch", "compiler", "build_type"
+    requires = (
+        "zlib/1.3.1",
+        "fmt/10.2.1",
+    )
+    test_requires = "gtest/1.14.0"
+
+    def requirements(self):
+        self.requires("openssl/[>=3.0 <4]", force=True)
+        if self.options.with_tls:
+            self.requires("mbedtls/3.5.2")
+
+    def build_requirements(self):
+        self.tool_requires("cmake/[>=3.25]")
+"#;
+        let conan = Conan {
+            format: ConanFormat::Recipe,
+            lockfile: None,
+            include_transitive: false,
+        };
+        let result = conan.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("cmake", "[>=3.25]", "build"),
+                ("fmt", "10.2.1", "runtime"),
+                ("gtest", "1.14.0", "test"),
+                ("mbedtls", "3.5.2", "runtime"),
+                ("openssl", "[>=3.0 <4]", "runtime"),
+                ("zlib", "1.3.1", "runtime"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case(false, &[
+        ("cmake", "3.28.1", "build"),
+        ("mbedtls", "3.5.2", "runtime"),
+        ("openssl", "3.2.1", "runtime"),
+        ("zlib", "1.3.1", "runtime"),
+    ])]
+    #[test_case(true, &[
+        ("cmake", "3.28.1", "build"),
+        ("mbedtls", "3.5.2", "runtime"),
+        ("ninja", "1.11.1", "build"),
+        ("openssl", "3.2.1", "runtime"),
+        ("zlib", "1.3.1", "runtime"),
+    ])]
+    fn locked_requirements(include_transitive: bool, soups: &[(&str, &str, &str)]) {
+        let conan = Conan {
+            format: ConanFormat::Text,
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive,
+        };
+        let result = conan.soups(TEXT, &Map::new()).unwrap();
+        assert_eq!(soups, scopes(&result));
+    }
+
+    #[test]
+    fn locked_attributes() {
+        let conan = Conan {
+            format: ConanFormat::Text,
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: false,
+        };
+        let soups = conan.soups(TEXT, &Map::new()).unwrap();
+        let mbedtls = soups.iter().find(|soup| soup.name == "mbedtls").unwrap();
+        assert_eq!(
+            Some(&Value::String("acme/stable".to_owned())),
+            mbedtls.attributes.get("channel")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "9e6aa4ef4f3e9c44a1d0c5ae33f1e7b1".to_owned()
+            )),
+            mbedtls.attributes.get("revision")
+        );
+        let openssl = soups.iter().find(|soup| soup.name == "openssl").unwrap();
+        assert_eq!(
+            Some(&Value::String("[>=3.0 <4]".to_owned())),
+            openssl.attributes.get("requirement")
+        );
+    }
+
+    #[test]
+    fn conan_1_lockfile() {
+        let lockfile = r#"{
+            "graph_lock": {
+                "nodes": {
+                    "0": { "options": "", "requires": ["1"] },
+                    "1": { "ref": "zlib/1.2.13#13c96f538b52e1600c40b88994de240f" }
+                }
+            },
+            "version": "0.4"
+        }"#;
+        let conan = Conan {
+            format: ConanFormat::Text,
+            lockfile: Some(lockfile.to_owned()),
+            include_transitive: false,
+        };
+        let result = conan
+            .soups("[requires]\nzlib/[>=1.2]\n", &Map::new())
+            .unwrap();
+        assert_eq!(vec![("zlib", "1.2.13", "runtime")], scopes(&result));
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let conan = Conan {
+            format: ConanFormat::Text,
+            lockfile: Some("{\"requires\": [".to_owned()),
+            include_transitive: false,
+        };
+        assert_eq!(true, conan.soups(TEXT, &Map::new()).is_err());
+    }
+}
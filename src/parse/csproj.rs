@@ -1,11 +1,21 @@
 use super::SoupParse;
-use crate::soup::model::{Soup, SoupSourceParseError};
-use quick_xml::Reader;
-use quick_xml::events::Event;
+use super::msbuild;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
 use serde_json::{Map, Value};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{self, Path, PathBuf};
 
-pub struct CsProj {}
+#[derive(Default)]
+pub struct CsProj {
+    /// Path of the project file, which imported files are resolved relative to
+    pub path: Option<PathBuf>,
+    /// Directory of the scan, which imported files are not searched for above
+    pub root: Option<PathBuf>,
+    /// Files that MSBuild imports before the project, i.e. the closest `Directory.Build.props`
+    /// and `Directory.Packages.props`
+    pub implicit_imports: Vec<PathBuf>,
+}
 
 impl SoupParse for CsProj {
     fn soups(
@@ -13,69 +23,75 @@ impl SoupParse for CsProj {
         content: &str,
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
-        let mut reader = Reader::from_str(content);
-        reader.config_mut().trim_text(true);
-        reader.config_mut().expand_empty_elements = true;
-
-        let mut soups: BTreeSet<Soup> = BTreeSet::new();
-        let mut buf = Vec::new();
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => {
-                    if e.name().as_ref() == b"PackageReference" {
-                        let attributes_by_key = e
-                            .attributes()
-                            .filter_map(|attribute| attribute.ok())
-                            .map(|attribute| {
-                                (
-                                    attribute.key.into_inner().to_vec(),
-                                    attribute.value.to_vec(),
-                                )
-                            })
-                            .collect::<HashMap<Vec<u8>, Vec<u8>>>();
-                        let name = attribute_value(&attributes_by_key, "Include")?;
-                        let version = attribute_value(&attributes_by_key, "Version")?;
-                        soups.insert(Soup {
-                            name,
-                            version,
-                            meta: default_meta.clone(),
-                        });
-                    }
-                }
-                Ok(Event::Eof) => break,
-                Err(e) => {
-                    return Err(SoupSourceParseError {
-                        message: format!("Invalid XML structure {}", e),
-                    });
-                }
-                _ => {}
-            }
-        }
-        buf.clear();
-        Ok(soups)
+        let load = |path: &Path| fs::read_to_string(path).ok();
+        // MSBuild resolves imports from absolute paths, e.g. `$(MSBuildThisFileDirectory)`
+        let implicit_imports = self
+            .implicit_imports
+            .iter()
+            .map(|import| absolute(import))
+            .collect::<Vec<_>>();
+        let evaluation = msbuild::evaluate(
+            content,
+            self.path.as_deref().map(absolute).as_deref(),
+            self.root.as_deref().map(absolute).as_deref(),
+            &implicit_imports,
+            &load,
+        )?;
+        Ok(soups(&evaluation, default_meta))
     }
 }
 
-fn attribute_value(
-    attributes: &HashMap<Vec<u8>, Vec<u8>>,
-    key: &str,
-) -> Result<String, SoupSourceParseError> {
-    match attributes.get(key.as_bytes()) {
-        Some(value) => match String::from_utf8(value.to_owned()) {
-            Ok(value) => Ok(value),
-            Err(_e) => Err(SoupSourceParseError {
-                message: format!("Unable to parse attribute {} as utf8", key),
-            }),
-        },
-        None => Err(SoupSourceParseError {
-            message: format!("Missing required attribute: {}", key),
-        }),
-    }
+fn absolute(path: &Path) -> PathBuf {
+    path::absolute(path).unwrap_or_else(|_| path.to_owned())
+}
+
+/// SOUPs referenced by an evaluated project, with versions from the reference itself or, with
+/// central package management, from `PackageVersion` items unless overridden by the reference
+fn soups(evaluation: &msbuild::Evaluation, default_meta: &Map<String, Value>) -> BTreeSet<Soup> {
+    let central_package_management = evaluation
+        .property("ManagePackageVersionsCentrally")
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+    let references = evaluation
+        .items("PackageReference")
+        .chain(evaluation.items("GlobalPackageReference"));
+    references
+        .map(|reference| {
+            let central_version = match central_package_management {
+                true => evaluation
+                    .items("PackageVersion")
+                    .filter(|package| package.include.eq_ignore_ascii_case(&reference.include))
+                    .find_map(|package| package.metadata("Version")),
+                false => None,
+            };
+            let version = match reference
+                .metadata("VersionOverride")
+                .or(central_version)
+                .or(reference.metadata("Version"))
+            {
+                Some(version) => version,
+                None => {
+                    eprintln!(
+                        "Warning: unable to resolve the version of package {}, as it is missing or its properties have no value",
+                        reference.include
+                    );
+                    "unknown"
+                }
+            };
+            Soup {
+                name: reference.include.to_owned(),
+                version: version.to_owned(),
+                ecosystem: Some(Ecosystem::NuGet),
+                meta: default_meta.clone(),
+                ..Default::default()
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::versions;
 
     #[test]
     fn single_dependency() {
@@ -87,7 +103,7 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -95,6 +111,7 @@ mod tests {
             name: "Azure.Messaging.ServiceBus".to_owned(),
             version: "7.2.1".to_owned(),
             meta: Map::new(),
+            ..Default::default()
         };
         assert_eq!(true, soups.contains(&expected_soup));
     }
@@ -110,7 +127,7 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -119,11 +136,13 @@ mod tests {
                 name: "Azure.Messaging.ServiceBus".to_owned(),
                 version: "7.2.1".to_owned(),
                 meta: Map::new(),
+                ..Default::default()
             },
             Soup {
                 name: "Swashbuckle.AspNetCore".to_owned(),
                 version: "6.3.1".to_owned(),
                 meta: Map::new(),
+                ..Default::default()
             },
         ]
         .into_iter()
@@ -140,9 +159,128 @@ mod tests {
 </Project>
         "#;
 
-        let result = CsProj {}.soups(content, &Map::new());
+        let result = CsProj::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
     }
+
+    fn evaluate(content: &str, files: &[(&str, &str)]) -> BTreeSet<Soup> {
+        let files = files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+            .collect::<Vec<_>>();
+        let load = |path: &Path| {
+            files
+                .iter()
+                .find(|(file, _)| file == path)
+                .map(|(_, content)| content.to_owned())
+        };
+        let implicit_imports = files
+            .iter()
+            .map(|(path, _)| path.to_owned())
+            .collect::<Vec<_>>();
+        let evaluation = msbuild::evaluate(
+            content,
+            Some(Path::new("/repo/src/App/App.csproj")),
+            Some(Path::new("/repo")),
+            &implicit_imports,
+            &load,
+        )
+        .unwrap();
+        soups(&evaluation, &Map::new())
+    }
+
+    #[test]
+    fn property_versions() {
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk.Web">
+    <ItemGroup>
+        <PackageReference Include="Microsoft.AspNetCore.Authentication.JwtBearer" Version="$(AspNetCoreVersion)" />
+        <PackageReference Include="Swashbuckle.AspNetCore">
+            <Version>6.3.1</Version>
+        </PackageReference>
+    </ItemGroup>
+</Project>
+        "#;
+        let directory_build_props = r#"
+<Project>
+    <PropertyGroup>
+        <AspNetCoreVersion>6.0.8</AspNetCoreVersion>
+    </PropertyGroup>
+</Project>
+        "#;
+        let soups = evaluate(
+            content,
+            &[("/repo/Directory.Build.props", directory_build_props)],
+        );
+        assert_eq!(
+            vec![
+                ("Microsoft.AspNetCore.Authentication.JwtBearer", "6.0.8"),
+                ("Swashbuckle.AspNetCore", "6.3.1"),
+            ],
+            versions(&soups)
+        );
+    }
+
+    #[test]
+    fn central_package_management() {
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk.Web">
+    <ItemGroup>
+        <PackageReference Include="Azure.Messaging.ServiceBus" />
+        <PackageReference Include="Serilog" VersionOverride="3.0.0" />
+        <PackageReference Include="Unversioned.Package" />
+    </ItemGroup>
+</Project>
+        "#;
+        let directory_packages_props = r#"
+<Project>
+    <PropertyGroup>
+        <ManagePackageVersionsCentrally>true</ManagePackageVersionsCentrally>
+        <SerilogVersion>2.12.0</SerilogVersion>
+    </PropertyGroup>
+    <ItemGroup>
+        <PackageVersion Include="Azure.Messaging.ServiceBus" Version="7.2.1" />
+        <PackageVersion Include="Serilog" Version="$(SerilogVersion)" />
+        <GlobalPackageReference Include="StyleCop.Analyzers" Version="1.1.118" />
+    </ItemGroup>
+</Project>
+        "#;
+        let soups = evaluate(
+            content,
+            &[("/repo/Directory.Packages.props", directory_packages_props)],
+        );
+        assert_eq!(
+            vec![
+                ("Azure.Messaging.ServiceBus", "7.2.1"),
+                ("Serilog", "3.0.0"),
+                ("StyleCop.Analyzers", "1.1.118"),
+                ("Unversioned.Package", "unknown"),
+            ],
+            versions(&soups)
+        );
+    }
+
+    #[test]
+    fn update_reference() {
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk.Web">
+    <ItemGroup>
+        <PackageReference Include="Newtonsoft.Json" Version="12.0.1" />
+    </ItemGroup>
+    <ItemGroup>
+        <PackageReference Update="Newtonsoft.Json" Version="13.0.1" />
+    </ItemGroup>
+</Project>
+        "#;
+        let soups = evaluate(content, &[]);
+        assert_eq!(vec![("Newtonsoft.Json", "13.0.1")], versions(&soups));
+    }
+
+    #[test]
+    fn invalid_xml() {
+        let result = CsProj::default().soups("<Project><ItemGroup></Project>", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
 }
@@ -0,0 +1,133 @@
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A package that direct dependencies depend on, along with the names of the packages leading to
+/// it, starting with a direct dependency, and the strongest scope of the direct dependencies that
+/// it is reached from
+pub struct TransitivePackage<'a, P, S> {
+    pub package: P,
+    pub path: Vec<&'a str>,
+    pub scope: S,
+}
+
+impl<P, S> TransitivePackage<'_, P, S> {
+    /// Records the path leading to the package in the attributes of its SOUP
+    pub fn insert_dependency_path(&self, attributes: &mut Map<String, Value>) {
+        attributes.insert(
+            "dependency_path".to_owned(),
+            Value::Array(
+                self.path
+                    .iter()
+                    .map(|name| Value::String(name.to_string()))
+                    .collect(),
+            ),
+        );
+    }
+}
+
+/// Packages that the given direct dependencies depend on, directly or indirectly, in
+/// breadth-first order so that each package gets its shortest path. Packages are identified by
+/// `key`, so that each is listed once, and are listed with the strongest of the scopes of all
+/// direct dependencies they are reached from as merged by `strongest`, rather than the scope of
+/// the direct dependency on their shortest path.
+pub fn transitive<'a, P, K, S>(
+    direct: &[(P, S)],
+    name: impl Fn(P) -> &'a str,
+    key: impl Fn(P) -> K,
+    dependencies: impl Fn(P) -> Vec<P>,
+    strongest: impl Fn(S, S) -> S,
+) -> Vec<TransitivePackage<'a, P, S>>
+where
+    P: Copy,
+    K: Eq + Hash,
+    S: Copy + PartialEq,
+{
+    let mut visited = direct
+        .iter()
+        .map(|(package, _)| key(*package))
+        .collect::<HashSet<_>>();
+    let mut queue = direct
+        .iter()
+        .map(|(package, _)| (*package, vec![name(*package)]))
+        .collect::<VecDeque<_>>();
+    let mut transitive = Vec::new();
+    while let Some((package, path)) = queue.pop_front() {
+        for dependency in dependencies(package) {
+            if !visited.insert(key(dependency)) {
+                continue;
+            }
+            let mut dependency_path = path.clone();
+            dependency_path.push(name(dependency));
+            transitive.push((dependency, path.clone()));
+            queue.push_back((dependency, dependency_path));
+        }
+    }
+
+    // Scopes only ever get stronger, so packages are revisited at most once per scope
+    let mut scopes: HashMap<K, S> = HashMap::new();
+    let mut stack = direct.to_vec();
+    while let Some((package, scope)) = stack.pop() {
+        for dependency in dependencies(package) {
+            let dependency_key = key(dependency);
+            let merged = match scopes.get(&dependency_key) {
+                Some(existing) if strongest(*existing, scope) == *existing => continue,
+                Some(existing) => strongest(*existing, scope),
+                None => scope,
+            };
+            scopes.insert(dependency_key, merged);
+            stack.push((dependency, merged));
+        }
+    }
+
+    transitive
+        .into_iter()
+        .filter_map(|(package, path)| {
+            let scope = *scopes.get(&key(package))?;
+            Some(TransitivePackage {
+                package,
+                path,
+                scope,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn strongest_scope_of_all_paths() {
+        // `dev-tool` leads to `shared` directly, whereas `app` only leads to it through `lib`
+        let graph = BTreeMap::from([
+            ("app", vec!["lib"]),
+            ("lib", vec!["shared"]),
+            ("dev-tool", vec!["shared", "mock"]),
+            ("shared", vec!["app"]),
+            ("mock", vec![]),
+        ]);
+        let transitive = transitive(
+            &[("app", "runtime"), ("dev-tool", "dev")],
+            |package| package,
+            |package| package,
+            |package| graph[package].clone(),
+            |a, b| match (a, b) {
+                ("runtime", _) | (_, "runtime") => "runtime",
+                _ => a,
+            },
+        )
+        .into_iter()
+        .map(|transitive| (transitive.package, transitive.path, transitive.scope))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("lib", vec!["app"], "runtime"),
+                ("shared", vec!["dev-tool"], "runtime"),
+                ("mock", vec!["dev-tool"], "dev"),
+            ],
+            transitive
+        );
+    }
+}
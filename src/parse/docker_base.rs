@@ -1,24 +1,24 @@
-use super::SoupParse;
-use crate::soup::model::{Soup, SoupSourceParseError};
+use super::{SoupParse, dockerfile};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
 use lazy_static::lazy_static;
-use regex::{Regex, RegexSet};
+use regex::Regex;
 use serde_json::{Map, Value};
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 
-pub struct DockerBase {}
+const LATEST_TAG: &str = "latest";
 
-static PATTERNS: [&str; 2] = [
-    r"^(?i)FROM(?-i) (?:--platform=[\w/]+ )?(?P<name>(?:[a-z0-9\.\-_]+){1}(?:/[a-z0-9\.\-_]+)*)[:@](?P<tag>[a-zA-Z0-9\.\-_]+)(?: (?i)AS(?-i) [\w\-]+)?$",
-    r"^(?i)FROM(?-i) (?:--platform=[\w/]+ )?(?P<name>(?:[a-z0-9\.\-_]+){1}:[0-9]+(?:/[a-z0-9\.\-_]+)*)[:@](?P<tag>[a-zA-Z0-9\.\-_]+)(?: (?i)AS(?-i) [\w\-]+)?$",
-];
+/// Parses the base images of the stages of a Dockerfile
+#[derive(Default)]
+pub struct DockerBase {
+    /// Values of build arguments, overriding the defaults of `ARG` instructions
+    pub build_args: HashMap<String, String>,
+}
 
 lazy_static! {
-    static ref PATTERN_SET: RegexSet = RegexSet::new(PATTERNS).unwrap();
-    static ref REGEXES: Vec<Regex> = PATTERN_SET
-        .patterns()
-        .iter()
-        .map(|pat| Regex::new(pat).unwrap())
-        .collect();
+    static ref IMAGE_NAME: Regex =
+        Regex::new(r"^[a-z0-9\.\-_]+(?::[0-9]+)?(?:/[a-z0-9\.\-_]+)*$").unwrap();
+    static ref TAG: Regex = Regex::new(r"^[a-zA-Z0-9\.\-_]+$").unwrap();
+    static ref DIGEST: Regex = Regex::new(r"^(?:[a-z0-9]+:)?[a-zA-Z0-9]+$").unwrap();
 }
 
 impl SoupParse for DockerBase {
@@ -28,34 +28,91 @@ impl SoupParse for DockerBase {
         default_meta: &Map<String, Value>,
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
         let mut result: BTreeSet<Soup> = BTreeSet::new();
-        let lines = content.lines();
-        for line in lines {
-            let matching_patterns = PATTERN_SET
-                .matches(line)
-                .into_iter()
-                .map(|match_id| &REGEXES[match_id])
-                .collect::<Vec<&Regex>>();
-            if let Some(pattern) = matching_patterns.first() {
-                if let Some(captures) = pattern.captures(line) {
-                    result.insert(Soup {
-                        name: named_capture(&captures, "name")?,
-                        version: named_capture(&captures, "tag")?,
-                        meta: default_meta.clone(),
-                    });
+        let mut scope = dockerfile::Scope::new(&self.build_args);
+        let mut stages: Vec<String> = Vec::new();
+        for instruction in dockerfile::instructions(content) {
+            if instruction.keyword == "FROM" {
+                let arguments = scope.substitute(&instruction);
+                let words = arguments
+                    .split_whitespace()
+                    .filter(|word| !word.starts_with("--"))
+                    .collect::<Vec<&str>>();
+                if let [image, ..] = words.as_slice() {
+                    // Stages built earlier in the same Dockerfile are no images of their own
+                    let is_stage = stages.iter().any(|stage| stage.eq_ignore_ascii_case(image));
+                    if !is_stage && *image != "scratch" {
+                        if let Some(soup) = image_soup(image, default_meta) {
+                            result.insert(soup);
+                        }
+                    }
+                }
+                if let [_, keyword, stage, ..] = words.as_slice() {
+                    if keyword.eq_ignore_ascii_case("as") {
+                        stages.push(stage.to_string());
+                    }
                 }
             }
+            scope.apply(&instruction);
         }
         Ok(result)
     }
 }
 
-fn named_capture(captures: &regex::Captures, name: &str) -> Result<String, SoupSourceParseError> {
-    match captures.name(name) {
-        Some(value) => Ok(value.as_str().to_owned()),
-        None => Err(SoupSourceParseError {
-            message: "Unable to parse FROM statement in dockerfile".to_owned(),
-        }),
+/// SOUP of an image reference such as `postgres:14.4`, `postgres@sha256:<hash>` or both, where
+/// the digest is kept as an attribute alongside the tag. References without either are reported
+/// as `latest`, which is what Docker pulls for them.
+pub fn image_soup(image: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    let (reference, digest) = match image.split_once('@') {
+        Some((reference, digest)) => (reference, Some(digest)),
+        None => (image, None),
+    };
+    // A colon after the last slash separates the tag, whereas one before it separates a port
+    let (name, tag) = match reference.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+        _ => (reference, None),
+    };
+    if !IMAGE_NAME.is_match(name)
+        || !tag.is_none_or(|tag| TAG.is_match(tag))
+        || !digest.is_none_or(|digest| DIGEST.is_match(digest))
+    {
+        if image.contains('$') {
+            eprintln!(
+                "Warning: unable to resolve image {}, as not all of its variables have a value",
+                image
+            );
+        }
+        return None;
     }
+    let mut attributes = Map::new();
+    let version = match (tag, digest) {
+        (Some(tag), Some(digest)) => {
+            attributes.insert("digest".to_owned(), Value::String(digest.to_owned()));
+            tag.to_owned()
+        }
+        (Some(tag), None) => tag.to_owned(),
+        (None, Some(digest)) => digest.to_owned(),
+        (None, None) => {
+            eprintln!(
+                "Warning: image {} has no tag, assuming {}",
+                name, LATEST_TAG
+            );
+            LATEST_TAG.to_owned()
+        }
+    };
+    Some(Soup {
+        name: name.to_owned(),
+        version,
+        ecosystem: Some(Ecosystem::Docker),
+        attributes,
+        meta: default_meta.clone(),
+        ..Default::default()
+    })
+}
+
+/// Whether the SOUP of an image reference is pinned to a digest, rather than a tag that may be
+/// moved to another image
+pub fn is_pinned(soup: &Soup) -> bool {
+    soup.attributes.contains_key("digest") || soup.version.starts_with("sha256:")
 }
 
 #[cfg(test)]
@@ -67,7 +124,7 @@ mod tests {
     #[test_case("FROM --platform=linux/x86_64 postgres:14.4")]
     #[test_case("FROM postgres:14.4 AS build-env")]
     fn simple_image_name(input: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -77,7 +134,8 @@ mod tests {
             Soup {
                 name: "postgres".to_owned(),
                 version: "14.4".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         );
     }
@@ -86,7 +144,7 @@ mod tests {
     #[test_case("FROM --platform=linux/i686 fedora/httpd:v1.6.2")]
     #[test_case("FROM fedora/httpd:v1.6.2 AS some-name")]
     fn multiple_parts_image_name(input: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -96,7 +154,8 @@ mod tests {
             Soup {
                 name: "fedora/httpd".to_owned(),
                 version: "v1.6.2".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         )
     }
@@ -105,7 +164,7 @@ mod tests {
     #[test_case("FROM --platform=linux/x86_64 mcr.microsoft.com/dotnet/sdk:6.0")]
     #[test_case("FROM mcr.microsoft.com/dotnet/sdk:6.0 AS build-env")]
     fn hostname_image_name(input: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -115,7 +174,8 @@ mod tests {
             Soup {
                 name: "mcr.microsoft.com/dotnet/sdk".to_owned(),
                 version: "6.0".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         );
     }
@@ -124,7 +184,7 @@ mod tests {
     #[test_case("FROM --platform=linux/x86_64 mcr.microsoft.com:443/dotnet/sdk:6.0")]
     #[test_case("FROM mcr.microsoft.com:443/dotnet/sdk:6.0 AS build-env")]
     fn hostname_port_image_name(input: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -134,7 +194,8 @@ mod tests {
             Soup {
                 name: "mcr.microsoft.com:443/dotnet/sdk".to_owned(),
                 version: "6.0".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         );
     }
@@ -150,7 +211,7 @@ mod tests {
         "mcr.microsoft.com:443/dotnet/sdk"
     )]
     fn with_digest(input: &str, expected_name: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -160,7 +221,8 @@ mod tests {
             Soup {
                 name: expected_name.to_owned(),
                 version: "ca468b84b84846e84".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         );
     }
@@ -178,7 +240,7 @@ mod tests {
         "6.0"
     )]
     fn lower_case(input: &str, expected_name: &str, expected_version: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -188,15 +250,85 @@ mod tests {
             Soup {
                 name: expected_name.to_owned(),
                 version: expected_version.to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }
         );
     }
 
+    #[test_case("FROM postgres", "postgres", "latest")]
+    #[test_case(
+        "FROM mcr.microsoft.com:443/dotnet/sdk",
+        "mcr.microsoft.com:443/dotnet/sdk",
+        "latest"
+    )]
+    fn untagged_image(input: &str, expected_name: &str, expected_version: &str) {
+        let soups = DockerBase::default().soups(input, &Map::new()).unwrap();
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!(expected_name, soup.name);
+        assert_eq!(expected_version, soup.version);
+    }
+
+    #[test]
+    fn tag_with_digest() {
+        let soups = DockerBase::default()
+            .soups("FROM postgres:14.4@sha256:ca468b84b84846e84", &Map::new())
+            .unwrap();
+        let soup = soups.into_iter().next().unwrap();
+        assert_eq!("14.4", soup.version);
+        assert_eq!(
+            Some(&Value::String("sha256:ca468b84b84846e84".to_owned())),
+            soup.attributes.get("digest")
+        );
+    }
+
+    #[test]
+    fn substituted_arguments() {
+        let content = r#"
+ARG REGISTRY=mcr.microsoft.com
+ARG DOTNET_VERSION=6.0
+ARG RUNTIME_IMAGE
+FROM ${REGISTRY}/dotnet/sdk:${DOTNET_VERSION} AS build-env
+FROM ${RUNTIME_IMAGE:-mcr.microsoft.com/dotnet/aspnet}:$DOTNET_VERSION
+"#;
+        let build_args = HashMap::from([("DOTNET_VERSION".to_owned(), "8.0".to_owned())]);
+        let soups = DockerBase { build_args }
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    "mcr.microsoft.com/dotnet/aspnet".to_owned(),
+                    "8.0".to_owned()
+                ),
+                ("mcr.microsoft.com/dotnet/sdk".to_owned(), "8.0".to_owned()),
+            ],
+            soups
+        );
+    }
+
+    #[test]
+    fn internal_stages() {
+        let content = r#"
+FROM node:20.14 AS build
+FROM build AS test
+FROM BUILD
+FROM scratch
+COPY --from=build /app /app
+"#;
+        let soups = DockerBase::default().soups(content, &Map::new()).unwrap();
+        assert_eq!(1, soups.len());
+        assert_eq!("node", soups.into_iter().next().unwrap().name);
+    }
+
     #[test_case("COPY --chown app:app . ./")]
+    #[test_case("FROM ${BASE_IMAGE}:${TAG}")]
     #[test_case("")]
     fn no_from_statement(input: &str) {
-        let result = DockerBase {}.soups(input, &Map::new());
+        let result = DockerBase::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
@@ -0,0 +1,170 @@
+use super::{SoupParse, docker_base, dockerfile};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+const DEFAULT_DOCKERFILE: &str = "Dockerfile";
+
+lazy_static! {
+    /// `compose.yaml`, `docker-compose.yml` and variants such as `docker-compose.override.yml` or
+    /// `compose.prod.yaml`, as given to `docker compose -f`
+    static ref FILE_NAME: Regex =
+        Regex::new(r"^(?:docker-)?compose(?:[.\-][\w.\-]+)?\.ya?ml$").unwrap();
+}
+
+/// Parses the images of the services of a Compose file
+pub struct DockerCompose {}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    services: BTreeMap<String, Option<Service>>,
+}
+
+#[derive(Deserialize)]
+struct Service {
+    image: Option<String>,
+    build: Option<Build>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Build {
+    Context(String),
+    Details {
+        context: Option<String>,
+        dockerfile: Option<String>,
+    },
+}
+
+impl SoupParse for DockerCompose {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content = parse(content)?;
+        // Interpolation falls back to defaults, e.g. `postgres:${POSTGRES_VERSION:-14.4}`
+        let variables = HashMap::new();
+        Ok(content
+            .services
+            .values()
+            .flatten()
+            // The image of a service that is built names the built image rather than a base
+            .filter(|service| service.build.is_none())
+            .filter_map(|service| service.image.as_ref())
+            .filter_map(|image| {
+                let image = dockerfile::substitute(image, &variables);
+                docker_base::image_soup(&image, default_meta)
+            })
+            .collect())
+    }
+}
+
+pub fn is_compose_file(file_name: &str) -> bool {
+    FILE_NAME.is_match(file_name)
+}
+
+/// Dockerfiles that services of a Compose file are built from, relative to the Compose file
+pub fn dockerfiles(content: &str) -> Result<Vec<PathBuf>, SoupSourceParseError> {
+    let content = parse(content)?;
+    Ok(content
+        .services
+        .values()
+        .flatten()
+        .filter_map(|service| service.build.as_ref())
+        .map(|build| match build {
+            Build::Context(context) => PathBuf::from(context).join(DEFAULT_DOCKERFILE),
+            Build::Details {
+                context,
+                dockerfile,
+            } => PathBuf::from(context.as_deref().unwrap_or("."))
+                .join(dockerfile.as_deref().unwrap_or(DEFAULT_DOCKERFILE)),
+        })
+        .collect())
+}
+
+fn parse(content: &str) -> Result<Content, SoupSourceParseError> {
+    match serde_yaml_ng::from_str(content) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid Compose file ({})", e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CONTENT: &str = r#"
+services:
+  db:
+    image: postgres:${POSTGRES_VERSION:-14.4}
+  cache:
+    image: "redis@sha256:ca468b84b84846e84"
+  proxy:
+    image: nginx
+  api:
+    build:
+      context: ./api
+      dockerfile: api.dockerfile
+    image: my-org/api:dev
+  worker:
+    build: ./worker
+  placeholder:
+"#;
+
+    #[test]
+    fn service_images() {
+        let soups = DockerCompose {}
+            .soups(CONTENT, &Map::new())
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("nginx".to_owned(), "latest".to_owned()),
+                ("postgres".to_owned(), "14.4".to_owned()),
+                ("redis".to_owned(), "sha256:ca468b84b84846e84".to_owned()),
+            ],
+            soups
+        );
+    }
+
+    #[test]
+    fn built_dockerfiles() {
+        assert_eq!(
+            vec![
+                PathBuf::from("./api/api.dockerfile"),
+                PathBuf::from("./worker/Dockerfile"),
+            ],
+            dockerfiles(CONTENT).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_content() {
+        assert_eq!(
+            true,
+            DockerCompose {}.soups("services: [", &Map::new()).is_err()
+        );
+    }
+
+    #[test_case("docker-compose.yml", true)]
+    #[test_case("docker-compose.override.yaml", true)]
+    #[test_case("compose.yaml", true)]
+    #[test_case("compose.prod.yml", true)]
+    #[test_case("docker-compose-dev.yml", true)]
+    #[test_case("composer.json", false)]
+    #[test_case("composer.yml", false)]
+    fn compose_file_names(file_name: &str, expected: bool) {
+        assert_eq!(expected, is_compose_file(file_name));
+    }
+}
@@ -0,0 +1,579 @@
+use super::{SoupParse, dockerfile, shell};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Parses artifacts that Dockerfiles download with `ADD <url>`, `curl` or `wget`
+#[derive(Default)]
+pub struct DockerDownload {
+    /// Values of build arguments, overriding the defaults of `ARG` instructions
+    pub build_args: HashMap<String, String>,
+}
+
+static SCHEMES: [&str; 3] = ["https://", "http://", "ftp://"];
+static CURL_OPTIONS_WITH_VALUE: [&str; 20] = [
+    "-o",
+    "--output",
+    "-H",
+    "--header",
+    "-u",
+    "--user",
+    "-d",
+    "--data",
+    "-x",
+    "--proxy",
+    "-A",
+    "--user-agent",
+    "-e",
+    "--referer",
+    "-w",
+    "--write-out",
+    "-m",
+    "--max-time",
+    "--retry",
+    "--connect-timeout",
+];
+static WGET_OPTIONS_WITH_VALUE: [&str; 12] = [
+    "-O",
+    "--output-document",
+    "-P",
+    "--directory-prefix",
+    "-o",
+    "--output-file",
+    "--header",
+    "-U",
+    "--user-agent",
+    "-T",
+    "--timeout",
+    "--tries",
+];
+static CHECKSUM_PROGRAMS: [(&str, &str); 4] = [
+    ("sha256sum", "sha256"),
+    ("sha512sum", "sha512"),
+    ("sha1sum", "sha1"),
+    ("md5sum", "md5"),
+];
+/// Extensions of signature files, which are downloaded to verify but are not software themselves
+static SIGNATURE_EXTENSIONS: [&str; 3] = [".asc", ".sig", ".gpg"];
+static ARCHIVE_EXTENSIONS: [&str; 21] = [
+    ".tar.gz", ".tar.xz", ".tar.bz2", ".tar.zst", ".tgz", ".txz", ".tar", ".zip", ".gz", ".xz",
+    ".bz2", ".deb", ".rpm", ".apk", ".jar", ".war", ".whl", ".sh", ".run", ".exe", ".msi",
+];
+/// Path segments of download URLs that don't name the artifact, e.g. of GitHub archives
+static GENERIC_SEGMENTS: [&str; 7] = [
+    "archive", "refs", "tags", "heads", "download", "releases", "latest",
+];
+lazy_static! {
+    /// File names with a version, e.g. `node-v20.14.0-linux-x64` or `terraform_1.8.5_linux_amd64`
+    static ref FILE_VERSION: Regex = Regex::new(
+        r"^(?P<name>[A-Za-z0-9].*?)[-_.](?P<version>v?\d+(?:\.\d+)+(?:-(?:rc|alpha|beta)[.\d]*)?)(?:[-_.+]|$)"
+    )
+    .unwrap();
+    static ref VERSION: Regex = Regex::new(r"^v?\d+(?:\.\d+)+$").unwrap();
+}
+
+/// A download found in an instruction, with the file it is saved as
+struct Download {
+    url: String,
+    file: String,
+    checksum: Option<String>,
+    checksum_source: Option<String>,
+}
+
+impl SoupParse for DockerDownload {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut scope = dockerfile::Scope::new(&self.build_args);
+        let mut downloads: Vec<Download> = Vec::new();
+        for instruction in dockerfile::instructions(content) {
+            match instruction.keyword.as_str() {
+                "ADD" => downloads.append(&mut added(&scope.substitute(&instruction))),
+                "RUN" => downloads.append(&mut fetched(&scope.substitute(&instruction))),
+                _ => scope.apply(&instruction),
+            }
+        }
+
+        Ok(downloads
+            .into_iter()
+            .map(|download| {
+                let (name, version) = name_and_version(&download.url);
+                let mut attributes = Map::new();
+                attributes.insert("source".to_owned(), Value::String(download.url));
+                if let Some(checksum) = download.checksum {
+                    attributes.insert("checksum".to_owned(), Value::String(checksum));
+                }
+                if let Some(checksum_source) = download.checksum_source {
+                    attributes.insert("checksum_source".to_owned(), Value::String(checksum_source));
+                }
+                Soup {
+                    name,
+                    version,
+                    ecosystem: None,
+                    scope: None,
+                    attributes,
+                    meta: default_meta.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Remote sources of an `ADD` instruction, verified with `--checksum=<algorithm>:<hash>` if given
+fn added(arguments: &str) -> Vec<Download> {
+    let words = arguments.split_whitespace().collect::<Vec<&str>>();
+    let checksum = words
+        .iter()
+        .find_map(|word| word.strip_prefix("--checksum="))
+        .map(|checksum| checksum.to_owned());
+    let paths = words
+        .iter()
+        .filter(|word| !word.starts_with("--"))
+        .collect::<Vec<_>>();
+    // The last path is the destination
+    paths
+        .iter()
+        .take(paths.len().saturating_sub(1))
+        .filter(|path| is_url(path))
+        .map(|url| Download {
+            url: url.to_string(),
+            file: file_name(url).to_owned(),
+            checksum: checksum.to_owned(),
+            checksum_source: None,
+        })
+        .collect()
+}
+
+/// URLs fetched by `curl` or `wget` in a `RUN` instruction, along with checksums of the
+/// artifacts verified with e.g. `echo "<hash>  <file>" | sha256sum -c` or `sha256sum -c <file>`
+fn fetched(script: &str) -> Vec<Download> {
+    let commands = shell::commands(script);
+    let mut downloads: Vec<Download> = Vec::new();
+    // Hashes of files as listed for `sha256sum -c`, and files listing such hashes
+    let mut checksums: Vec<(String, String)> = Vec::new();
+    let mut checksum_files: Vec<String> = Vec::new();
+    for (index, command) in commands.iter().enumerate() {
+        let program = command
+            .iter()
+            .position(|word| is_download_program(shell::program_name(word)));
+        if let Some(program) = program {
+            let is_curl = shell::program_name(&command[program]) == "curl";
+            let (options, output_options): (&[&str], &[&str]) = match is_curl {
+                true => (&CURL_OPTIONS_WITH_VALUE, &["-o", "--output"]),
+                false => (&WGET_OPTIONS_WITH_VALUE, &["-O", "--output-document"]),
+            };
+            let words = &command[program + 1..];
+            let output = option_value(words, output_options).filter(|output| *output != "-");
+            for url in shell::operands(words, options) {
+                if !is_url(url) {
+                    continue;
+                }
+                downloads.push(Download {
+                    url: url.to_owned(),
+                    file: output.unwrap_or(file_name(url)).to_owned(),
+                    checksum: None,
+                    checksum_source: None,
+                });
+            }
+            continue;
+        }
+
+        let algorithm = command.iter().find_map(|word| {
+            CHECKSUM_PROGRAMS
+                .iter()
+                .find(|(program, _)| *program == shell::program_name(word))
+                .map(|(_, algorithm)| *algorithm)
+        });
+        let algorithm = match algorithm {
+            Some(algorithm) if command.iter().any(|word| word == "-c" || word == "--check") => {
+                algorithm
+            }
+            _ => continue,
+        };
+        let files = command
+            .iter()
+            .skip(1)
+            .filter(|word| !word.starts_with('-'))
+            .collect::<Vec<_>>();
+        match files.first() {
+            Some(file) => checksum_files.push(file_name(file).to_owned()),
+            // Hashes are piped from the preceding command, e.g. `echo "<hash>  <file>"`
+            None => {
+                if let Some(previous) = index.checked_sub(1).map(|index| &commands[index]) {
+                    if previous
+                        .first()
+                        .is_some_and(|word| word == "echo" || word == "printf")
+                    {
+                        for line in previous[1..].join(" ").lines() {
+                            if let Some((hash, file)) = line.trim().split_once(char::is_whitespace)
+                            {
+                                let file = file.trim().trim_start_matches('*');
+                                checksums.push((
+                                    file_name(file).to_owned(),
+                                    format!("{}:{}", algorithm, hash),
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for (file, checksum) in checksums {
+        if let Some(download) = downloads
+            .iter_mut()
+            .find(|download| download_name(download) == file)
+        {
+            download.checksum = Some(checksum);
+        }
+    }
+    for checksum_file in checksum_files {
+        let checksum_url = downloads
+            .iter()
+            .find(|download| download_name(download) == checksum_file)
+            .map(|download| download.url.to_owned());
+        downloads.retain(|download| download_name(download) != checksum_file);
+        let verified = downloads
+            .iter_mut()
+            .filter(|download| is_verified_by(download, &checksum_file, checksum_url.as_deref()));
+        for download in verified {
+            download.checksum_source =
+                Some(checksum_url.to_owned().unwrap_or(checksum_file.to_owned()));
+        }
+    }
+    downloads.retain(|download| {
+        !SIGNATURE_EXTENSIONS
+            .iter()
+            .any(|extension| download.file.ends_with(extension))
+    });
+    downloads
+}
+
+fn is_download_program(program: &str) -> bool {
+    program == "curl" || program == "wget"
+}
+
+fn is_url(word: &str) -> bool {
+    SCHEMES.iter().any(|scheme| word.starts_with(scheme))
+}
+
+/// Value of the first of the given options, as a separate word or following a `=`
+fn option_value<'a>(words: &'a [String], options: &[&str]) -> Option<&'a str> {
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if options.contains(&word.as_str()) {
+            return words.next().map(|value| value.as_str());
+        }
+        if let Some(value) = options.iter().find_map(|option| {
+            word.strip_prefix(option)
+                .and_then(|value| value.strip_prefix('='))
+        }) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Whether a checksum file lists the hash of the given download, i.e. is named after it, e.g.
+/// `tool-1.2.3.tar.gz.sha256`, or lists the hashes of a release, e.g. `SHA256SUMS`, and was
+/// downloaded from the same directory
+fn is_verified_by(download: &Download, checksum_file: &str, checksum_url: Option<&str>) -> bool {
+    if checksum_file
+        .strip_prefix(download_name(download))
+        .is_some_and(|extension| extension.starts_with('.'))
+    {
+        return true;
+    }
+    checksum_file.to_lowercase().contains("sums")
+        && checksum_url.is_some_and(|url| directory(url) == directory(&download.url))
+}
+
+/// URL without its last segment, query or fragment
+fn directory(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    url.rsplit_once('/').map_or(url, |(directory, _)| directory)
+}
+
+fn download_name(download: &Download) -> &str {
+    file_name(&download.file)
+}
+
+/// Last segment of a path or URL, without query or fragment
+fn file_name(path: &str) -> &str {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Name and version of a downloaded artifact, derived from the file name of its URL, e.g.
+/// `node` and `v20.14.0` for `https://nodejs.org/dist/v20.14.0/node-v20.14.0-linux-x64.tar.xz`,
+/// or from a version in its path, e.g. `kubectl` and `v1.30.1` for
+/// `https://dl.k8s.io/release/v1.30.1/bin/linux/amd64/kubectl`
+pub fn name_and_version(url: &str) -> (String, String) {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(without_scheme);
+    let segments = path
+        .split('/')
+        .skip(1)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+    let file = segments.last().copied().unwrap_or("");
+    let stem = ARCHIVE_EXTENSIONS
+        .iter()
+        .find_map(|extension| file.strip_suffix(extension))
+        .unwrap_or(file);
+
+    if let Some(captures) = FILE_VERSION
+        .captures(stem)
+        .filter(|_| !VERSION.is_match(stem))
+    {
+        return (captures["name"].to_owned(), captures["version"].to_owned());
+    }
+    // Otherwise the version may be found in a directory, e.g. `v1.30.1` or `jq-1.7.1`
+    let directory_version = segments.iter().rev().skip(1).find_map(|segment| {
+        match (VERSION.is_match(segment), FILE_VERSION.captures(segment)) {
+            (true, _) => Some((None, segment.to_string())),
+            (false, Some(captures)) => Some((
+                Some(captures.name("name")?.as_str()),
+                captures["version"].to_owned(),
+            )),
+            _ => None,
+        }
+    });
+    let (directory_name, version) = match directory_version {
+        Some((name, version)) => (name, Some(version)),
+        None => (None, None),
+    };
+    let name = match (VERSION.is_match(stem), directory_name) {
+        // Archives named after a version only, e.g. `v1.2.3.tar.gz`, are named after their
+        // project
+        (true, _) => segments
+            .iter()
+            .rev()
+            .skip(1)
+            .find(|segment| !GENERIC_SEGMENTS.contains(segment) && !VERSION.is_match(segment))
+            .copied(),
+        (false, Some(name)) if stem.starts_with(name) => Some(name),
+        (false, _) => Some(stem).filter(|stem| !stem.is_empty()),
+    };
+    (
+        name.unwrap_or(path.trim_end_matches('/')).to_owned(),
+        version.unwrap_or(match VERSION.is_match(stem) {
+            true => stem.to_owned(),
+            false => "unknown".to_owned(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn downloads(input: &str) -> Vec<(String, String, Map<String, Value>)> {
+        DockerDownload::default()
+            .soups(input, &Map::new())
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version, soup.attributes))
+            .collect()
+    }
+
+    fn attributes(attributes: &[(&str, &str)]) -> Map<String, Value> {
+        attributes
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+            .collect()
+    }
+
+    #[test_case(
+        "https://nodejs.org/dist/v20.14.0/node-v20.14.0-linux-x64.tar.xz",
+        "node",
+        "v20.14.0"
+    )]
+    #[test_case(
+        "https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_linux_amd64.zip",
+        "terraform",
+        "1.8.5"
+    )]
+    #[test_case(
+        "https://dl.k8s.io/release/v1.30.1/bin/linux/amd64/kubectl",
+        "kubectl",
+        "v1.30.1"
+    )]
+    #[test_case(
+        "https://github.com/owner/toolbox/archive/refs/tags/v1.2.3.tar.gz",
+        "toolbox",
+        "v1.2.3"
+    )]
+    #[test_case(
+        "https://downloads.apache.org/maven/maven-3/3.9.7/binaries/apache-maven-3.9.7-bin.tar.gz",
+        "apache-maven",
+        "3.9.7"
+    )]
+    #[test_case(
+        "https://github.com/jqlang/jq/releases/download/jq-1.7.1/jq-linux-amd64",
+        "jq",
+        "1.7.1"
+    )]
+    #[test_case("https://get.docker.com/", "get.docker.com", "unknown")]
+    fn derived_name_and_version(url: &str, name: &str, version: &str) {
+        assert_eq!((name.to_owned(), version.to_owned()), name_and_version(url));
+    }
+
+    #[test]
+    fn add_with_checksum() {
+        let content = r#"
+ARG JQ_VERSION=1.7.1
+ADD --checksum=sha256:5942c9b0934e510ee61eb3e30273f1b3fe2590df93933a93d7c58b81d19c8ff5 \
+    https://github.com/jqlang/jq/releases/download/jq-${JQ_VERSION}/jq-linux-amd64 /usr/local/bin/jq
+ADD ./local.tar.gz /opt/
+"#;
+        assert_eq!(
+            vec![(
+                "jq".to_owned(),
+                "1.7.1".to_owned(),
+                attributes(&[
+                    (
+                        "source",
+                        "https://github.com/jqlang/jq/releases/download/jq-1.7.1/jq-linux-amd64"
+                    ),
+                    (
+                        "checksum",
+                        "sha256:5942c9b0934e510ee61eb3e30273f1b3fe2590df93933a93d7c58b81d19c8ff5"
+                    ),
+                ])
+            )],
+            downloads(content)
+        );
+    }
+
+    #[test]
+    fn curl_with_piped_checksum() {
+        let content = r#"
+ARG HELM_VERSION=v3.15.1
+ENV HELM_SHA256=9d1c8e3b7e1e0a3f6b3dcd6a3f1b1f8f6bd0f0b1c1b4f4c9a7f1f4d0bb7b3a7b
+RUN curl -fsSL -o /tmp/helm.tar.gz https://get.helm.sh/helm-${HELM_VERSION}-linux-amd64.tar.gz \
+    && echo "${HELM_SHA256}  /tmp/helm.tar.gz" | sha256sum -c - \
+    && tar -xzf /tmp/helm.tar.gz -C /usr/local/bin
+"#;
+        assert_eq!(
+            vec![(
+                "helm".to_owned(),
+                "v3.15.1".to_owned(),
+                attributes(&[
+                    (
+                        "source",
+                        "https://get.helm.sh/helm-v3.15.1-linux-amd64.tar.gz"
+                    ),
+                    (
+                        "checksum",
+                        "sha256:9d1c8e3b7e1e0a3f6b3dcd6a3f1b1f8f6bd0f0b1c1b4f4c9a7f1f4d0bb7b3a7b"
+                    ),
+                ])
+            )],
+            downloads(content)
+        );
+    }
+
+    #[test]
+    fn wget_with_checksum_file() {
+        let content = r#"
+RUN wget -q https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_linux_amd64.zip \
+    && wget -q https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_SHA256SUMS \
+    && wget -q https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_SHA256SUMS.sig \
+    && sha256sum --ignore-missing -c terraform_1.8.5_SHA256SUMS
+"#;
+        assert_eq!(
+            vec![(
+                "terraform".to_owned(),
+                "1.8.5".to_owned(),
+                attributes(&[
+                    (
+                        "source",
+                        "https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_linux_amd64.zip"
+                    ),
+                    (
+                        "checksum_source",
+                        "https://releases.hashicorp.com/terraform/1.8.5/terraform_1.8.5_SHA256SUMS"
+                    ),
+                ])
+            )],
+            downloads(content)
+        );
+    }
+
+    #[test]
+    fn checksum_file_of_other_download() {
+        let content = r#"
+RUN curl -LO https://example.org/tool/1.2.3/tool-1.2.3.tar.gz \
+    && curl -LO https://example.org/tool/1.2.3/tool-1.2.3.tar.gz.sha256 \
+    && sha256sum -c tool-1.2.3.tar.gz.sha256 \
+    && curl -fsSL https://get.other.sh/install-2.0.0.sh | sh
+"#;
+        assert_eq!(
+            vec![
+                (
+                    "install".to_owned(),
+                    "2.0.0".to_owned(),
+                    attributes(&[("source", "https://get.other.sh/install-2.0.0.sh")])
+                ),
+                (
+                    "tool".to_owned(),
+                    "1.2.3".to_owned(),
+                    attributes(&[
+                        ("source", "https://example.org/tool/1.2.3/tool-1.2.3.tar.gz"),
+                        (
+                            "checksum_source",
+                            "https://example.org/tool/1.2.3/tool-1.2.3.tar.gz.sha256"
+                        ),
+                    ])
+                ),
+            ],
+            downloads(content)
+        );
+    }
+
+    #[test]
+    fn piped_checksum_of_other_file() {
+        let content = r#"
+RUN curl -fsSLO https://example.org/a-1.0.0.tar.gz \
+    && echo "9d1c8e3b7e1e0a3f6b3dcd6a3f1b1f8f6bd0f0b1c1b4f4c9a7f1f4d0bb7b3a7b  b-2.0.0.tar.gz" | sha256sum -c -
+"#;
+        assert_eq!(
+            vec![(
+                "a".to_owned(),
+                "1.0.0".to_owned(),
+                attributes(&[("source", "https://example.org/a-1.0.0.tar.gz")])
+            )],
+            downloads(content)
+        );
+    }
+
+    #[test]
+    fn curl_piped_to_shell() {
+        let content = "RUN curl -fsSL https://deb.nodesource.com/setup_20.x | bash -";
+        assert_eq!(
+            vec![(
+                "setup_20.x".to_owned(),
+                "unknown".to_owned(),
+                attributes(&[("source", "https://deb.nodesource.com/setup_20.x")])
+            )],
+            downloads(content)
+        );
+    }
+
+    #[test_case("RUN curl --version")]
+    #[test_case("ADD https://example.org/tool.tar.gz")]
+    #[test_case("COPY https://example.org/tool.tar.gz /opt/")]
+    fn no_download(input: &str) {
+        assert_eq!(0, downloads(input).len());
+    }
+}
@@ -0,0 +1,246 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref COMMENT_LINE: Regex = Regex::new(r"^[ \t]*#").unwrap();
+    static ref VARIABLE: Regex = Regex::new(
+        r"\$(?:\{(?P<braced>[A-Za-z_][A-Za-z0-9_]*)(?::?-(?P<default>[^}]*))?\}|(?P<name>[A-Za-z_][A-Za-z0-9_]*))"
+    )
+    .unwrap();
+}
+
+/// An instruction of a Dockerfile, such as `RUN apt-get update`
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    /// Upper case keyword of the instruction, e.g. `RUN`
+    pub keyword: String,
+    /// Arguments of the instruction, with line continuations joined
+    pub arguments: String,
+}
+
+/// Splits a Dockerfile into its instructions, joining line continuations and skipping comments
+pub fn instructions(content: &str) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        if COMMENT_LINE.is_match(line) {
+            continue;
+        }
+        let line = line.trim_end();
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line);
+                if let Some(instruction) = instruction(&current) {
+                    instructions.push(instruction);
+                }
+                current.clear();
+            }
+        }
+    }
+    if let Some(instruction) = instruction(&current) {
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+fn instruction(line: &str) -> Option<Instruction> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (keyword, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    Some(Instruction {
+        keyword: keyword.to_ascii_uppercase(),
+        arguments: arguments.trim().to_owned(),
+    })
+}
+
+/// Variables in scope while walking through the instructions of a Dockerfile. `ARG`s declared
+/// before the first `FROM` are only in scope of `FROM` instructions, unless declared again within
+/// a stage, and take the value of a build argument of the same name if given.
+pub struct Scope<'a> {
+    build_args: &'a HashMap<String, String>,
+    global: HashMap<String, String>,
+    stage: Option<HashMap<String, String>>,
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(build_args: &'a HashMap<String, String>) -> Scope<'a> {
+        Scope {
+            build_args,
+            global: HashMap::new(),
+            stage: None,
+        }
+    }
+
+    /// Declares the variables of an `ARG` or `ENV` instruction, or starts a new stage on `FROM`
+    pub fn apply(&mut self, instruction: &Instruction) {
+        if instruction.keyword == "FROM" {
+            self.stage = Some(HashMap::new());
+            return;
+        }
+        if instruction.keyword != "ARG" && instruction.keyword != "ENV" {
+            return;
+        }
+        for (name, value) in declarations(instruction) {
+            let value = match (instruction.keyword.as_str(), value) {
+                ("ARG", value) => match self.build_args.get(&name) {
+                    Some(build_arg) => Some(build_arg.to_owned()),
+                    None => value
+                        .map(|value| substitute(&value, self.variables()))
+                        .or_else(|| match self.stage {
+                            Some(_) => self.global.get(&name).cloned(),
+                            None => None,
+                        }),
+                },
+                (_, value) => value.map(|value| substitute(&value, self.variables())),
+            };
+            if let Some(value) = value {
+                match &mut self.stage {
+                    Some(stage) => stage.insert(name, value),
+                    None => self.global.insert(name, value),
+                };
+            }
+        }
+    }
+
+    /// Arguments of an instruction with the variables in its scope substituted
+    pub fn substitute(&self, instruction: &Instruction) -> String {
+        match instruction.keyword.as_str() {
+            "FROM" => substitute(&instruction.arguments, &self.global),
+            _ => substitute(&instruction.arguments, self.variables()),
+        }
+    }
+
+    fn variables(&self) -> &HashMap<String, String> {
+        self.stage.as_ref().unwrap_or(&self.global)
+    }
+}
+
+/// Declarations of an `ARG` or `ENV` instruction, e.g. `VERSION=1.0` or `VERSION 1.0`, where
+/// declarations without value have none
+fn declarations(instruction: &Instruction) -> Vec<(String, Option<String>)> {
+    let words = instruction
+        .arguments
+        .split_whitespace()
+        .collect::<Vec<&str>>();
+    // The legacy `ENV KEY value` form sets a single variable to the rest of the line
+    if instruction.keyword == "ENV" && words.len() > 1 && !words[0].contains('=') {
+        let value = instruction.arguments[words[0].len()..].trim();
+        return vec![(words[0].to_owned(), Some(unquote(value).to_owned()))];
+    }
+    words
+        .iter()
+        .map(|word| match word.split_once('=') {
+            Some((name, value)) => (name.to_owned(), Some(unquote(value).to_owned())),
+            None => (word.to_string(), None),
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+}
+
+/// Substitutes `$NAME`, `${NAME}` and `${NAME:-default}` with the values of the given variables,
+/// leaving references to unknown variables without default as they are
+pub fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    VARIABLE
+        .replace_all(text, |captures: &Captures| {
+            let name = match captures.name("braced") {
+                Some(name) => name.as_str(),
+                None => &captures["name"],
+            };
+            match (variables.get(name), captures.name("default")) {
+                (Some(value), _) => value.to_owned(),
+                (None, Some(default)) => default.as_str().to_owned(),
+                (None, None) => captures[0].to_owned(),
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn joined_instructions() {
+        let content = "# syntax=docker/dockerfile:1\nFROM alpine:3.20\nrun apk add \\\n    # Some comment\n    curl \\\n    git\n";
+        assert_eq!(
+            vec![
+                Instruction {
+                    keyword: "FROM".to_owned(),
+                    arguments: "alpine:3.20".to_owned(),
+                },
+                Instruction {
+                    keyword: "RUN".to_owned(),
+                    arguments: "apk add      curl      git".to_owned(),
+                },
+            ],
+            instructions(content)
+        );
+    }
+
+    #[test_case("ARG VERSION=1.0 CHECKSUM", &[("VERSION", Some("1.0")), ("CHECKSUM", None)])]
+    #[test_case("ENV VERSION=\"1.0\" HOME=/app", &[("VERSION", Some("1.0")), ("HOME", Some("/app"))])]
+    #[test_case("ENV VERSION 1.0", &[("VERSION", Some("1.0"))])]
+    fn declared_variables(input: &str, expected: &[(&str, Option<&str>)]) {
+        let instruction = instructions(input).remove(0);
+        let expected = expected
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.map(|value| value.to_owned())))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, declarations(&instruction));
+    }
+
+    #[test_case("tool-$VERSION.tar.gz", "tool-1.0.tar.gz" ; "plain")]
+    #[test_case("tool-${VERSION}.tar.gz", "tool-1.0.tar.gz" ; "braced")]
+    #[test_case("${REGISTRY:-docker.io}/tool", "docker.io/tool" ; "default")]
+    #[test_case("${ARCH}/tool", "${ARCH}/tool" ; "unknown")]
+    fn substituted_variables(input: &str, expected: &str) {
+        let variables = HashMap::from([("VERSION".to_owned(), "1.0".to_owned())]);
+        assert_eq!(expected, substitute(input, &variables));
+    }
+
+    #[test]
+    fn scoped_variables() {
+        let content = r#"
+ARG BASE=alpine
+ARG TAG=3.19
+ARG VERSION=1.0
+FROM ${BASE}:${TAG}
+RUN echo $BASE $VERSION
+ARG VERSION
+ENV HOME=/opt/$VERSION
+RUN echo $VERSION $HOME
+"#;
+        let build_args = HashMap::from([("TAG".to_owned(), "3.20".to_owned())]);
+        let mut scope = Scope::new(&build_args);
+        let mut substituted = Vec::new();
+        for instruction in instructions(content) {
+            if instruction.keyword != "ARG" && instruction.keyword != "ENV" {
+                substituted.push(scope.substitute(&instruction));
+            }
+            scope.apply(&instruction);
+        }
+        assert_eq!(
+            vec!["alpine:3.20", "echo $BASE $VERSION", "echo 1.0 /opt/1.0"],
+            substituted
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use super::{SoupParse, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses `gem install` commands
+pub struct Gem {}
+
+static VERSION_OPTIONS: [&str; 2] = ["-v", "--version"];
+static OPTIONS_WITH_VALUE: [&str; 12] = [
+    "-v",
+    "--version",
+    "-i",
+    "--install-dir",
+    "-n",
+    "--bindir",
+    "-s",
+    "--source",
+    "--platform",
+    "-P",
+    "--trust-policy",
+    "--build-root",
+];
+
+impl SoupParse for Gem {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for command in shell::commands(content) {
+            let words = match shell::subcommand_words(
+                &command,
+                |program| program == "gem",
+                &["install", "i"],
+                &OPTIONS_WITH_VALUE,
+            ) {
+                Some(words) => words,
+                None => continue,
+            };
+            // A version given with `--version` applies to the gems of the command that don't
+            // specify one as `name:version`
+            let version = version_option(words);
+            for gem in shell::operands(words, &OPTIONS_WITH_VALUE) {
+                if gem.ends_with(".gem") || gem.contains('/') {
+                    continue;
+                }
+                let (name, gem_version) = match gem.split_once(':') {
+                    Some((name, version)) => (name, Some(version)),
+                    None => (gem.as_str(), None),
+                };
+                result.insert(Soup {
+                    name: name.to_owned(),
+                    version: gem_version.or(version).unwrap_or("unknown").to_owned(),
+                    ecosystem: Some(Ecosystem::Gem),
+                    meta: default_meta.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn version_option(words: &[String]) -> Option<&str> {
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if VERSION_OPTIONS.contains(&word.as_str()) {
+            return words.next().map(|version| version.as_str());
+        }
+        if let Some(version) = word.strip_prefix("--version=") {
+            return Some(version);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+    use test_case::test_case;
+
+    #[test_case("gem install rails -v 7.1.3", &[("rails", "7.1.3")])]
+    #[test_case("RUN gem install --no-document bundler --version '~> 2.5'", &[("bundler", "~> 2.5")])]
+    #[test_case("gem install --version=1.16.6 nokogiri", &[("nokogiri", "1.16.6")])]
+    #[test_case("gem install rake:13.2.1 rubocop", &[("rake", "13.2.1"), ("rubocop", "unknown")])]
+    #[test_case("gem install ./pkg/tool-1.0.gem", &[])]
+    fn gems(input: &str, expected: &[(&str, &str)]) {
+        let result = Gem {}.soups(input, &Map::new()).unwrap();
+        assert_eq!(expected, versions(&result));
+    }
+
+    #[test_case("gem update --system")]
+    #[test_case("gem list")]
+    fn no_install_statement(input: &str) {
+        assert_eq!(0, Gem {}.soups(input, &Map::new()).unwrap().len());
+    }
+}
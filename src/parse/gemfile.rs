@@ -0,0 +1,449 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const DEV_SCOPE: &str = "dev";
+/// Groups of gems that are only needed during development
+const DEV_GROUPS: [&str; 2] = ["development", "test"];
+
+lazy_static! {
+    static ref GEM: Regex =
+        Regex::new(r#"^gem[\s(]+["'](?P<name>[^"']+)["']\s*(?P<rest>.*)$"#).unwrap();
+    static ref GROUP: Regex = Regex::new(r"^group[\s(]+(?P<groups>[^)]*?)\)?\s+do\b").unwrap();
+    static ref GROUP_OPTION: Regex =
+        Regex::new(r#"\bgroups?(?::|\s*=>)\s*(?P<groups>\[[^\]]*\]|:\w+|["']\w+["'])"#).unwrap();
+    static ref QUOTED: Regex = Regex::new(r#"^["']([^"']*)["']$"#).unwrap();
+    static ref SYMBOL: Regex = Regex::new(r#":(\w+)|["'](\w+)["']"#).unwrap();
+    static ref OPTION: Regex = Regex::new(r"^:?(?P<key>\w+)(?::|\s*=>)\s*(?P<value>.+)$").unwrap();
+    static ref SPEC: Regex = Regex::new(r"^    (?P<name>[^\s(]+) \((?P<version>[^)]+)\)$").unwrap();
+    static ref CHECKSUM: Regex =
+        Regex::new(r"^  (?P<name>[^\s(]+) \((?P<version>[^)]+)\) (?P<checksum>\S+)").unwrap();
+}
+
+/// Parses the gems of a Gemfile, with versions resolved in the Gemfile.lock next to it
+#[derive(Default)]
+pub struct Gemfile {
+    /// Content of the Gemfile.lock next to the Gemfile
+    pub lockfile: Option<String>,
+    /// Whether to include gems that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+struct Dependency {
+    name: String,
+    requirement: Option<String>,
+    scope: &'static str,
+    source: Option<String>,
+}
+
+struct LockedGem {
+    name: String,
+    version: String,
+    platform: Option<String>,
+    /// Repository of gems from git, which are sourced at a revision
+    git: Option<(String, String)>,
+    /// Whether the gem is within the repository
+    is_path: bool,
+    checksum: Option<String>,
+}
+
+impl SoupParse for Gemfile {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let dependencies = dependencies(content);
+        let locked = match &self.lockfile {
+            Some(lockfile) => locked_gems(lockfile)?,
+            None => Vec::new(),
+        };
+        let mut soups = BTreeSet::new();
+        let mut visited = HashSet::new();
+        for dependency in dependencies {
+            let mut attributes = Map::new();
+            if let Some(source) = dependency.source {
+                attributes.insert("source".to_owned(), Value::String(source));
+            }
+            let locked_gem = locked.iter().find(|gem| gem.name == dependency.name);
+            let version = match locked_gem {
+                Some(gem) => {
+                    if let Some(requirement) = dependency.requirement {
+                        attributes.insert("requirement".to_owned(), Value::String(requirement));
+                    }
+                    insert_lock_attributes(&mut attributes, gem);
+                    gem.version.to_owned()
+                }
+                None => dependency.requirement.unwrap_or("unknown".to_owned()),
+            };
+            visited.insert(dependency.name.to_owned());
+            soups.insert(Soup {
+                name: dependency.name,
+                version,
+                ecosystem: Some(Ecosystem::Gem),
+                scope: Some(dependency.scope.to_owned()),
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+
+        // Gemfile.lock does not record the groups of gems, so the gems that dependencies depend
+        // on are added as runtime dependencies
+        if self.include_transitive {
+            for gem in locked.iter().filter(|gem| !gem.is_path) {
+                if !visited.insert(gem.name.to_owned()) {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                insert_lock_attributes(&mut attributes, gem);
+                soups.insert(Soup {
+                    name: gem.name.to_owned(),
+                    version: gem.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Gem),
+                    scope: Some(RUNTIME_SCOPE.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, gem: &LockedGem) {
+    if let Some((remote, revision)) = &gem.git {
+        attributes.insert("source".to_owned(), Value::String(remote.to_owned()));
+        attributes.insert("revision".to_owned(), Value::String(revision.to_owned()));
+    }
+    if let Some(platform) = &gem.platform {
+        attributes.insert("platform".to_owned(), Value::String(platform.to_owned()));
+    }
+    if let Some(checksum) = &gem.checksum {
+        attributes.insert("checksum".to_owned(), Value::String(checksum.to_owned()));
+    }
+}
+
+/// Gems declared in a Gemfile, in the scope of the groups they belong to, either by a
+/// `group :test do` block or a `group: :test` option. Gems within the repository, i.e. with a
+/// `path`, are no SOUP of their own.
+fn dependencies(content: &str) -> Vec<Dependency> {
+    // Groups of the blocks that gems are declared in, where blocks other than groups have none
+    let mut blocks: Vec<Option<Vec<String>>> = Vec::new();
+    let mut dependencies = Vec::new();
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or(line).trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == "end" {
+            blocks.pop();
+            continue;
+        }
+        if let Some(captures) = GROUP.captures(line) {
+            blocks.push(Some(symbols(&captures["groups"])));
+            continue;
+        }
+        let captures = match GEM.captures(line) {
+            Some(captures) => captures,
+            None => {
+                let is_block = line.ends_with(" do")
+                    || line.contains(" do |")
+                    || line.starts_with("if ")
+                    || line.starts_with("unless ");
+                if is_block {
+                    blocks.push(None);
+                }
+                continue;
+            }
+        };
+        let rest = &captures["rest"];
+        let mut requirements = Vec::new();
+        let mut groups = blocks
+            .iter()
+            .flatten()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        if let Some(option) = GROUP_OPTION.captures(rest) {
+            groups.extend(symbols(&option["groups"]));
+        }
+        let mut source = None;
+        let mut is_path = false;
+        for argument in rest.trim_end_matches(')').split(',') {
+            let argument = argument.trim();
+            if let Some(requirement) = QUOTED.captures(argument) {
+                requirements.push(requirement[1].to_owned());
+                continue;
+            }
+            let option = match OPTION.captures(argument) {
+                Some(option) => option,
+                None => continue,
+            };
+            let value = option["value"].trim_matches(['"', '\'']).to_owned();
+            match &option["key"] {
+                "git" => source = Some(value),
+                "github" => source = Some(format!("https://github.com/{}", value)),
+                "path" => is_path = true,
+                _ => {}
+            }
+        }
+        if is_path {
+            continue;
+        }
+        let is_dev = !groups.is_empty()
+            && groups
+                .iter()
+                .all(|group| DEV_GROUPS.contains(&group.as_str()));
+        dependencies.push(Dependency {
+            name: captures["name"].to_owned(),
+            requirement: Some(requirements.join(", ")).filter(|r| !r.is_empty()),
+            scope: match is_dev {
+                true => DEV_SCOPE,
+                false => RUNTIME_SCOPE,
+            },
+            source,
+        });
+    }
+    dependencies
+}
+
+/// Names of symbols or strings, e.g. `development` and `test` for `:development, :test`
+fn symbols(text: &str) -> Vec<String> {
+    SYMBOL
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(1).or(captures.get(2)))
+        .map(|symbol| symbol.as_str().to_owned())
+        .collect()
+}
+
+/// Gems resolved in a Gemfile.lock, i.e. the specs of its GEM, GIT and PATH sections along with
+/// their checksums, if recorded
+fn locked_gems(lockfile: &str) -> Result<Vec<LockedGem>, SoupSourceParseError> {
+    if !lockfile
+        .lines()
+        .any(|line| line == "GEM" || line == "GIT" || line == "PATH")
+    {
+        return Err(SoupSourceParseError {
+            message: "Invalid Gemfile.lock (no GEM, GIT or PATH section)".to_owned(),
+        });
+    }
+    let mut gems: Vec<LockedGem> = Vec::new();
+    let mut section = "";
+    let mut remote = None;
+    let mut revision = None;
+    for line in lockfile.lines() {
+        if !line.starts_with(' ') {
+            section = line.trim();
+            remote = None;
+            revision = None;
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("  remote: ") {
+            remote = Some(value.to_owned());
+        } else if let Some(value) = line.strip_prefix("  revision: ") {
+            revision = Some(value.to_owned());
+        } else if section == "CHECKSUMS" {
+            let captures = match CHECKSUM.captures(line) {
+                Some(captures) => captures,
+                None => continue,
+            };
+            let (version, platform) = version_and_platform(&captures["version"]);
+            let gem = gems.iter_mut().find(|gem| {
+                gem.name == captures["name"] && gem.version == version && gem.platform == platform
+            });
+            if let Some(gem) = gem {
+                gem.checksum = Some(captures["checksum"].replacen('=', ":", 1));
+            }
+        } else if let Some(captures) = SPEC.captures(line) {
+            let (version, platform) = version_and_platform(&captures["version"]);
+            gems.push(LockedGem {
+                name: captures["name"].to_owned(),
+                version,
+                platform,
+                git: match (section, &remote, &revision) {
+                    ("GIT", Some(remote), Some(revision)) => {
+                        Some((remote.to_owned(), revision.to_owned()))
+                    }
+                    _ => None,
+                },
+                is_path: section == "PATH",
+                checksum: None,
+            });
+        }
+    }
+    Ok(gems)
+}
+
+/// Version and platform of a locked gem, e.g. `1.16.5` and `x86_64-linux` for
+/// `1.16.5-x86_64-linux`, as versions of gems contain no dashes
+fn version_and_platform(version: &str) -> (String, Option<String>) {
+    match version.split_once('-') {
+        Some((version, platform)) => (version.to_owned(), Some(platform.to_owned())),
+        None => (version.to_owned(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::scopes;
+    use test_case::test_case;
+
+    const GEMFILE: &str = r#"
+source "https://rubygems.org"
+
+ruby "3.3.1"
+
+gem "rails", "~> 7.1", ">= 7.1.3"
+gem 'pg', '~> 1.5'
+gem "nokogiri"
+gem "sidekiq-pro", git: "https://gems.example.com/sidekiq-pro.git"
+gem "internal_tools", path: "./tools"
+gem "rubocop", require: false, groups: [:development, :test]
+
+platforms :mri, :windows do
+  gem "debug"
+end
+
+group :development, :test do
+  gem "rspec-rails", "~> 6.1" # test framework
+end
+"#;
+
+    const LOCKFILE: &str = r#"GIT
+  remote: https://gems.example.com/sidekiq-pro.git
+  revision: 6fe2c0a2fd2b3cb1b87c3a7cfb5f7c4a6a7b0e1d
+  specs:
+    sidekiq-pro (7.2.0)
+
+PATH
+  remote: tools
+  specs:
+    internal_tools (0.1.0)
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    debug (1.9.2)
+    nokogiri (1.16.5-x86_64-linux)
+      racc (~> 1.4)
+    pg (1.5.6)
+    racc (1.8.0)
+    rails (7.1.3.4)
+    rspec-rails (6.1.3)
+    rubocop (1.64.1)
+
+PLATFORMS
+  x86_64-linux
+
+DEPENDENCIES
+  debug
+  internal_tools!
+  nokogiri
+  pg (~> 1.5)
+  rails (~> 7.1, >= 7.1.3)
+  rspec-rails (~> 6.1)
+  rubocop
+  sidekiq-pro!
+
+CHECKSUMS
+  pg (1.5.6) sha256=4a5e5d8ac6c5a4f4b9b4e0f1f3c4b2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5
+
+BUNDLED WITH
+   2.5.11
+"#;
+
+    #[test]
+    fn declared_versions() {
+        let result = Gemfile::default().soups(GEMFILE, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("debug", "unknown", "runtime"),
+                ("nokogiri", "unknown", "runtime"),
+                ("pg", "~> 1.5", "runtime"),
+                ("rails", "~> 7.1, >= 7.1.3", "runtime"),
+                ("rspec-rails", "~> 6.1", "dev"),
+                ("rubocop", "unknown", "dev"),
+                ("sidekiq-pro", "unknown", "runtime"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case(false, &[
+        ("debug", "1.9.2", "runtime"),
+        ("nokogiri", "1.16.5", "runtime"),
+        ("pg", "1.5.6", "runtime"),
+        ("rails", "7.1.3.4", "runtime"),
+        ("rspec-rails", "6.1.3", "dev"),
+        ("rubocop", "1.64.1", "dev"),
+        ("sidekiq-pro", "7.2.0", "runtime"),
+    ])]
+    #[test_case(true, &[
+        ("debug", "1.9.2", "runtime"),
+        ("nokogiri", "1.16.5", "runtime"),
+        ("pg", "1.5.6", "runtime"),
+        ("racc", "1.8.0", "runtime"),
+        ("rails", "7.1.3.4", "runtime"),
+        ("rspec-rails", "6.1.3", "dev"),
+        ("rubocop", "1.64.1", "dev"),
+        ("sidekiq-pro", "7.2.0", "runtime"),
+    ])]
+    fn locked_versions(include_transitive: bool, soups: &[(&str, &str, &str)]) {
+        let gemfile = Gemfile {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive,
+        };
+        let result = gemfile.soups(GEMFILE, &Map::new()).unwrap();
+        assert_eq!(soups, scopes(&result));
+    }
+
+    #[test]
+    fn locked_attributes() {
+        let gemfile = Gemfile {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: false,
+        };
+        let soups = gemfile.soups(GEMFILE, &Map::new()).unwrap();
+        let attribute = |name: &str, key: &str| {
+            soups
+                .iter()
+                .find(|soup| soup.name == name)
+                .and_then(|soup| soup.attributes.get(key))
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_owned())
+        };
+        assert_eq!(
+            Some(
+                "sha256:4a5e5d8ac6c5a4f4b9b4e0f1f3c4b2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5"
+                    .to_owned()
+            ),
+            attribute("pg", "checksum")
+        );
+        assert_eq!(
+            Some("x86_64-linux".to_owned()),
+            attribute("nokogiri", "platform")
+        );
+        assert_eq!(
+            Some("6fe2c0a2fd2b3cb1b87c3a7cfb5f7c4a6a7b0e1d".to_owned()),
+            attribute("sidekiq-pro", "revision")
+        );
+        assert_eq!(
+            Some("~> 7.1, >= 7.1.3".to_owned()),
+            attribute("rails", "requirement")
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let gemfile = Gemfile {
+            lockfile: Some("{}".to_owned()),
+            include_transitive: false,
+        };
+        assert_eq!(true, gemfile.soups(GEMFILE, &Map::new()).is_err());
+    }
+}
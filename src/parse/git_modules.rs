@@ -0,0 +1,169 @@
+use super::SoupParse;
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Parses the submodules of a `.gitmodules` file, which are named after their repository
+pub struct GitModules {
+    /// Commits that submodules are pinned to, by their path
+    pub commits: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct Submodule {
+    path: Option<String>,
+    url: Option<String>,
+    branch: Option<String>,
+}
+
+impl SoupParse for GitModules {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut soups = BTreeSet::new();
+        for submodule in submodules(content)? {
+            let (path, url) = match (submodule.path, submodule.url) {
+                (Some(path), Some(url)) => (path, url),
+                _ => continue,
+            };
+            let mut attributes = Map::new();
+            attributes.insert("source".to_owned(), Value::String(url.to_owned()));
+            attributes.insert("path".to_owned(), Value::String(path.to_owned()));
+            if let Some(branch) = submodule.branch {
+                attributes.insert("branch".to_owned(), Value::String(branch));
+            }
+            soups.insert(Soup {
+                name: repository_name(&url).to_owned(),
+                version: self
+                    .commits
+                    .get(path.trim_end_matches('/'))
+                    .map_or("unknown".to_owned(), |commit| commit.to_owned()),
+                ecosystem: None,
+                scope: None,
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+        Ok(soups)
+    }
+}
+
+/// Submodules of a `.gitmodules` file, which uses the syntax of git config files, e.g.
+/// `[submodule "mbedtls"]` followed by `path = third_party/mbedtls` and `url = ...`
+fn submodules(content: &str) -> Result<Vec<Submodule>, SoupSourceParseError> {
+    let mut submodules: Vec<Submodule> = Vec::new();
+    let mut in_submodule = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') {
+            if !line.ends_with(']') {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid .gitmodules (unclosed section {})", line),
+                });
+            }
+            in_submodule = line.starts_with("[submodule");
+            if in_submodule {
+                submodules.push(Submodule::default());
+            }
+            continue;
+        }
+        // Keys without value are booleans, e.g. `shallow`
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim().trim_matches('"').to_owned()),
+            None => (line, "true".to_owned()),
+        };
+        let submodule = match (in_submodule, submodules.last_mut()) {
+            (true, Some(submodule)) => submodule,
+            _ => continue,
+        };
+        match key.to_lowercase().as_str() {
+            "path" => submodule.path = Some(value),
+            "url" => submodule.url = Some(value),
+            "branch" => submodule.branch = Some(value),
+            _ => {}
+        }
+    }
+    Ok(submodules)
+}
+
+/// Name of a repository, e.g. `mbedtls` for `https://github.com/Mbed-TLS/mbedtls.git` or
+/// `git@example.com:team/unity.git`
+fn repository_name(url: &str) -> &str {
+    let url = url.trim_end_matches('/');
+    let name = url.rsplit(['/', ':']).next().unwrap_or(url);
+    name.strip_suffix(".git").unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CONTENT: &str = r#"
+[submodule "third_party/mbedtls"]
+	path = third_party/mbedtls
+	url = https://github.com/Mbed-TLS/mbedtls.git
+	branch = mbedtls-3.6
+	shallow
+[submodule "unity"]
+	path = third_party/unity
+	url = git@github.com:ThrowTheSwitch/Unity.git
+"#;
+
+    #[test]
+    fn pinned_submodules() {
+        let commits = HashMap::from([(
+            "third_party/mbedtls".to_owned(),
+            "2ca6c285a0dd3f33982dd57299012dacab1ff206".to_owned(),
+        )]);
+        let soups = GitModules { commits }
+            .soups(CONTENT, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("Unity", "unknown"),
+                ("mbedtls", "2ca6c285a0dd3f33982dd57299012dacab1ff206"),
+            ],
+            soups
+                .iter()
+                .map(|soup| (soup.name.as_str(), soup.version.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "https://github.com/Mbed-TLS/mbedtls.git".to_owned()
+            )),
+            soups[1].attributes.get("source")
+        );
+        assert_eq!(
+            Some(&Value::String("mbedtls-3.6".to_owned())),
+            soups[1].attributes.get("branch")
+        );
+    }
+
+    #[test]
+    fn invalid_content() {
+        assert_eq!(
+            true,
+            GitModules {
+                commits: HashMap::new()
+            }
+            .soups("[submodule \"lib\"\n\tpath = lib\n", &Map::new())
+            .is_err()
+        );
+    }
+
+    #[test_case("https://github.com/Mbed-TLS/mbedtls.git", "mbedtls")]
+    #[test_case("git@example.com:unity.git", "unity")]
+    #[test_case("../lwip", "lwip")]
+    fn repository_names(url: &str, expected: &str) {
+        assert_eq!(expected, repository_name(url));
+    }
+}
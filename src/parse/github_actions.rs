@@ -0,0 +1,203 @@
+use super::{SoupParse, docker_base};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::BTreeSet;
+
+lazy_static! {
+    static ref COMMIT_SHA: Regex = Regex::new(r"^[0-9a-f]{40}$").unwrap();
+}
+
+/// Parses the actions, reusable workflows and container images that GitHub Actions workflows
+/// under `.github/workflows` and composite or Docker actions (`action.yml`) use
+pub struct GitHubActions {}
+
+impl SoupParse for GitHubActions {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: YamlValue = match serde_yaml_ng::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid GitHub Actions definition ({})", e),
+                });
+            }
+        };
+        let mut uses: Vec<&str> = Vec::new();
+        let mut images: Vec<&str> = Vec::new();
+        let jobs = content
+            .get("jobs")
+            .and_then(|jobs| jobs.as_mapping())
+            .into_iter()
+            .flat_map(|jobs| jobs.values());
+        for job in jobs {
+            // Jobs either call a reusable workflow or run steps
+            uses.extend(job.get("uses").and_then(|uses| uses.as_str()));
+            uses.extend(step_uses(job));
+            match job.get("container") {
+                Some(YamlValue::String(image)) => images.push(image),
+                Some(container) => {
+                    images.extend(container.get("image").and_then(|image| image.as_str()))
+                }
+                None => {}
+            }
+            let services = job
+                .get("services")
+                .and_then(|services| services.as_mapping())
+                .into_iter()
+                .flat_map(|services| services.values());
+            images.extend(services.filter_map(|service| service.get("image")?.as_str()));
+        }
+        if let Some(runs) = content.get("runs") {
+            uses.extend(step_uses(runs));
+            uses.extend(runs.get("image").and_then(|image| image.as_str()));
+        }
+
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for reference in uses {
+            match reference.strip_prefix("docker://") {
+                Some(image) => images.push(image),
+                None => result.extend(action_soup(reference, default_meta)),
+            }
+        }
+        for image in images {
+            if let Some(mut soup) = docker_base::image_soup(image, default_meta) {
+                if !docker_base::is_pinned(&soup) {
+                    soup.attributes
+                        .insert("mutable_ref".to_owned(), Value::Bool(true));
+                }
+                result.insert(soup);
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn step_uses(value: &YamlValue) -> impl Iterator<Item = &str> {
+    value
+        .get("steps")
+        .and_then(|steps| steps.as_sequence())
+        .into_iter()
+        .flatten()
+        .filter_map(|step| step.get("uses")?.as_str())
+}
+
+/// SOUP of an action or reusable workflow reference such as `actions/checkout@v4` or
+/// `owner/repo/.github/workflows/build.yml@<sha>`, where references that aren't commit SHAs are
+/// flagged as mutable. Actions within the same repository, e.g. `./.github/actions/setup`, are
+/// no SOUP of their own.
+fn action_soup(reference: &str, default_meta: &Map<String, Value>) -> Option<Soup> {
+    if reference.starts_with("./") {
+        return None;
+    }
+    let (name, git_ref) = reference.split_once('@')?;
+    let mut attributes = Map::new();
+    if !COMMIT_SHA.is_match(git_ref) {
+        attributes.insert("mutable_ref".to_owned(), Value::Bool(true));
+    }
+    Some(Soup {
+        name: name.to_owned(),
+        version: git_ref.to_owned(),
+        ecosystem: Some(Ecosystem::GitHubActions),
+        scope: None,
+        attributes,
+        meta: default_meta.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::summarize;
+
+    #[test]
+    fn workflow() {
+        let content = r#"
+name: Build
+on: [push]
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    container: node:20.14
+    services:
+      db:
+        image: postgres:14.4@sha256:ca468b84b84846e84
+    steps:
+      - uses: actions/checkout@692973e3d937129bcbf40652eb9f2f61becf3332
+      - uses: actions/setup-node@v4
+        with:
+          node-version: 20
+      - uses: ./.github/actions/cache
+      - uses: docker://alpine:3.20
+      - run: npm ci
+  release:
+    uses: owner/pipelines/.github/workflows/release.yml@v1.2.0
+"#;
+        let result = GitHubActions {}.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                (
+                    "actions/checkout",
+                    "692973e3d937129bcbf40652eb9f2f61becf3332",
+                    false
+                ),
+                ("actions/setup-node", "v4", true),
+                ("alpine", "3.20", true),
+                ("node", "20.14", true),
+                (
+                    "owner/pipelines/.github/workflows/release.yml",
+                    "v1.2.0",
+                    true
+                ),
+                ("postgres", "14.4", false),
+            ],
+            summarize(&result, |soup| soup.attributes.contains_key("mutable_ref"))
+        );
+    }
+
+    #[test]
+    fn composite_action() {
+        let content = r#"
+name: Setup
+runs:
+  using: composite
+  steps:
+    - uses: actions/cache@v4
+    - run: echo done
+      shell: bash
+"#;
+        let result = GitHubActions {}.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![("actions/cache", "v4", true)],
+            summarize(&result, |soup| soup.attributes.contains_key("mutable_ref"))
+        );
+    }
+
+    #[test]
+    fn docker_action() {
+        let content = r#"
+name: Lint
+runs:
+  using: docker
+  image: docker://ghcr.io/owner/linter:2.1.0
+"#;
+        let result = GitHubActions {}.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![("ghcr.io/owner/linter", "2.1.0", true)],
+            summarize(&result, |soup| soup.attributes.contains_key("mutable_ref"))
+        );
+    }
+
+    #[test]
+    fn invalid_workflow() {
+        assert_eq!(
+            true,
+            GitHubActions {}.soups("jobs: [", &Map::new()).is_err()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use super::{SoupParse, docker_base, dockerfile};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::{BTreeSet, HashMap};
+
+/// Parses the images and service images that jobs of a GitLab CI/CD pipeline (`.gitlab-ci.yml`)
+/// run in, whether set globally, as `default` or per job
+pub struct GitLabCi {}
+
+impl SoupParse for GitLabCi {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: YamlValue = match serde_yaml_ng::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid GitLab CI/CD configuration ({})", e),
+                });
+            }
+        };
+        let variables = variables(&content);
+        let mut images: Vec<&str> = Vec::new();
+        // Besides jobs, hidden jobs such as `.build` and `default` are mappings at the top level
+        let jobs = content
+            .as_mapping()
+            .into_iter()
+            .flat_map(|mapping| mapping.values())
+            .filter(|value| value.is_mapping());
+        for job in std::iter::once(&content).chain(jobs) {
+            images.extend(job.get("image").and_then(image_name));
+            let services = job
+                .get("services")
+                .and_then(|services| services.as_sequence())
+                .into_iter()
+                .flatten();
+            images.extend(services.filter_map(image_name));
+        }
+        Ok(images
+            .into_iter()
+            .filter_map(|image| {
+                let image = dockerfile::substitute(image, &variables);
+                let mut soup = docker_base::image_soup(&image, default_meta)?;
+                if !docker_base::is_pinned(&soup) {
+                    soup.attributes
+                        .insert("mutable_ref".to_owned(), Value::Bool(true));
+                }
+                Some(soup)
+            })
+            .collect())
+    }
+}
+
+/// Image names are either given as is or as `name` of an image or service
+fn image_name(value: &YamlValue) -> Option<&str> {
+    match value {
+        YamlValue::String(name) => Some(name),
+        _ => value.get("name")?.as_str(),
+    }
+}
+
+/// Global variables, given either as value or as mapping with a `value`
+fn variables(content: &YamlValue) -> HashMap<String, String> {
+    content
+        .get("variables")
+        .and_then(|variables| variables.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                YamlValue::Mapping(_) => value.get("value")?,
+                _ => value,
+            };
+            let value = match value {
+                YamlValue::String(value) => value.to_owned(),
+                YamlValue::Number(value) => value.to_string(),
+                _ => return None,
+            };
+            Some((name.as_str()?.to_owned(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_images() {
+        let content = r#"
+image: node:20.14
+variables:
+  POSTGRES_VERSION: "14.4"
+  KANIKO_VERSION:
+    value: v1.23.0
+    description: Version of the Kaniko executor
+default:
+  services:
+    - docker:26-dind
+stages: [build, test]
+.test:
+  services:
+    - name: postgres:$POSTGRES_VERSION
+      alias: db
+build:
+  stage: build
+  image:
+    name: gcr.io/kaniko-project/executor:${KANIKO_VERSION}
+    entrypoint: [""]
+  script:
+    - /kaniko/executor
+test:
+  extends: .test
+  image: python@sha256:ca468b84b84846e84
+  script:
+    - pytest
+"#;
+        let soups = GitLabCi {}
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .map(|soup| {
+                let mutable = soup.attributes.contains_key("mutable_ref");
+                (soup.name, soup.version, mutable)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("docker".to_owned(), "26-dind".to_owned(), true),
+                (
+                    "gcr.io/kaniko-project/executor".to_owned(),
+                    "v1.23.0".to_owned(),
+                    true
+                ),
+                ("node".to_owned(), "20.14".to_owned(), true),
+                ("postgres".to_owned(), "14.4".to_owned(), true),
+                (
+                    "python".to_owned(),
+                    "sha256:ca468b84b84846e84".to_owned(),
+                    false
+                ),
+            ],
+            soups
+        );
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        assert_eq!(true, GitLabCi {}.soups("image: [", &Map::new()).is_err());
+    }
+}
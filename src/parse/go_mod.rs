@@ -0,0 +1,297 @@
+use super::{SoupParse, go_sum};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+#[derive(Default)]
+pub struct GoMod {
+    /// Content of the go.sum next to the go.mod, to attach the hashes of the modules
+    pub go_sum: Option<String>,
+}
+
+/// A directive such as `require golang.org/x/text v0.3.0 // indirect`, either on its own line or
+/// within a block such as `require ( ... )`
+struct Directive {
+    line: usize,
+    verb: String,
+    arguments: Vec<String>,
+    comment: String,
+}
+
+struct Replace {
+    path: String,
+    version: Option<String>,
+    replacement: String,
+    replacement_version: Option<String>,
+}
+
+impl SoupParse for GoMod {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let hashes = match &self.go_sum {
+            Some(go_sum) => go_sum::parse(go_sum)?,
+            None => go_sum::GoSum::new(),
+        };
+        let directives = directives(content)?;
+        let replaces = directives
+            .iter()
+            .filter(|directive| directive.verb == "replace")
+            .map(replace)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut soups = BTreeSet::new();
+        for directive in directives.iter().filter(|d| d.verb == "require") {
+            let (path, version) = match directive.arguments.as_slice() {
+                [path, version] => (path, version),
+                _ => return Err(malformed(directive)),
+            };
+            let mut attributes = Map::new();
+            // Replacements of a specific version take precedence over those of any version
+            let replace = replaces
+                .iter()
+                .filter(|replace| replace.path == *path)
+                .find(|replace| replace.version.as_ref() == Some(version))
+                .or_else(|| {
+                    replaces
+                        .iter()
+                        .find(|replace| replace.path == *path && replace.version.is_none())
+                });
+            let (name, version) = match replace {
+                // Replacements with a directory are modules within the repository
+                Some(replace) if is_local_path(&replace.replacement) => continue,
+                Some(replace) => {
+                    attributes.insert(
+                        "replaces".to_owned(),
+                        Value::String(format!("{}@{}", path, version)),
+                    );
+                    let replacement_version = match &replace.replacement_version {
+                        Some(replacement_version) => replacement_version,
+                        None => return Err(malformed(directive)),
+                    };
+                    (&replace.replacement, replacement_version)
+                }
+                None => (path, version),
+            };
+            if directive.comment == "indirect" || directive.comment.starts_with("indirect;") {
+                attributes.insert("indirect".to_owned(), Value::Bool(true));
+            }
+            if let Some(hash) = hashes.get(&(name.to_owned(), version.to_owned())) {
+                attributes.insert("checksum".to_owned(), Value::String(hash.to_owned()));
+            }
+            soups.insert(Soup {
+                name: name.to_owned(),
+                version: version.to_owned(),
+                ecosystem: Some(Ecosystem::Go),
+                attributes,
+                meta: default_meta.clone(),
+                ..Default::default()
+            });
+        }
+        Ok(soups)
+    }
+}
+
+fn directives(content: &str) -> Result<Vec<Directive>, SoupSourceParseError> {
+    let mut directives = Vec::new();
+    let mut block: Option<String> = None;
+    for (index, line) in content.lines().enumerate() {
+        let (line, comment) = match line.split_once("//") {
+            Some((line, comment)) => (line.trim(), comment.trim()),
+            None => (line.trim(), ""),
+        };
+        if line.is_empty() {
+            continue;
+        }
+        let mut tokens = line
+            .split_whitespace()
+            .map(|token| token.trim_matches(['"', '`']).to_owned())
+            .collect::<Vec<String>>();
+        let verb = match (&block, tokens.as_slice()) {
+            (Some(_), [end]) if end == ")" => {
+                block = None;
+                continue;
+            }
+            (Some(verb), _) => verb.to_owned(),
+            (None, [verb, start]) if start == "(" => {
+                block = Some(verb.to_owned());
+                continue;
+            }
+            (None, _) => tokens.remove(0),
+        };
+        directives.push(Directive {
+            line: index + 1,
+            verb,
+            arguments: tokens,
+            comment: comment.to_owned(),
+        });
+    }
+    match block {
+        Some(verb) => Err(SoupSourceParseError {
+            message: format!("Invalid go.mod (unterminated {} block)", verb),
+        }),
+        None => Ok(directives),
+    }
+}
+
+/// Parses a replace directive such as `golang.org/x/net v1.2.3 => example.com/fork/net v1.4.5`,
+/// where the version of the replaced module is optional, as is the version of a replacement
+/// directory
+fn replace(directive: &Directive) -> Result<Replace, SoupSourceParseError> {
+    let arguments = directive
+        .arguments
+        .iter()
+        .map(|argument| argument.as_str())
+        .collect::<Vec<&str>>();
+    let (replaced, replacement) = match arguments.iter().position(|a| *a == "=>") {
+        Some(index) => (&arguments[..index], &arguments[index + 1..]),
+        None => return Err(malformed(directive)),
+    };
+    let (path, version) = match replaced {
+        [path] => (path, None),
+        [path, version] => (path, Some(version.to_string())),
+        _ => return Err(malformed(directive)),
+    };
+    let (replacement, replacement_version) = match replacement {
+        [replacement] => (replacement, None),
+        [replacement, version] => (replacement, Some(version.to_string())),
+        _ => return Err(malformed(directive)),
+    };
+    Ok(Replace {
+        path: path.to_string(),
+        version,
+        replacement: replacement.to_string(),
+        replacement_version,
+    })
+}
+
+fn is_local_path(path: &str) -> bool {
+    path.starts_with("./")
+        || path.starts_with("../")
+        || path.starts_with('/')
+        || path.starts_with(".\\")
+        || path.starts_with("..\\")
+        || path == "."
+        || path == ".."
+}
+
+fn malformed(directive: &Directive) -> SoupSourceParseError {
+    SoupSourceParseError {
+        message: format!(
+            "Invalid go.mod (malformed {} on line {})",
+            directive.verb, directive.line
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const GO_MOD: &str = r#"
+module example.com/service
+
+go 1.22
+
+require (
+	github.com/google/uuid v1.6.0
+	github.com/gorilla/mux v1.8.1
+	example.com/shared v0.0.0-00010101000000-000000000000
+	golang.org/x/net v0.25.0 // indirect
+)
+
+require golang.org/x/text v0.15.0 // indirect
+
+replace example.com/shared => ../shared
+
+replace (
+	github.com/gorilla/mux v1.8.1 => github.com/owner/mux v1.8.2-fix
+	golang.org/x/net v0.24.0 => golang.org/x/net v0.23.0
+)
+"#;
+
+    const GO_SUM: &str = r#"
+github.com/google/uuid v1.6.0 h1:NIvaJDMOsjHA8n1jAhLSgzrAzy1Hgr+hNrb57e+94F0=
+github.com/google/uuid v1.6.0/go.mod h1:TIyPZe4MgqvfeYDBFedMoGGpEw/LqOeaOT+nhxU+yHo=
+github.com/owner/mux v1.8.2-fix h1:Hoy+4ZiGO1JNd2gX7r5ziB4IbfLo0V1DUhoaYZ6Uv2U=
+"#;
+
+    #[test]
+    fn requirements() {
+        let result = GoMod::default().soups(GO_MOD, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(4, soups.len());
+        assert_eq!("github.com/google/uuid", soups[0].name);
+        assert_eq!("v1.6.0", soups[0].version);
+        assert_eq!(Some(Ecosystem::Go), soups[0].ecosystem);
+        assert_eq!(None, soups[0].attributes.get("indirect"));
+        assert_eq!("golang.org/x/net", soups[2].name);
+        assert_eq!("v0.25.0", soups[2].version);
+        assert_eq!(
+            Some(&Value::Bool(true)),
+            soups[2].attributes.get("indirect")
+        );
+        assert_eq!("golang.org/x/text", soups[3].name);
+        assert_eq!(
+            Some(&Value::Bool(true)),
+            soups[3].attributes.get("indirect")
+        );
+    }
+
+    #[test]
+    fn replacements() {
+        let soups = GoMod::default()
+            .soups(GO_MOD, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            false,
+            soups.iter().any(|soup| soup.name == "example.com/shared")
+        );
+        assert_eq!("github.com/owner/mux", soups[1].name);
+        assert_eq!("v1.8.2-fix", soups[1].version);
+        assert_eq!(
+            Some(&Value::String("github.com/gorilla/mux@v1.8.1".to_owned())),
+            soups[1].attributes.get("replaces")
+        );
+    }
+
+    #[test]
+    fn hashes() {
+        let go_mod = GoMod {
+            go_sum: Some(GO_SUM.to_owned()),
+        };
+        let soups = go_mod
+            .soups(GO_MOD, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Some(&Value::String(
+                "h1:NIvaJDMOsjHA8n1jAhLSgzrAzy1Hgr+hNrb57e+94F0=".to_owned()
+            )),
+            soups[0].attributes.get("checksum")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "h1:Hoy+4ZiGO1JNd2gX7r5ziB4IbfLo0V1DUhoaYZ6Uv2U=".to_owned()
+            )),
+            soups[1].attributes.get("checksum")
+        );
+        assert_eq!(None, soups[2].attributes.get("checksum"));
+    }
+
+    #[test_case("require (\n\tgithub.com/google/uuid v1.6.0\n" ; "unterminated_block")]
+    #[test_case("require github.com/google/uuid" ; "missing_version")]
+    #[test_case("require a v1.0.0\nreplace a v1.0.0 b v1.0.1" ; "missing_arrow")]
+    #[test_case("require a v1.0.0\nreplace a => b" ; "missing_replacement_version")]
+    fn invalid_go_mod(content: &str) {
+        let result = GoMod::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::soup::model::SoupSourceParseError;
+use std::collections::HashMap;
+
+/// Hashes of module contents by module path and version, as recorded in a go.sum
+pub type GoSum = HashMap<(String, String), String>;
+
+/// Parses a go.sum, where each line holds a module path, a version and a hash, e.g.
+/// `golang.org/x/text v0.3.0 h1:g61tztE5qeGQ89tm6NTjjM9VPIm088od1l6aSorWRWg=`. Lines for the
+/// go.mod file of a module, with versions such as `v0.3.0/go.mod`, are skipped as only the hash
+/// of the module contents identifies the downloaded source.
+pub fn parse(content: &str) -> Result<GoSum, SoupSourceParseError> {
+    let mut hashes = HashMap::new();
+    for (index, line) in content.lines().enumerate() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        match fields.as_slice() {
+            [] => {}
+            [_, version, _] if version.ends_with("/go.mod") => {}
+            [module, version, hash] => {
+                hashes.insert((module.to_string(), version.to_string()), hash.to_string());
+            }
+            _ => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid go.sum (malformed line {})", index + 1),
+                });
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_hashes() {
+        let content = r#"
+github.com/google/uuid v1.6.0 h1:NIvaJDMOsjHA8n1jAhLSgzrAzy1Hgr+hNrb57e+94F0=
+github.com/google/uuid v1.6.0/go.mod h1:TIyPZe4MgqvfeYDBFedMoGGpEw/LqOeaOT+nhxU+yHo=
+golang.org/x/text v0.3.0/go.mod h1:NqM8EUOU14njkJ3fqMW+pc6Ldnwhi/IjpwHt7yyuwOQ=
+"#;
+        let hashes = parse(content).unwrap();
+        assert_eq!(1, hashes.len());
+        assert_eq!(
+            Some(&"h1:NIvaJDMOsjHA8n1jAhLSgzrAzy1Hgr+hNrb57e+94F0=".to_owned()),
+            hashes.get(&("github.com/google/uuid".to_owned(), "v1.6.0".to_owned()))
+        );
+    }
+
+    #[test]
+    fn malformed_line() {
+        assert_eq!(true, parse("github.com/google/uuid v1.6.0").is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use super::SoupParse;
+use super::version_catalog;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Names of configurations that dependencies are declared in, which may also be prefixed with a
+/// source set or variant, e.g. `testImplementation` or `debugApi`
+const CONFIGURATIONS: [&str; 10] = [
+    "implementation",
+    "api",
+    "compileOnly",
+    "runtimeOnly",
+    "annotationProcessor",
+    "classpath",
+    "compile",
+    "runtime",
+    "kapt",
+    "ksp",
+];
+
+/// Configurations, by the end of their name, of tools that only run during the build
+const BUILD_CONFIGURATIONS: [&str; 5] = [
+    "compileOnly",
+    "annotationProcessor",
+    "classpath",
+    "kapt",
+    "ksp",
+];
+/// Configurations, by the end of their name, of dependencies that are only needed at runtime
+const RUNTIME_CONFIGURATIONS: [&str; 2] = ["runtimeOnly", "runtime"];
+
+lazy_static! {
+    static ref BLOCK_COMMENT: Regex = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    static ref LINE_COMMENT: Regex = Regex::new(r"(?m)(^|\s)//.*$").unwrap();
+    /// `implementation 'group:name:version'`, `implementation("group:name:version")` and
+    /// `implementation(platform("group:name:version"))`, with an optional classifier or extension
+    static ref STRING_NOTATION: Regex = Regex::new(
+        r#"\b([A-Za-z]+)\s*\(?\s*(?:(?:platform|enforcedPlatform)\s*\(\s*)?["']([^"'\s:$]+):([^"'\s:]+)(?::([^"'\s:@]+))?(?::[^"'\s@]+)?(?:@[A-Za-z]+)?["']"#
+    )
+    .unwrap();
+    /// `implementation group: 'group', name: 'name', version: 'version'` and the Kotlin
+    /// equivalent with named arguments
+    static ref MAP_NOTATION: Regex = Regex::new(
+        r#"\b([A-Za-z]+)\s*\(?\s*group\s*[:=]\s*["']([^"']+)["']\s*,\s*name\s*[:=]\s*["']([^"']+)["'](?:\s*,\s*version\s*[:=]\s*["']([^"']+)["'])?"#
+    )
+    .unwrap();
+    /// `implementation libs.some.library` and `implementation(libs.bundles.some.bundle)`
+    static ref CATALOG_NOTATION: Regex = Regex::new(
+        r"\b([A-Za-z]+)\s*\(?\s*(?:(?:platform|enforcedPlatform)\s*\(\s*)?(libs\.[A-Za-z0-9_.]+)"
+    )
+    .unwrap();
+    /// `def springVersion = '6.1.8'`, `val springVersion = "6.1.8"`, `ext.springVersion = '6.1.8'`
+    /// and assignments within `ext { }`
+    static ref VARIABLE: Regex = Regex::new(
+        r#"(?m)^\s*(?:(?:def|val|var)\s+|ext\.|extra\[)?["']?([A-Za-z_][A-Za-z0-9_]*)["']?\]?\s*(?::\s*String\s*)?=\s*["']([^"'$]+)["']"#
+    )
+    .unwrap();
+    static ref INTERPOLATION: Regex =
+        Regex::new(r"\$\{?([A-Za-z_][A-Za-z0-9_.]*)\}?").unwrap();
+}
+
+/// Parses Gradle build scripts, `build.gradle` in Groovy or `build.gradle.kts` in Kotlin, for
+/// dependencies declared in common notations
+#[derive(Default)]
+pub struct Gradle {
+    /// Content of the closest gradle.properties, which versions may be interpolated from
+    pub properties: Option<String>,
+    /// Content of the version catalog of the build, i.e. `gradle/libs.versions.toml`
+    pub version_catalog: Option<String>,
+}
+
+impl SoupParse for Gradle {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let catalog = match &self.version_catalog {
+            Some(version_catalog) => Some(version_catalog::parse(version_catalog)?),
+            None => None,
+        };
+        let content = BLOCK_COMMENT.replace_all(content, "");
+        let content = LINE_COMMENT.replace_all(&content, "$1");
+
+        let mut variables = self
+            .properties
+            .as_deref()
+            .map(properties)
+            .unwrap_or_default();
+        for captures in VARIABLE.captures_iter(&content) {
+            variables.insert(captures[1].to_owned(), captures[2].to_owned());
+        }
+        let interpolate = |text: &str| {
+            INTERPOLATION
+                .replace_all(text, |captures: &Captures| {
+                    // Properties may be qualified, e.g. `rootProject.ext.springVersion`
+                    let name = captures[1].rsplit('.').next().unwrap_or(&captures[1]);
+                    match variables.get(name) {
+                        Some(value) => value.to_owned(),
+                        None => captures[0].to_owned(),
+                    }
+                })
+                .to_string()
+        };
+
+        let mut dependencies: Vec<(String, String, Option<String>, String)> = Vec::new();
+        for notation in [&*STRING_NOTATION, &*MAP_NOTATION] {
+            for captures in notation.captures_iter(&content) {
+                if !is_configuration(&captures[1]) {
+                    continue;
+                }
+                dependencies.push((
+                    interpolate(&captures[2]),
+                    interpolate(&captures[3]),
+                    captures.get(4).map(|version| interpolate(version.as_str())),
+                    captures[1].to_owned(),
+                ));
+            }
+        }
+        if let Some(catalog) = &catalog {
+            for captures in CATALOG_NOTATION.captures_iter(&content) {
+                if !is_configuration(&captures[1]) {
+                    continue;
+                }
+                for library in catalog.libraries(&captures[2]) {
+                    dependencies.push((
+                        library.group.to_owned(),
+                        library.name.to_owned(),
+                        library.version.to_owned(),
+                        captures[1].to_owned(),
+                    ));
+                }
+            }
+        }
+
+        Ok(dependencies
+            .into_iter()
+            .map(|(group, name, version, configuration)| Soup {
+                name: format!("{}:{}", group, name),
+                version: version.unwrap_or("unknown".to_owned()),
+                ecosystem: Some(Ecosystem::Maven),
+                scope: Some(scope(&configuration).to_owned()),
+                attributes: Map::from_iter([(
+                    "configuration".to_owned(),
+                    Value::String(configuration),
+                )]),
+                meta: default_meta.clone(),
+            })
+            .collect())
+    }
+}
+
+fn is_configuration(name: &str) -> bool {
+    CONFIGURATIONS.iter().any(|configuration| {
+        let mut chars = configuration.chars();
+        let capitalized = match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => String::new(),
+        };
+        name == *configuration || name.ends_with(&capitalized)
+    })
+}
+
+/// Scope of the dependencies of a configuration, named like the scopes of Maven, so that e.g.
+/// `testImplementation` and `androidTestImplementation` dependencies are test dependencies
+fn scope(configuration: &str) -> &'static str {
+    let ends_with = |suffixes: &[&str]| {
+        suffixes.iter().any(|suffix| {
+            configuration
+                .to_lowercase()
+                .ends_with(&suffix.to_lowercase())
+        })
+    };
+    if configuration.starts_with("test") || configuration.contains("Test") {
+        "test"
+    } else if ends_with(&BUILD_CONFIGURATIONS) {
+        "build"
+    } else if ends_with(&RUNTIME_CONFIGURATIONS) {
+        "runtime"
+    } else {
+        "compile"
+    }
+}
+
+/// Properties of a gradle.properties file, formatted as `key=value` or `key: value`
+fn properties(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.starts_with('#') && !line.starts_with('!'))
+        .filter_map(|line| line.split_once(['=', ':']))
+        .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{scopes, summarize};
+    use test_case::test_case;
+
+    #[test]
+    fn groovy_notations() {
+        let content = r#"
+plugins {
+    id 'java'
+}
+
+ext {
+    jacksonVersion = '2.17.1'
+}
+def junitVersion = "5.10.2"
+
+dependencies {
+    implementation 'org.slf4j:slf4j-api:2.0.13'
+    implementation "com.fasterxml.jackson.core:jackson-databind:$jacksonVersion"
+    implementation platform('org.springframework.boot:spring-boot-dependencies:3.3.0')
+    runtimeOnly group: 'org.postgresql', name: 'postgresql', version: '42.7.3'
+    testImplementation "org.junit.jupiter:junit-jupiter:${junitVersion}"
+    implementation 'org.springframework.boot:spring-boot-starter-web'
+    implementation project(':shared')
+    // implementation 'commented:out:1.0'
+    /* implementation 'commented:out:2.0' */
+}
+"#;
+        let result = Gradle::default().soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                (
+                    "com.fasterxml.jackson.core:jackson-databind",
+                    "2.17.1",
+                    "compile"
+                ),
+                ("org.junit.jupiter:junit-jupiter", "5.10.2", "test"),
+                ("org.postgresql:postgresql", "42.7.3", "runtime"),
+                ("org.slf4j:slf4j-api", "2.0.13", "compile"),
+                (
+                    "org.springframework.boot:spring-boot-dependencies",
+                    "3.3.0",
+                    "compile"
+                ),
+                (
+                    "org.springframework.boot:spring-boot-starter-web",
+                    "unknown",
+                    "compile"
+                ),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test]
+    fn kotlin_notations() {
+        let content = r#"
+val jacksonVersion: String = "2.17.1"
+val junitVersion: String by project
+
+dependencies {
+    implementation("org.slf4j:slf4j-api:2.0.13")
+    implementation("com.fasterxml.jackson.core:jackson-databind:$jacksonVersion")
+    runtimeOnly(group = "org.postgresql", name = "postgresql", version = "42.7.3")
+    testImplementation("org.junit.jupiter:junit-jupiter:$junitVersion")
+    kapt("com.google.dagger:dagger-compiler:2.51.1")
+    implementation(kotlin("stdlib"))
+}
+"#;
+        let gradle = Gradle {
+            properties: Some("# versions\njunitVersion=5.10.2\n".to_owned()),
+            version_catalog: None,
+        };
+        let result = gradle.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                (
+                    "com.fasterxml.jackson.core:jackson-databind",
+                    "2.17.1",
+                    "compile"
+                ),
+                ("com.google.dagger:dagger-compiler", "2.51.1", "build"),
+                ("org.junit.jupiter:junit-jupiter", "5.10.2", "test"),
+                ("org.postgresql:postgresql", "42.7.3", "runtime"),
+                ("org.slf4j:slf4j-api", "2.0.13", "compile"),
+            ],
+            scopes(&result)
+        );
+        assert_eq!(
+            vec![
+                "implementation",
+                "kapt",
+                "testImplementation",
+                "runtimeOnly",
+                "implementation"
+            ],
+            summarize(&result, |soup| soup.attributes["configuration"]
+                .as_str()
+                .unwrap())
+            .into_iter()
+            .map(|(_, _, configuration)| configuration)
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn version_catalog() {
+        let content = r#"
+dependencies {
+    implementation(libs.guava)
+    implementation libs.bundles.jackson
+    testImplementation(libs.junit.jupiter)
+    implementation(libs.versions.jackson.get())
+}
+"#;
+        let catalog = r#"
+[versions]
+jackson = "2.17.1"
+
+[libraries]
+guava = "com.google.guava:guava:33.2.1-jre"
+jackson-databind = { module = "com.fasterxml.jackson.core:jackson-databind", version.ref = "jackson" }
+junit-jupiter = { module = "org.junit.jupiter:junit-jupiter", version = "5.10.2" }
+
+[bundles]
+jackson = ["jackson-databind"]
+"#;
+        let gradle = Gradle {
+            properties: None,
+            version_catalog: Some(catalog.to_owned()),
+        };
+        let result = gradle.soups(content, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                (
+                    "com.fasterxml.jackson.core:jackson-databind",
+                    "2.17.1",
+                    "compile"
+                ),
+                ("com.google.guava:guava", "33.2.1-jre", "compile"),
+                ("org.junit.jupiter:junit-jupiter", "5.10.2", "test"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case("implementation", "compile")]
+    #[test_case("api", "compile")]
+    #[test_case("runtimeOnly", "runtime")]
+    #[test_case("compileOnly", "build")]
+    #[test_case("debugCompileOnly", "build")]
+    #[test_case("annotationProcessor", "build")]
+    #[test_case("testImplementation", "test")]
+    #[test_case("testCompileOnly", "test")]
+    #[test_case("androidTestImplementation", "test")]
+    fn scopes_of_configurations(configuration: &str, expected: &str) {
+        assert_eq!(expected, scope(configuration));
+    }
+
+    #[test_case("implementation", true)]
+    #[test_case("testImplementation", true)]
+    #[test_case("debugApi", true)]
+    #[test_case("id", false)]
+    #[test_case("version", false)]
+    fn configurations(name: &str, expected: bool) {
+        assert_eq!(expected, is_configuration(name));
+    }
+}
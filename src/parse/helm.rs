@@ -0,0 +1,165 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses the dependencies of a Helm chart, declared in Chart.yaml or, for charts of API version
+/// v1, in requirements.yaml
+pub struct HelmChart {
+    /// Content of the Chart.lock or requirements.lock of the chart
+    pub lockfile: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    name: String,
+    version: Option<String>,
+    repository: Option<String>,
+    alias: Option<String>,
+}
+
+impl SoupParse for HelmChart {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let chart = parse(content, "chart")?;
+        let locked = match &self.lockfile {
+            Some(lockfile) => parse(lockfile, "lockfile")?.dependencies,
+            None => Vec::new(),
+        };
+        Ok(chart
+            .dependencies
+            .iter()
+            // Subcharts within the repository are no SOUP of their own
+            .filter(|dependency| {
+                !dependency
+                    .repository
+                    .as_ref()
+                    .is_some_and(|repository| repository.starts_with("file://"))
+            })
+            .map(|dependency| {
+                let version = locked
+                    .iter()
+                    .find(|locked| {
+                        locked.name == dependency.name && locked.repository == dependency.repository
+                    })
+                    .and_then(|locked| locked.version.as_ref())
+                    .or(dependency.version.as_ref());
+                let mut attributes = Map::new();
+                if let Some(repository) = &dependency.repository {
+                    attributes.insert("source".to_owned(), Value::String(repository.to_owned()));
+                }
+                if let Some(alias) = &dependency.alias {
+                    attributes.insert("alias".to_owned(), Value::String(alias.to_owned()));
+                }
+                Soup {
+                    name: dependency.name.to_owned(),
+                    version: version.map_or("unknown".to_owned(), |version| version.to_owned()),
+                    ecosystem: Some(Ecosystem::Helm),
+                    scope: None,
+                    attributes,
+                    meta: default_meta.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+fn parse(content: &str, kind: &str) -> Result<Content, SoupSourceParseError> {
+    match serde_yaml_ng::from_str::<Option<Content>>(content) {
+        Ok(content) => Ok(content.unwrap_or(Content {
+            dependencies: Vec::new(),
+        })),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid Helm {} ({})", kind, e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+
+    const CHART: &str = r#"
+apiVersion: v2
+name: shop
+version: 1.0.0
+dependencies:
+  - name: postgresql
+    version: ~12.5.0
+    repository: https://charts.bitnami.com/bitnami
+  - name: redis
+    version: 18.x.x
+    repository: oci://registry-1.docker.io/bitnamicharts
+    alias: cache
+  - name: common
+    version: 0.1.0
+    repository: file://../common
+"#;
+
+    #[test]
+    fn declared_versions() {
+        let result = HelmChart { lockfile: None }
+            .soups(CHART, &Map::new())
+            .unwrap();
+        assert_eq!(
+            vec![("postgresql", "~12.5.0"), ("redis", "18.x.x")],
+            versions(&result)
+        );
+        let soups = result.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            Some(&Value::String(
+                "https://charts.bitnami.com/bitnami".to_owned()
+            )),
+            soups[0].attributes.get("source")
+        );
+        assert_eq!(
+            Some(&Value::String("cache".to_owned())),
+            soups[1].attributes.get("alias")
+        );
+    }
+
+    #[test]
+    fn locked_versions() {
+        let lockfile = r#"
+dependencies:
+- name: postgresql
+  repository: https://charts.bitnami.com/bitnami
+  version: 12.5.6
+- name: redis
+  repository: oci://registry-1.docker.io/bitnamicharts
+  version: 18.19.4
+digest: sha256:0d1f2e3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0
+generated: "2024-06-01T12:00:00Z"
+"#;
+        let result = HelmChart {
+            lockfile: Some(lockfile.to_owned()),
+        }
+        .soups(CHART, &Map::new())
+        .unwrap();
+        assert_eq!(
+            vec![("postgresql", "12.5.6"), ("redis", "18.19.4")],
+            versions(&result)
+        );
+    }
+
+    #[test]
+    fn invalid_chart() {
+        assert_eq!(
+            true,
+            HelmChart { lockfile: None }
+                .soups("dependencies: {", &Map::new())
+                .is_err()
+        );
+    }
+}
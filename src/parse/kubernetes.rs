@@ -0,0 +1,155 @@
+use super::{SoupParse, docker_base};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::BTreeSet;
+
+/// Fields of Pod specs that list containers
+const CONTAINER_FIELDS: [&str; 3] = ["containers", "initContainers", "ephemeralContainers"];
+
+/// Parses the container images of Kubernetes manifests, which may hold multiple documents
+pub struct Kubernetes {}
+
+impl SoupParse for Kubernetes {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let documents = documents(content)?;
+        let mut images = Vec::new();
+        for document in documents.iter().filter(|document| is_resource(document)) {
+            container_images(document, &mut images);
+        }
+        Ok(images
+            .into_iter()
+            .filter_map(|image| docker_base::image_soup(image, default_meta))
+            .collect())
+    }
+}
+
+/// Whether any document of a YAML file is a Kubernetes resource, i.e. has an `apiVersion` and a
+/// `kind`. Files that aren't valid YAML, e.g. Helm templates, are no manifests.
+pub fn is_manifest(content: &str) -> bool {
+    documents(content).is_ok_and(|documents| documents.iter().any(is_resource))
+}
+
+fn documents(content: &str) -> Result<Vec<YamlValue>, SoupSourceParseError> {
+    let mut documents = Vec::new();
+    for document in serde_yaml_ng::Deserializer::from_str(content) {
+        match YamlValue::deserialize(document) {
+            Ok(document) => documents.push(document),
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Kubernetes manifest ({})", e),
+                });
+            }
+        }
+    }
+    Ok(documents)
+}
+
+fn is_resource(document: &YamlValue) -> bool {
+    document.get("apiVersion").is_some_and(YamlValue::is_string)
+        && document.get("kind").is_some_and(YamlValue::is_string)
+}
+
+/// Images of the containers of all Pod specs within a resource, wherever its kind nests them,
+/// e.g. `spec.template.spec` of a Deployment or `spec.jobTemplate.spec.template.spec` of a CronJob
+fn container_images<'a>(value: &'a YamlValue, images: &mut Vec<&'a str>) {
+    match value {
+        YamlValue::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let is_containers = key
+                    .as_str()
+                    .is_some_and(|key| CONTAINER_FIELDS.contains(&key));
+                match (is_containers, value) {
+                    (true, YamlValue::Sequence(containers)) => images.extend(
+                        containers
+                            .iter()
+                            .filter_map(|container| container.get("image"))
+                            .filter_map(|image| image.as_str()),
+                    ),
+                    _ => container_images(value, images),
+                }
+            }
+        }
+        YamlValue::Sequence(sequence) => {
+            for value in sequence {
+                container_images(value, images);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn workload_images() {
+        let content = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: api
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          image: flyway/flyway:10.15
+      containers:
+        - name: api
+          image: registry.example.org/team/api@sha256:ca468b84b84846e84
+        - name: proxy
+          image: envoyproxy/envoy:v1.30.2
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: cleanup
+spec:
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+            - name: cleanup
+              image: busybox
+---
+# Not a resource
+containers:
+  - image: ignored:1.0
+"#;
+        let soups = Kubernetes {}
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.version))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("busybox".to_owned(), "latest".to_owned()),
+                ("envoyproxy/envoy".to_owned(), "v1.30.2".to_owned()),
+                ("flyway/flyway".to_owned(), "10.15".to_owned()),
+                (
+                    "registry.example.org/team/api".to_owned(),
+                    "sha256:ca468b84b84846e84".to_owned()
+                ),
+            ],
+            soups
+        );
+    }
+
+    #[test_case("apiVersion: v1\nkind: Pod\n", true ; "pod")]
+    #[test_case("foo: bar\n---\napiVersion: v1\nkind: ConfigMap\n", true ; "second_document")]
+    #[test_case("apiVersion: v2\nname: chart\n", false ; "chart")]
+    #[test_case("image: {{ .Values.image }}\n", false ; "template")]
+    fn manifests(content: &str, expected: bool) {
+        assert_eq!(expected, is_manifest(content));
+    }
+}
@@ -0,0 +1,401 @@
+use super::SoupParse;
+use super::xml::{self, Load, Node};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_PARENT_DEPTH: usize = 16;
+const MAX_EXPANSION_DEPTH: usize = 16;
+const DEFAULT_SCOPE: &str = "compile";
+
+lazy_static! {
+    static ref PROPERTY: Regex = Regex::new(r"\$\{([^}]+)\}").unwrap();
+}
+
+#[derive(Default)]
+pub struct Pom {
+    /// Path of the POM, which the relative path of its parent POM is resolved from
+    pub path: Option<PathBuf>,
+}
+
+/// Version and scope of a dependency as managed in `<dependencyManagement>`
+struct Managed {
+    version: Option<String>,
+    scope: Option<String>,
+}
+
+impl SoupParse for Pom {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let load = |path: &Path| fs::read_to_string(path).ok();
+        let project = parse(content)?;
+        let parents = parents(&project, self.path.as_deref(), &load)?;
+        Ok(soups(&project, &parents, default_meta))
+    }
+}
+
+/// SOUPs of the dependencies declared in the POM, with properties, versions and scopes inherited
+/// from its parents, nearest parent first
+fn soups(project: &Node, parents: &[Node], default_meta: &Map<String, Value>) -> BTreeSet<Soup> {
+    let properties = properties(project, parents);
+    let expand = |text: &str| expand(text, &properties);
+
+    // Managed dependencies of nearer POMs take precedence
+    let mut managed = HashMap::new();
+    for pom in parents.iter().rev().chain([project]) {
+        let dependencies = pom
+            .child("dependencyManagement")
+            .and_then(|management| management.child("dependencies"));
+        for dependency in dependencies.iter().flat_map(|d| d.children("dependency")) {
+            let scope = dependency.child_text("scope").map(expand);
+            // Imported BOMs manage the versions of other dependencies, which are not known here
+            if scope.as_deref() == Some("import") {
+                continue;
+            }
+            if let Some(key) = coordinates(dependency, &expand) {
+                let version = dependency.child_text("version").map(expand);
+                managed.insert(key, Managed { version, scope });
+            }
+        }
+    }
+
+    let dependencies = project.child("dependencies");
+    dependencies
+        .iter()
+        .flat_map(|dependencies| dependencies.children("dependency"))
+        .filter_map(|dependency| {
+            let name = coordinates(dependency, &expand)?;
+            let managed = managed.get(&name);
+            let version = dependency
+                .child_text("version")
+                .map(expand)
+                .or_else(|| managed.and_then(|managed| managed.version.to_owned()))
+                .unwrap_or("unknown".to_owned());
+            let scope = dependency
+                .child_text("scope")
+                .map(expand)
+                .or_else(|| managed.and_then(|managed| managed.scope.to_owned()))
+                .unwrap_or(DEFAULT_SCOPE.to_owned());
+            let mut attributes = Map::new();
+            if dependency.child_text("optional").map(expand).as_deref() == Some("true") {
+                attributes.insert("optional".to_owned(), Value::Bool(true));
+            }
+            Some(Soup {
+                name,
+                version,
+                ecosystem: Some(Ecosystem::Maven),
+                scope: Some(scope),
+                attributes,
+                meta: default_meta.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Name of a dependency, formatted as `groupId:artifactId`
+fn coordinates<F>(dependency: &Node, expand: &F) -> Option<String>
+where
+    F: Fn(&str) -> String,
+{
+    let group_id = expand(dependency.child_text("groupId")?);
+    let artifact_id = expand(dependency.child_text("artifactId")?);
+    Some(format!("{}:{}", group_id, artifact_id))
+}
+
+/// Properties declared in the POM and its parents, along with the coordinates of the project,
+/// e.g. `project.version`, which a project inherits from its parent unless declared
+fn properties(project: &Node, parents: &[Node]) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    for pom in parents.iter().rev().chain([project]) {
+        let declared = pom.child("properties");
+        for property in declared
+            .iter()
+            .flat_map(|declared| declared.children.iter())
+        {
+            properties.insert(property.name.to_owned(), property.text.trim().to_owned());
+        }
+    }
+    let parent = project.child("parent");
+    for key in ["groupId", "artifactId", "version"] {
+        if let Some(value) = parent.and_then(|parent| parent.child_text(key)) {
+            properties.insert(format!("project.parent.{}", key), value.to_owned());
+            if key != "artifactId" {
+                properties.insert(format!("project.{}", key), value.to_owned());
+            }
+        }
+        if let Some(value) = project.child_text(key) {
+            properties.insert(format!("project.{}", key), value.to_owned());
+        }
+    }
+    properties
+}
+
+/// Replaces references such as `${spring.version}` with the values of the properties, leaving
+/// references to unknown properties as they are
+fn expand(text: &str, properties: &HashMap<String, String>) -> String {
+    let mut expanded = text.to_owned();
+    for _ in 0..MAX_EXPANSION_DEPTH {
+        let next = PROPERTY
+            .replace_all(&expanded, |captures: &Captures| {
+                match properties.get(&captures[1]) {
+                    Some(value) => value.to_owned(),
+                    None => captures[0].to_owned(),
+                }
+            })
+            .to_string();
+        if next == expanded {
+            break;
+        }
+        expanded = next;
+    }
+    expanded
+}
+
+/// Parent POMs within the repository, nearest parent first. A parent is looked up at its
+/// `<relativePath>`, `../pom.xml` by default, and only used if its coordinates match.
+fn parents(
+    project: &Node,
+    path: Option<&Path>,
+    load: &Load,
+) -> Result<Vec<Node>, SoupSourceParseError> {
+    let mut parents: Vec<Node> = Vec::new();
+    let mut path = match path {
+        Some(path) => path.to_path_buf(),
+        None => return Ok(parents),
+    };
+    for _ in 0..MAX_PARENT_DEPTH {
+        let child = parents.last().unwrap_or(project);
+        let parent = match child.child("parent") {
+            Some(parent) => parent,
+            None => break,
+        };
+        let relative_path = match parent.child("relativePath") {
+            Some(relative_path) => relative_path.text.trim(),
+            None => "../pom.xml",
+        };
+        // An empty relative path disables the lookup within the repository
+        if relative_path.is_empty() {
+            break;
+        }
+        let mut parent_path = path.parent().unwrap_or(Path::new("")).join(relative_path);
+        if !relative_path.ends_with(".xml") {
+            parent_path.push("pom.xml");
+        }
+        let content = match load(&parent_path) {
+            Some(content) => content,
+            None => break,
+        };
+        let parent_pom = parse(&content)?;
+        if parent_pom.child_text("artifactId") != parent.child_text("artifactId") {
+            break;
+        }
+        parents.push(parent_pom);
+        path = parent_path;
+    }
+    Ok(parents)
+}
+
+fn parse(content: &str) -> Result<Node, SoupSourceParseError> {
+    match xml::parse(content)?
+        .children
+        .into_iter()
+        .find(|node| node.name == "project")
+    {
+        Some(project) => Ok(project),
+        None => Err(SoupSourceParseError {
+            message: "Missing project element".to_owned(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::xml::load_from;
+
+    const PARENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <groupId>com.example</groupId>
+  <artifactId>parent</artifactId>
+  <version>1.2.0</version>
+  <packaging>pom</packaging>
+  <properties>
+    <jackson.version>2.17.1</jackson.version>
+    <junit.version>5.10.2</junit.version>
+  </properties>
+  <dependencyManagement>
+    <dependencies>
+      <dependency>
+        <groupId>com.fasterxml.jackson.core</groupId>
+        <artifactId>jackson-databind</artifactId>
+        <version>${jackson.version}</version>
+      </dependency>
+      <dependency>
+        <groupId>org.junit.jupiter</groupId>
+        <artifactId>junit-jupiter</artifactId>
+        <version>${junit.version}</version>
+        <scope>test</scope>
+      </dependency>
+      <dependency>
+        <groupId>org.springframework.boot</groupId>
+        <artifactId>spring-boot-dependencies</artifactId>
+        <version>3.3.0</version>
+        <type>pom</type>
+        <scope>import</scope>
+      </dependency>
+    </dependencies>
+  </dependencyManagement>
+</project>
+"#;
+
+    const SERVICE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.2.0</version>
+  </parent>
+  <artifactId>service</artifactId>
+  <properties>
+    <junit.version>5.11.0</junit.version>
+  </properties>
+  <dependencies>
+    <dependency>
+      <groupId>com.fasterxml.jackson.core</groupId>
+      <artifactId>jackson-databind</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>org.junit.jupiter</groupId>
+      <artifactId>junit-jupiter</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>${project.groupId}</groupId>
+      <artifactId>shared</artifactId>
+      <version>${project.version}</version>
+    </dependency>
+    <dependency>
+      <groupId>jakarta.servlet</groupId>
+      <artifactId>jakarta.servlet-api</artifactId>
+      <version>6.0.0</version>
+      <scope>provided</scope>
+      <optional>true</optional>
+    </dependency>
+    <dependency>
+      <groupId>org.springframework.boot</groupId>
+      <artifactId>spring-boot-starter-web</artifactId>
+    </dependency>
+  </dependencies>
+</project>
+"#;
+
+    fn evaluate(content: &str, path: &str, files: &[(&str, &str)]) -> Vec<Soup> {
+        let project = parse(content).unwrap();
+        let parents = parents(&project, Some(Path::new(path)), &load_from(files)).unwrap();
+        soups(&project, &parents, &Map::new()).into_iter().collect()
+    }
+
+    #[test]
+    fn dependencies() {
+        let result = Pom::default().soups(PARENT, &Map::new());
+        assert_eq!(true, result.is_ok());
+        assert_eq!(0, result.unwrap().len());
+
+        let content = r#"<project>
+  <dependencies>
+    <dependency>
+      <groupId>org.slf4j</groupId>
+      <artifactId>slf4j-api</artifactId>
+      <version>2.0.13</version>
+    </dependency>
+  </dependencies>
+</project>"#;
+        let soups = Pom::default()
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(1, soups.len());
+        assert_eq!("org.slf4j:slf4j-api", soups[0].name);
+        assert_eq!("2.0.13", soups[0].version);
+        assert_eq!(Some(Ecosystem::Maven), soups[0].ecosystem);
+        assert_eq!(Some("compile".to_owned()), soups[0].scope);
+    }
+
+    #[test]
+    fn inherited_from_parent() {
+        let soups = evaluate(
+            SERVICE,
+            "/repo/service/pom.xml",
+            &[("/repo/service/../pom.xml", PARENT)],
+        );
+        let versions = soups
+            .iter()
+            .map(|soup| {
+                (
+                    soup.name.as_str(),
+                    soup.version.as_str(),
+                    soup.scope.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("com.example:shared", "1.2.0", "compile"),
+                (
+                    "com.fasterxml.jackson.core:jackson-databind",
+                    "2.17.1",
+                    "compile"
+                ),
+                ("jakarta.servlet:jakarta.servlet-api", "6.0.0", "provided"),
+                ("org.junit.jupiter:junit-jupiter", "5.11.0", "test"),
+                (
+                    "org.springframework.boot:spring-boot-starter-web",
+                    "unknown",
+                    "compile"
+                ),
+            ],
+            versions
+        );
+        assert_eq!(
+            Some(&Value::Bool(true)),
+            soups[2].attributes.get("optional")
+        );
+    }
+
+    #[test]
+    fn parent_outside_repository() {
+        let soups = evaluate(SERVICE, "/repo/service/pom.xml", &[]);
+        assert_eq!("1.2.0", soups[0].version);
+        assert_eq!("unknown", soups[1].version);
+        assert_eq!(Some("compile".to_owned()), soups[3].scope);
+    }
+
+    #[test]
+    fn parent_with_other_coordinates() {
+        let other = PARENT.replace(
+            "<artifactId>parent</artifactId>",
+            "<artifactId>other</artifactId>",
+        );
+        let soups = evaluate(
+            SERVICE,
+            "/repo/service/pom.xml",
+            &[("/repo/service/../pom.xml", &other)],
+        );
+        assert_eq!("unknown", soups[1].version);
+    }
+
+    #[test]
+    fn missing_project() {
+        let result = Pom::default().soups("<settings></settings>", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const DEV_SCOPE: &str = "dev";
+/// Environments that dependencies may be only needed in during development
+const DEV_ENVIRONMENTS: [&str; 2] = ["dev", "test"];
+/// Options of git dependencies that select the checked out reference, by precedence
+const GIT_REFERENCES: [&str; 3] = ["tag", "ref", "branch"];
+
+lazy_static! {
+    static ref DEPS: Regex =
+        Regex::new(r"(?s)\bdefp?\s+deps\b.*?\bdo\b(?P<body>.*?)\n\s*end\b").unwrap();
+    static ref DEPENDENCY: Regex =
+        Regex::new(r"\{\s*:(?P<name>\w+)\s*,(?P<rest>[^{}]*)\}").unwrap();
+    static ref REQUIREMENT: Regex = Regex::new(r#"^\s*"(?P<requirement>[^"]*)""#).unwrap();
+    static ref OPTION: Regex =
+        Regex::new(r#"\b(?P<key>only|git|github|tag|ref|branch|path):\s*(?P<value>"[^"]*"|\[[^\]]*\]|:\w+)"#)
+            .unwrap();
+    static ref ATOM: Regex = Regex::new(r":(\w+)").unwrap();
+    static ref HEX_PACKAGE: Regex = Regex::new(
+        r#""(?P<name>\w+)":\s*\{:hex,\s*:\w+,\s*"(?P<version>[^"]+)",\s*"(?P<inner_checksum>[0-9a-f]+)"(?:[^\n]*,\s*"(?P<checksum>[0-9a-f]{64})"\})?"#
+    )
+    .unwrap();
+    static ref GIT_PACKAGE: Regex =
+        Regex::new(r#""(?P<name>\w+)":\s*\{:git,\s*"(?P<url>[^"]+)",\s*"(?P<revision>[0-9a-f]+)""#)
+            .unwrap();
+}
+
+/// Parses the dependencies of an Elixir project (mix.exs), with versions resolved in the
+/// mix.lock next to it
+#[derive(Default)]
+pub struct Mix {
+    /// Content of the mix.lock next to the mix.exs
+    pub lockfile: Option<String>,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+struct Dependency {
+    name: String,
+    requirement: Option<String>,
+    scope: &'static str,
+    source: Option<String>,
+    /// Tag, ref or branch of a git dependency
+    git_reference: Option<String>,
+}
+
+struct LockedPackage {
+    name: String,
+    version: String,
+    checksum: Option<String>,
+    source: Option<String>,
+}
+
+impl SoupParse for Mix {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let locked = match &self.lockfile {
+            Some(lockfile) => locked_packages(lockfile)?,
+            None => Vec::new(),
+        };
+        let mut soups = BTreeSet::new();
+        let mut visited = HashSet::new();
+        for dependency in dependencies(content) {
+            let mut attributes = Map::new();
+            if let Some(source) = dependency.source {
+                attributes.insert("source".to_owned(), Value::String(source));
+            }
+            let locked_package = locked
+                .iter()
+                .find(|package| package.name == dependency.name);
+            let version = match locked_package {
+                Some(package) => {
+                    if let Some(requirement) = dependency.requirement {
+                        attributes.insert("requirement".to_owned(), Value::String(requirement));
+                    }
+                    insert_lock_attributes(&mut attributes, package);
+                    package.version.to_owned()
+                }
+                None => dependency
+                    .git_reference
+                    .or(dependency.requirement)
+                    .unwrap_or("unknown".to_owned()),
+            };
+            visited.insert(dependency.name.to_owned());
+            soups.insert(Soup {
+                name: dependency.name,
+                version,
+                ecosystem: Some(Ecosystem::Hex),
+                scope: Some(dependency.scope.to_owned()),
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+
+        // mix.lock does not record the environments of packages, so the packages that
+        // dependencies depend on are added as runtime dependencies
+        if self.include_transitive {
+            for package in &locked {
+                if !visited.insert(package.name.to_owned()) {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                insert_lock_attributes(&mut attributes, package);
+                soups.insert(Soup {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Hex),
+                    scope: Some(RUNTIME_SCOPE.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    if let Some(source) = &package.source {
+        attributes.insert("source".to_owned(), Value::String(source.to_owned()));
+    }
+    if let Some(checksum) = &package.checksum {
+        attributes.insert("checksum".to_owned(), Value::String(checksum.to_owned()));
+    }
+}
+
+/// Dependencies listed in the `deps` function of a mix.exs, e.g. `{:phoenix, "~> 1.7"}` or
+/// `{:credo, "~> 1.7", only: [:dev, :test], runtime: false}`. Dependencies within the
+/// repository, i.e. with a `path`, are no SOUP of their own.
+fn dependencies(content: &str) -> Vec<Dependency> {
+    let body = match DEPS.captures(content) {
+        Some(captures) => captures.name("body").map_or("", |body| body.as_str()),
+        None => return Vec::new(),
+    };
+    let mut dependencies = Vec::new();
+    for captures in DEPENDENCY.captures_iter(body) {
+        let rest = &captures["rest"];
+        let mut environments = Vec::new();
+        let mut source = None;
+        let mut git_references = Vec::new();
+        let mut is_path = false;
+        for option in OPTION.captures_iter(rest) {
+            let value = option["value"].trim_matches('"');
+            match &option["key"] {
+                "only" => {
+                    environments.extend(ATOM.captures_iter(value).map(|atom| atom[1].to_owned()))
+                }
+                "git" => source = Some(value.to_owned()),
+                "github" => source = Some(format!("https://github.com/{}", value)),
+                "path" => is_path = true,
+                key => git_references.push((key.to_owned(), value.to_owned())),
+            }
+        }
+        if is_path {
+            continue;
+        }
+        let is_dev = !environments.is_empty()
+            && environments
+                .iter()
+                .all(|environment| DEV_ENVIRONMENTS.contains(&environment.as_str()));
+        dependencies.push(Dependency {
+            name: captures["name"].to_owned(),
+            requirement: REQUIREMENT
+                .captures(rest)
+                .map(|requirement| requirement["requirement"].to_owned()),
+            scope: match is_dev {
+                true => DEV_SCOPE,
+                false => RUNTIME_SCOPE,
+            },
+            source,
+            git_reference: GIT_REFERENCES.iter().find_map(|key| {
+                git_references
+                    .iter()
+                    .find(|(option, _)| option == key)
+                    .map(|(_, value)| value.to_owned())
+            }),
+        });
+    }
+    dependencies
+}
+
+/// Packages resolved in a mix.lock, which maps the names of packages to tuples such as
+/// `{:hex, :name, "version", "inner checksum", ..., "repository", "outer checksum"}` or
+/// `{:git, "url", "revision", ...}`. The outer checksum is that of the package tarball as
+/// published, whereas lock files of older versions of Hex only record the inner one.
+fn locked_packages(lockfile: &str) -> Result<Vec<LockedPackage>, SoupSourceParseError> {
+    if !lockfile.trim_start().starts_with("%{") {
+        return Err(SoupSourceParseError {
+            message: "Invalid mix.lock (no map of packages)".to_owned(),
+        });
+    }
+    let hex_packages = HEX_PACKAGE
+        .captures_iter(lockfile)
+        .map(|captures| LockedPackage {
+            name: captures["name"].to_owned(),
+            version: captures["version"].to_owned(),
+            checksum: captures
+                .name("checksum")
+                .or(captures.name("inner_checksum"))
+                .map(|checksum| format!("sha256:{}", checksum.as_str())),
+            source: None,
+        });
+    let git_packages = GIT_PACKAGE
+        .captures_iter(lockfile)
+        .map(|captures| LockedPackage {
+            name: captures["name"].to_owned(),
+            version: captures["revision"].to_owned(),
+            checksum: None,
+            source: Some(captures["url"].to_owned()),
+        });
+    Ok(hex_packages.chain(git_packages).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{scopes, versions};
+    use test_case::test_case;
+
+    const MANIFEST: &str = r#"
+defmodule Gateway.MixProject do
+  use Mix.Project
+
+  def project do
+    [
+      app: :gateway,
+      version: "0.1.0",
+      elixir: "~> 1.16",
+      deps: deps()
+    ]
+  end
+
+  defp deps do
+    [
+      {:phoenix, "~> 1.7.14"},
+      {:jason, "~> 1.4"},
+      {:modbus, github: "acme/modbus", tag: "v0.3.1"},
+      {:protocol, path: "../protocol"},
+      {:credo, "~> 1.7", only: [:dev, :test], runtime: false},
+      {:mox, "~> 1.1", only: :test}
+    ]
+  end
+end
+"#;
+
+    const LOCKFILE: &str = r#"%{
+  "castore": {:hex, :castore, "1.0.8", "dedcf20ea746694647f883590b82d9e96014057aff1d44d03ec90f36a5c0dc6e", [:mix], [], "hexpm", "0b2b66d2ee742cb1d9cb8c8be3b43c3a70ee8651f37b75a8b982e036752983f1"},
+  "credo": {:hex, :credo, "1.7.7", "771445037228f763f9b2afd612b6aa2fd8e28432a95dbbc60d8e03ce71ba4446", [:mix], [], "hexpm", "8bc87496c9aaacdc3f90f01b7b0582467b69b4bd2441fe8aae3109d843cc2f2e"},
+  "jason": {:hex, :jason, "1.4.3", "d3f984eeb96fe53b85d20e0b049f03e57d075b5acda3ac8d465c969a2536c17b", [:mix], [], "hexpm", "9a90e868927f7c777689baa16d86f4d0e086d968db5c05d917ccff6d443e58a3"},
+  "modbus": {:git, "https://github.com/acme/modbus.git", "8e0b7c1d2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d", [tag: "v0.3.1"]},
+  "phoenix": {:hex, :phoenix, "1.7.14", "a7d0b3f1bc95987044ddada111e77bd7f75646a08518942c72a8440278ae7825", [:mix], [{:castore, ">= 0.0.0", [hex: :castore, repo: "hexpm", optional: false]}], "hexpm", "c7859bc56cc5dfef19ecfc240775dae358cbaa530231118a9e014df392ace61a"},
+}
+"#;
+
+    #[test]
+    fn declared_versions() {
+        let result = Mix::default().soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("credo", "~> 1.7", "dev"),
+                ("jason", "~> 1.4", "runtime"),
+                ("modbus", "v0.3.1", "runtime"),
+                ("mox", "~> 1.1", "dev"),
+                ("phoenix", "~> 1.7.14", "runtime"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case(false, &[
+        ("credo", "1.7.7", "dev"),
+        ("jason", "1.4.3", "runtime"),
+        ("modbus", "8e0b7c1d2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d", "runtime"),
+        ("mox", "~> 1.1", "dev"),
+        ("phoenix", "1.7.14", "runtime"),
+    ])]
+    #[test_case(true, &[
+        ("castore", "1.0.8", "runtime"),
+        ("credo", "1.7.7", "dev"),
+        ("jason", "1.4.3", "runtime"),
+        ("modbus", "8e0b7c1d2a3f4e5d6c7b8a9f0e1d2c3b4a5f6e7d", "runtime"),
+        ("mox", "~> 1.1", "dev"),
+        ("phoenix", "1.7.14", "runtime"),
+    ])]
+    fn locked_versions(include_transitive: bool, soups: &[(&str, &str, &str)]) {
+        let mix = Mix {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive,
+        };
+        let result = mix.soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(soups, scopes(&result));
+    }
+
+    #[test]
+    fn locked_attributes() {
+        let mix = Mix {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: false,
+        };
+        let soups = mix.soups(MANIFEST, &Map::new()).unwrap();
+        let attribute = |name: &str, key: &str| {
+            soups
+                .iter()
+                .find(|soup| soup.name == name)
+                .and_then(|soup| soup.attributes.get(key))
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_owned())
+        };
+        assert_eq!(
+            Some(
+                "sha256:9a90e868927f7c777689baa16d86f4d0e086d968db5c05d917ccff6d443e58a3"
+                    .to_owned()
+            ),
+            attribute("jason", "checksum")
+        );
+        assert_eq!(
+            Some("https://github.com/acme/modbus.git".to_owned()),
+            attribute("modbus", "source")
+        );
+        assert_eq!(Some("~> 1.4".to_owned()), attribute("jason", "requirement"));
+    }
+
+    #[test_case(r#"tag: "v1.2.0""#, "v1.2.0" ; "tag")]
+    #[test_case(r#"ref: "8e0b7c1""#, "8e0b7c1" ; "git_ref")]
+    #[test_case(r#"branch: "main""#, "main" ; "branch")]
+    #[test_case(r#"branch: "main", tag: "v1.2.0""#, "v1.2.0" ; "tag_over_branch")]
+    #[test_case("", "unknown" ; "default_branch")]
+    fn git_references(options: &str, version: &str) {
+        let content = format!(
+            "defp deps do\n  [\n    {{:modbus, git: \"https://github.com/acme/modbus.git\", {}}}\n  ]\nend",
+            options
+        );
+        let result = Mix::default().soups(&content, &Map::new()).unwrap();
+        assert_eq!(vec![("modbus", version)], versions(&result));
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let mix = Mix {
+            lockfile: Some("[]".to_owned()),
+            include_transitive: false,
+        };
+        assert_eq!(true, mix.soups(MANIFEST, &Map::new()).is_err());
+    }
+}
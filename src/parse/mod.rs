@@ -10,8 +10,116 @@ pub trait SoupParse {
     ) -> Result<BTreeSet<Soup>, SoupSourceParseError>;
 }
 
+/// Scopes of dependencies, from the strongest to the weakest. Packages needed in several scopes
+/// are reported in the strongest, so that excluding e.g. dev dependencies from a report never drops
+/// a package that ships with the software.
+const SCOPE_PRECEDENCE: [&str; 7] = [
+    "runtime", "compile", "optional", "peer", "build", "test", "dev",
+];
+
+/// Rank of a scope in `SCOPE_PRECEDENCE`, where unknown scopes are as strong as runtime
+fn scope_rank(scope: &str) -> usize {
+    SCOPE_PRECEDENCE
+        .iter()
+        .position(|known| *known == scope)
+        .unwrap_or(0)
+}
+
+/// The stronger of two scopes, or the first if they are equally strong
+pub fn strongest_scope<'a>(a: &'a str, b: &'a str) -> &'a str {
+    match scope_rank(b) < scope_rank(a) {
+        true => b,
+        false => a,
+    }
+}
+
+/// Inserts a SOUP unless one of the same name and version and an equal or stronger scope is
+/// already present, which it replaces otherwise
+pub fn insert_strongest(soups: &mut BTreeSet<Soup>, soup: Soup) {
+    let rank = |soup: &Soup| soup.scope.as_deref().map_or(0, scope_rank);
+    match soups.get(&soup) {
+        Some(existing) if rank(existing) <= rank(&soup) => {}
+        _ => {
+            soups.replace(soup);
+        }
+    }
+}
+
+/// Names and versions of SOUPs, along with a property of each, for tests to compare at a glance
+#[cfg(test)]
+pub fn summarize<'a, T>(
+    soups: &'a BTreeSet<Soup>,
+    property: impl Fn(&'a Soup) -> T,
+) -> Vec<(&'a str, &'a str, T)> {
+    soups
+        .iter()
+        .map(|soup| (soup.name.as_str(), soup.version.as_str(), property(soup)))
+        .collect()
+}
+
+/// Names and versions of SOUPs
+#[cfg(test)]
+pub fn versions(soups: &BTreeSet<Soup>) -> Vec<(&str, &str)> {
+    soups
+        .iter()
+        .map(|soup| (soup.name.as_str(), soup.version.as_str()))
+        .collect()
+}
+
+/// Names, versions and scopes of SOUPs
+#[cfg(test)]
+pub fn scopes(soups: &BTreeSet<Soup>) -> Vec<(&str, &str, &str)> {
+    summarize(soups, |soup| soup.scope.as_deref().unwrap_or_default())
+}
+
+pub mod apk;
 pub mod apt;
 pub mod cargo;
+pub mod cargo_lock;
+pub mod cmake;
+pub mod composer;
+pub mod conan;
 pub mod csproj;
+pub mod dependency_graph;
 pub mod docker_base;
+pub mod docker_compose;
+pub mod docker_download;
+pub mod dockerfile;
+pub mod gem;
+pub mod gemfile;
+pub mod git_modules;
+pub mod github_actions;
+pub mod gitlab_ci;
+pub mod go_mod;
+pub mod go_sum;
+pub mod gradle;
+pub mod helm;
+pub mod kubernetes;
+pub mod maven;
+pub mod mix;
+pub mod msbuild;
+pub mod npm_global;
+pub mod npm_lock;
+pub mod nuget_lock;
 pub mod package_json;
+pub mod package_lock;
+pub mod packages_config;
+pub mod pep508;
+pub mod pip;
+pub mod pipfile;
+pub mod pipfile_lock;
+pub mod pnpm_lock;
+pub mod poetry_lock;
+pub mod pubspec;
+pub mod pyproject;
+pub mod python_lock;
+pub mod requirements;
+pub mod rpm;
+pub mod shell;
+pub mod terraform;
+pub mod uv_lock;
+pub mod vcpkg;
+pub mod vendored;
+pub mod version_catalog;
+pub mod xml;
+pub mod yarn_lock;
@@ -0,0 +1,488 @@
+use super::xml::{self, Load, Node};
+use crate::soup::model::SoupSourceParseError;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+const MAX_IMPORT_DEPTH: usize = 16;
+
+lazy_static! {
+    static ref PROPERTY: Regex = Regex::new(r"\$\(([A-Za-z_][A-Za-z0-9_.\-]*)\)").unwrap();
+    static ref PATH_OF_FILE_ABOVE: Regex = Regex::new(
+        r"\$\(\[MSBuild\]::GetPathOfFileAbove\(\s*'?([^',)]+?)'?\s*(?:,\s*'([^']*)'\s*)?\)\)"
+    )
+    .unwrap();
+    static ref COMPARISON: Regex = Regex::new(r"^'([^']*)'\s*(==|!=)\s*'([^']*)'$").unwrap();
+}
+
+/// Properties and items of an evaluated MSBuild project
+pub struct Evaluation {
+    /// Property values by lowercase property name
+    properties: HashMap<String, String>,
+    pub items: Vec<Item>,
+}
+
+pub struct Item {
+    pub kind: String,
+    pub include: String,
+    /// Metadata values by lowercase metadata name
+    metadata: HashMap<String, String>,
+}
+
+impl Evaluation {
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .get(&name.to_lowercase())
+            .map(|value| value.as_str())
+    }
+
+    pub fn items(&self, kind: &str) -> impl Iterator<Item = &Item> {
+        self.items
+            .iter()
+            .filter(move |item| item.kind.eq_ignore_ascii_case(kind))
+    }
+}
+
+impl Item {
+    pub fn metadata(&self, name: &str) -> Option<&str> {
+        self.metadata
+            .get(&name.to_lowercase())
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Project file along with the directory that relative imports are resolved from
+struct File {
+    root: Node,
+    directory: Option<PathBuf>,
+}
+
+/// Evaluates a project the way MSBuild does in two passes: properties are evaluated first, in
+/// the order of the project and the files it imports, and items second, with the final property
+/// values.
+///
+/// The files in `implicit_imports`, e.g. `Directory.Build.props`, are evaluated before the
+/// project. Conditions are supported for comparisons such as `'$(Configuration)' == 'Release'`,
+/// other conditions are considered to be true. `GetPathOfFileAbove` does not search above
+/// `root`, if given.
+pub fn evaluate(
+    content: &str,
+    path: Option<&Path>,
+    root: Option<&Path>,
+    implicit_imports: &[PathBuf],
+    load: &Load,
+) -> Result<Evaluation, SoupSourceParseError> {
+    let mut properties = HashMap::new();
+    if let Some(path) = path {
+        let directory = path.parent().unwrap_or(Path::new(""));
+        properties.insert(
+            "msbuildprojectdirectory".to_owned(),
+            directory.to_string_lossy().to_string(),
+        );
+        if let Some(name) = path.file_stem() {
+            properties.insert(
+                "msbuildprojectname".to_owned(),
+                name.to_string_lossy().to_string(),
+            );
+        }
+    }
+    let mut evaluator = Evaluator {
+        properties,
+        files: Vec::new(),
+        visited: HashSet::new(),
+        root: root.map(normalize),
+        load,
+    };
+    for import in implicit_imports {
+        evaluator.import(import, 0)?;
+    }
+    let project = File {
+        root: parse(content)?,
+        directory: path
+            .and_then(|path| path.parent())
+            .map(|dir| dir.to_path_buf()),
+    };
+    evaluator.evaluate_properties(&project, 0)?;
+    evaluator.files.push(project);
+
+    let mut items: Vec<Item> = Vec::new();
+    for file in &evaluator.files {
+        evaluator.evaluate_items(file, &mut items);
+    }
+    Ok(Evaluation {
+        properties: evaluator.properties,
+        items,
+    })
+}
+
+struct Evaluator<'a> {
+    properties: HashMap<String, String>,
+    /// Evaluated files, in the order that their items are evaluated in
+    files: Vec<File>,
+    visited: HashSet<PathBuf>,
+    /// Directory that `GetPathOfFileAbove` stops searching at
+    root: Option<PathBuf>,
+    load: &'a Load<'a>,
+}
+
+impl Evaluator<'_> {
+    fn import(&mut self, path: &Path, depth: usize) -> Result<(), SoupSourceParseError> {
+        let path = normalize(path);
+        if depth > MAX_IMPORT_DEPTH || !self.visited.insert(path.to_owned()) {
+            return Ok(());
+        }
+        let content = match (self.load)(&path) {
+            Some(content) => content,
+            None => return Ok(()),
+        };
+        let file = File {
+            root: parse(&content)?,
+            directory: path.parent().map(|dir| dir.to_path_buf()),
+        };
+        self.evaluate_properties(&file, depth)?;
+        self.files.push(file);
+        Ok(())
+    }
+
+    fn evaluate_properties(
+        &mut self,
+        file: &File,
+        depth: usize,
+    ) -> Result<(), SoupSourceParseError> {
+        let directory = match &file.directory {
+            Some(directory) => format!("{}/", directory.to_string_lossy()),
+            None => String::new(),
+        };
+        for element in &file.root.children {
+            self.properties
+                .insert("msbuildthisfiledirectory".to_owned(), directory.to_owned());
+            if !self.condition_holds(element) {
+                continue;
+            }
+            match element.name.as_str() {
+                "PropertyGroup" => {
+                    for property in &element.children {
+                        if self.condition_holds(property) {
+                            let value = self.expand(&property.text);
+                            self.properties.insert(property.name.to_lowercase(), value);
+                        }
+                    }
+                }
+                "Import" if element.attribute("Sdk").is_none() => {
+                    let project = match element.attribute("Project") {
+                        Some(project) => project,
+                        None => continue,
+                    };
+                    if let Some(path) = self.import_path(project, &file.directory) {
+                        self.import(&path, depth + 1)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate_items(&self, file: &File, items: &mut Vec<Item>) {
+        let groups = file
+            .root
+            .children
+            .iter()
+            .filter(|element| element.name == "ItemGroup" && self.condition_holds(element));
+        for group in groups {
+            for element in &group.children {
+                if !self.condition_holds(element) {
+                    continue;
+                }
+                let mut metadata = element
+                    .attributes
+                    .iter()
+                    .filter(|(key, _)| {
+                        !["Include", "Update", "Remove", "Exclude", "Condition"]
+                            .iter()
+                            .any(|reserved| key.eq_ignore_ascii_case(reserved))
+                    })
+                    .map(|(key, value)| (key.to_lowercase(), self.expand(value)))
+                    .collect::<HashMap<String, String>>();
+                for child in &element.children {
+                    if self.condition_holds(child) {
+                        metadata.insert(child.name.to_lowercase(), self.expand(&child.text));
+                    }
+                }
+                let is_kind = |item: &Item| item.kind.eq_ignore_ascii_case(&element.name);
+                if let Some(include) = element.attribute("Include") {
+                    for include in split_items(&self.expand(include)) {
+                        items.push(Item {
+                            kind: element.name.to_owned(),
+                            include,
+                            metadata: metadata.clone(),
+                        });
+                    }
+                } else if let Some(update) = element.attribute("Update") {
+                    let updated = split_items(&self.expand(update));
+                    for item in items.iter_mut().filter(|item| is_kind(item)) {
+                        if updated
+                            .iter()
+                            .any(|update| update.eq_ignore_ascii_case(&item.include))
+                        {
+                            item.metadata.extend(metadata.clone());
+                        }
+                    }
+                } else if let Some(remove) = element.attribute("Remove") {
+                    let removed = split_items(&self.expand(remove));
+                    items.retain(|item| {
+                        !is_kind(item)
+                            || !removed
+                                .iter()
+                                .any(|remove| remove.eq_ignore_ascii_case(&item.include))
+                    });
+                }
+            }
+        }
+    }
+
+    /// Path of an imported project, with properties expanded and `GetPathOfFileAbove` resolved
+    fn import_path(&self, project: &str, directory: &Option<PathBuf>) -> Option<PathBuf> {
+        let directory = directory.to_owned().unwrap_or_default();
+        if let Some(captures) = PATH_OF_FILE_ABOVE.captures(project) {
+            let file_name = self.expand(&captures[1]);
+            let start = match captures.get(2) {
+                Some(start) => directory.join(self.expand(start.as_str()).replace('\\', "/")),
+                None => directory,
+            };
+            let mut dir = Some(normalize(&start));
+            while let Some(current_dir) = dir {
+                if !self
+                    .root
+                    .as_ref()
+                    .is_none_or(|root| current_dir.starts_with(root))
+                {
+                    break;
+                }
+                let candidate = current_dir.join(&file_name);
+                if (self.load)(&candidate).is_some() {
+                    return Some(candidate);
+                }
+                dir = current_dir.parent().map(|parent| parent.to_path_buf());
+            }
+            return None;
+        }
+        let project = self.expand(project).replace('\\', "/");
+        match project.is_empty() || project.contains('*') {
+            true => None,
+            false => Some(directory.join(project)),
+        }
+    }
+
+    fn expand(&self, value: &str) -> String {
+        PROPERTY
+            .replace_all(value.trim(), |captures: &Captures| {
+                match self.properties.get(&captures[1].to_lowercase()) {
+                    Some(value) => value.to_owned(),
+                    None => String::new(),
+                }
+            })
+            .to_string()
+    }
+
+    fn condition_holds(&self, node: &Node) -> bool {
+        let condition = match node.attribute("Condition") {
+            Some(condition) => self.expand(condition),
+            None => return true,
+        };
+        let lowercase = condition.to_lowercase();
+        if lowercase.contains(" or ") || lowercase.contains(" and ") || condition.contains('(') {
+            return true;
+        }
+        match COMPARISON.captures(condition.trim()) {
+            Some(captures) => {
+                let equal = captures[1].trim().eq_ignore_ascii_case(captures[3].trim());
+                match &captures[2] {
+                    "==" => equal,
+                    _ => !equal,
+                }
+            }
+            None => true,
+        }
+    }
+}
+
+fn split_items(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_owned())
+        .collect()
+}
+
+/// Resolves `.` and `..` in a path without accessing the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn parse(content: &str) -> Result<Node, SoupSourceParseError> {
+    match xml::parse(content)?
+        .children
+        .into_iter()
+        .find(|node| node.name == "Project")
+    {
+        Some(project) => Ok(project),
+        None => Err(SoupSourceParseError {
+            message: "Missing Project element".to_owned(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::xml::load_from;
+    use test_case::test_case;
+
+    #[test]
+    fn properties() {
+        let content = r#"
+<Project>
+    <PropertyGroup>
+        <Major>7</Major>
+        <Version>$(Major).2.1</Version>
+        <Unused Condition="'$(Major)' != '7'">true</Unused>
+        <Release Condition="'$(Configuration)' == ''">true</Release>
+    </PropertyGroup>
+</Project>
+        "#;
+        let evaluation = evaluate(content, None, None, &[], &load_from(&[])).unwrap();
+        assert_eq!(Some("7.2.1"), evaluation.property("version"));
+        assert_eq!(None, evaluation.property("Unused"));
+        assert_eq!(Some("true"), evaluation.property("Release"));
+    }
+
+    #[test]
+    fn items_evaluated_after_properties() {
+        let content = r#"
+<Project>
+    <ItemGroup>
+        <PackageReference Include="Some.Package;Another.Package" Version="$(PackageVersion)" />
+        <PackageReference Update="Another.Package">
+            <Version>2.0.0</Version>
+        </PackageReference>
+        <PackageReference Include="Removed.Package" Version="1.0.0" />
+        <PackageReference Remove="Removed.Package" />
+    </ItemGroup>
+    <PropertyGroup>
+        <PackageVersion>1.0.0</PackageVersion>
+    </PropertyGroup>
+</Project>
+        "#;
+        let evaluation = evaluate(content, None, None, &[], &load_from(&[])).unwrap();
+        let items = evaluation
+            .items("PackageReference")
+            .map(|item| (item.include.as_str(), item.metadata("Version")))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("Some.Package", Some("1.0.0")),
+                ("Another.Package", Some("2.0.0"))
+            ],
+            items
+        );
+    }
+
+    #[test]
+    fn imports() {
+        let load = load_from(&[
+            (
+                "/repo/Directory.Build.props",
+                r#"<Project>
+                    <Import Project="$([MSBuild]::GetPathOfFileAbove('Directory.Build.props', '$(MSBuildThisFileDirectory)../'))" />
+                    <Import Project="build\versions.props" />
+                </Project>"#,
+            ),
+            (
+                "/Directory.Build.props",
+                "<Project><PropertyGroup><Outer>1.0.0</Outer></PropertyGroup></Project>",
+            ),
+            (
+                "/repo/build/versions.props",
+                "<Project><PropertyGroup><Inner>$(Outer)</Inner></PropertyGroup></Project>",
+            ),
+        ]);
+        let evaluation = evaluate(
+            "<Project />",
+            Some(Path::new("/repo/src/App/App.csproj")),
+            None,
+            &[PathBuf::from("/repo/Directory.Build.props")],
+            &load,
+        )
+        .unwrap();
+        assert_eq!(Some("1.0.0"), evaluation.property("Inner"));
+        assert_eq!(Some("App"), evaluation.property("MSBuildProjectName"));
+    }
+
+    #[test_case("/repo" ; "project_in_subdirectory")]
+    #[test_case("/repo/src/App" ; "project_at_root")]
+    fn path_of_file_above_within_root(root: &str) {
+        let props = Path::new(root).join("Directory.Build.props");
+        let above = normalize(&Path::new(root).join("../Directory.Build.props"));
+        let (props_path, above_path) = (props.to_string_lossy(), above.to_string_lossy());
+        let files = [
+            (
+                props_path.as_ref(),
+                r#"<Project>
+                    <Import Project="$([MSBuild]::GetPathOfFileAbove('Directory.Build.props', '$(MSBuildThisFileDirectory)../'))" />
+                </Project>"#,
+            ),
+            (
+                above_path.as_ref(),
+                "<Project><PropertyGroup><Outer>1.0.0</Outer></PropertyGroup></Project>",
+            ),
+        ];
+        let load = load_from(&files);
+        let evaluation = evaluate(
+            "<Project />",
+            Some(Path::new("/repo/src/App/App.csproj")),
+            Some(Path::new(root)),
+            &[props.to_owned()],
+            &load,
+        )
+        .unwrap();
+        assert_eq!(None, evaluation.property("Outer"));
+    }
+
+    #[test]
+    fn import_cycle() {
+        let load = load_from(&[(
+            "a.props",
+            r#"<Project><Import Project="a.props" /></Project>"#,
+        )]);
+        let result = evaluate(
+            "<Project />",
+            None,
+            None,
+            &[PathBuf::from("a.props")],
+            &load,
+        );
+        assert_eq!(true, result.is_ok());
+    }
+
+    #[test]
+    fn missing_project_element() {
+        let result = evaluate("<Other />", None, None, &[], &load_from(&[]));
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use super::{SoupParse, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses `npm install --global` commands, which install tools outside of any package.json
+pub struct NpmGlobal {}
+
+static SUBCOMMANDS: [&str; 3] = ["install", "i", "add"];
+static OPTIONS_WITH_VALUE: [&str; 4] = ["--prefix", "--registry", "--tag", "--cache"];
+static GLOBAL_OPTIONS: [&str; 3] = ["-g", "--global", "--location=global"];
+
+impl SoupParse for NpmGlobal {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for command in shell::commands(content) {
+            if !command
+                .iter()
+                .any(|word| GLOBAL_OPTIONS.contains(&word.as_str()))
+            {
+                continue;
+            }
+            let words = match shell::subcommand_words(
+                &command,
+                |program| program == "npm",
+                &SUBCOMMANDS,
+                &OPTIONS_WITH_VALUE,
+            ) {
+                Some(words) => words,
+                None => continue,
+            };
+            for package in shell::operands(words, &OPTIONS_WITH_VALUE) {
+                if let Some((name, version)) = name_and_version(package) {
+                    result.insert(Soup {
+                        name: name.to_owned(),
+                        version: version.unwrap_or("unknown").to_owned(),
+                        ecosystem: Some(Ecosystem::Npm),
+                        meta: default_meta.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Name and version of a package specification such as `typescript@5.4.5` or `@angular/cli@18`,
+/// skipping local folders, tarballs and git URLs
+fn name_and_version(package: &str) -> Option<(&str, Option<&str>)> {
+    if package.starts_with(['.', '/', '~']) || package.contains("://") || package.ends_with(".tgz")
+    {
+        return None;
+    }
+    let (name, version) = match package.char_indices().skip(1).find(|(_, c)| *c == '@') {
+        Some((index, _)) => (&package[..index], Some(&package[index + 1..])),
+        None => (package, None),
+    };
+    // Specifications like `owner/repo` refer to GitHub repositories rather than packages
+    if name.contains('/') && !name.starts_with('@') {
+        return None;
+    }
+    Some((name, version.filter(|version| !version.is_empty())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+    use test_case::test_case;
+
+    #[test_case("npm install -g typescript@5.4.5", &[("typescript", "5.4.5")])]
+    #[test_case("RUN npm i --global @angular/cli@18 pnpm", &[("@angular/cli", "18"), ("pnpm", "unknown")])]
+    #[test_case("npm --location=global install --prefix /usr/local yarn@1.22.22", &[("yarn", "1.22.22")])]
+    #[test_case("npm install -g ./tools/cli owner/repo https://example.org/tool.tgz", &[])]
+    fn packages(input: &str, expected: &[(&str, &str)]) {
+        let result = NpmGlobal {}.soups(input, &Map::new()).unwrap();
+        assert_eq!(expected, versions(&result));
+    }
+
+    #[test_case("npm install typescript@5.4.5")]
+    #[test_case("npm ci")]
+    #[test_case("npm uninstall -g typescript")]
+    fn no_global_install(input: &str) {
+        assert_eq!(0, NpmGlobal {}.soups(input, &Map::new()).unwrap().len());
+    }
+}
@@ -0,0 +1,138 @@
+use super::{dependency_graph, package_lock, pnpm_lock, strongest_scope, yarn_lock};
+use crate::soup::model::SoupSourceParseError;
+use std::collections::HashMap;
+
+/// Content of a lockfile that resolves the dependencies of a package.json
+pub enum NpmLockfile {
+    Npm(String),
+    Yarn(String),
+    Pnpm(String),
+}
+
+impl NpmLockfile {
+    /// Parses the lockfile, resolving the dependencies of the package in the `importer`
+    /// directory relative to the lockfile
+    pub fn parse(&self, importer: &str) -> Result<NpmLock, SoupSourceParseError> {
+        match self {
+            NpmLockfile::Npm(content) => package_lock::parse(content, importer),
+            NpmLockfile::Yarn(content) => yarn_lock::parse(content),
+            NpmLockfile::Pnpm(content) => pnpm_lock::parse(content, importer),
+        }
+    }
+}
+
+/// Packages resolved by npm, yarn or pnpm, independent of the lockfile format
+#[derive(Default)]
+pub struct NpmLock {
+    /// Locked packages by a key that is specific to the lockfile format
+    pub packages: HashMap<String, LockedPackage>,
+    /// Keys of the packages that dependencies of the importing package resolve to, by name
+    pub roots: HashMap<String, String>,
+    /// Keys of the packages that descriptors such as `some-lib@^1.0.0` resolve to, for lockfiles
+    /// that resolve by range rather than by location
+    pub descriptors: HashMap<String, String>,
+}
+
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Subresource integrity of the package tarball
+    pub integrity: Option<String>,
+    /// Checksum of the package archive, for lockfiles that do not record integrity
+    pub checksum: Option<String>,
+    /// Keys of the packages that this package depends on
+    pub dependencies: Vec<String>,
+}
+
+pub type TransitivePackage<'a> =
+    dependency_graph::TransitivePackage<'a, &'a LockedPackage, &'a str>;
+
+impl NpmLock {
+    /// Resolved package of a dependency of the importing package, declared with the given range
+    pub fn resolve(&self, name: &str, range: &str) -> Option<&LockedPackage> {
+        let key = match self.roots.get(name) {
+            Some(key) => Some(key),
+            None => self
+                .descriptors
+                .get(&format!("{}@{}", name, range))
+                .or_else(|| self.descriptors.get(&format!("{}@npm:{}", name, range))),
+        };
+        key.and_then(|key| self.packages.get(key))
+    }
+
+    /// Packages that the given packages depend on, directly or indirectly, with the strongest
+    /// scope of the given packages that they are reached from
+    pub fn transitive<'a>(
+        &'a self,
+        direct: &[(&'a LockedPackage, &'a str)],
+    ) -> Vec<TransitivePackage<'a>> {
+        dependency_graph::transitive(
+            direct,
+            |package| package.name.as_str(),
+            |package| (package.name.as_str(), package.version.as_str()),
+            |package| {
+                package
+                    .dependencies
+                    .iter()
+                    .filter_map(|dependency| self.packages.get(dependency))
+                    .collect()
+            },
+            strongest_scope,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, dependencies: &[&str]) -> LockedPackage {
+        LockedPackage {
+            name: name.to_owned(),
+            version: "1.0.0".to_owned(),
+            integrity: None,
+            checksum: None,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let lock = NpmLock {
+            packages: vec![
+                ("a".to_owned(), package("a", &["c"])),
+                ("b".to_owned(), package("b", &["c", "d"])),
+                ("c".to_owned(), package("c", &["d", "e"])),
+                ("d".to_owned(), package("d", &["a"])),
+                ("e".to_owned(), package("e", &["missing"])),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let direct = vec![
+            (&lock.packages["a"], "runtime"),
+            (&lock.packages["b"], "dev"),
+        ];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| {
+                (
+                    transitive.package.name.as_str(),
+                    transitive.path,
+                    transitive.scope,
+                )
+            })
+            .collect::<Vec<_>>();
+        // `d` is reached from the runtime dependency `a` too, just on a longer path
+        assert_eq!(
+            vec![
+                ("c", vec!["a"], "runtime"),
+                ("d", vec!["b"], "runtime"),
+                ("e", vec!["a", "c"], "runtime"),
+            ],
+            transitive
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use super::{SoupParse, dependency_graph};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+const DIRECT_TYPE: &str = "Direct";
+const PROJECT_TYPE: &str = "Project";
+
+/// A package of a target framework, with its name, that direct dependencies depend on
+type TransitivePackage<'a> = dependency_graph::TransitivePackage<'a, (&'a str, &'a Package), ()>;
+
+/// Parses packages.lock.json, where NuGet records the resolved version and content hash of every
+/// package per target framework
+#[derive(Default)]
+pub struct NuGetLock {
+    /// Whether to include packages that direct dependencies depend on
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    dependencies: BTreeMap<String, BTreeMap<String, Package>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    #[serde(rename = "type")]
+    package_type: String,
+    requested: Option<String>,
+    resolved: Option<String>,
+    #[serde(rename = "contentHash")]
+    content_hash: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+}
+
+impl SoupParse for NuGetLock {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_json::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid packages.lock.json ({})", e),
+                });
+            }
+        };
+
+        let mut soups = BTreeSet::new();
+        for (target_framework, packages) in &content.dependencies {
+            for (name, package) in packages {
+                if package.package_type != DIRECT_TYPE {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                if let Some(requested) = &package.requested {
+                    attributes.insert(
+                        "requirement".to_owned(),
+                        Value::String(requested.to_owned()),
+                    );
+                }
+                soups.insert(soup(
+                    name,
+                    package,
+                    target_framework,
+                    attributes,
+                    default_meta,
+                )?);
+            }
+            if !self.include_transitive {
+                continue;
+            }
+            for transitive in transitive(packages) {
+                let (name, package) = transitive.package;
+                let mut attributes = Map::new();
+                transitive.insert_dependency_path(&mut attributes);
+                soups.insert(soup(
+                    name,
+                    package,
+                    target_framework,
+                    attributes,
+                    default_meta,
+                )?);
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn soup(
+    name: &str,
+    package: &Package,
+    target_framework: &str,
+    mut attributes: Map<String, Value>,
+    default_meta: &Map<String, Value>,
+) -> Result<Soup, SoupSourceParseError> {
+    let version = match &package.resolved {
+        Some(version) => version.to_owned(),
+        None => {
+            return Err(SoupSourceParseError {
+                message: format!("Missing resolved version for: {}", name),
+            });
+        }
+    };
+    if let Some(content_hash) = &package.content_hash {
+        attributes.insert(
+            "content_hash".to_owned(),
+            Value::String(content_hash.to_owned()),
+        );
+    }
+    attributes.insert(
+        "target_framework".to_owned(),
+        Value::String(target_framework.to_owned()),
+    );
+    Ok(Soup {
+        name: name.to_owned(),
+        version,
+        ecosystem: Some(Ecosystem::NuGet),
+        attributes,
+        meta: default_meta.clone(),
+        ..Default::default()
+    })
+}
+
+/// Packages of a target framework that direct dependencies or referenced projects depend on,
+/// directly or indirectly, along with the names of the packages leading to them
+fn transitive(packages: &BTreeMap<String, Package>) -> Vec<TransitivePackage<'_>> {
+    // Referenced projects are not SOUPs, but the packages they depend on are, so they are
+    // traversed after the direct dependencies
+    let direct = packages
+        .iter()
+        .filter(|(_, package)| package.package_type == DIRECT_TYPE)
+        .chain(
+            packages
+                .iter()
+                .filter(|(_, package)| package.package_type == PROJECT_TYPE),
+        )
+        .map(|(name, package)| ((name.as_str(), package), ()))
+        .collect::<Vec<_>>();
+    // NuGet does not distinguish scopes of packages
+    dependency_graph::transitive(
+        &direct,
+        |(name, _)| name,
+        // Package ids are case insensitive
+        |(name, _)| name.to_lowercase(),
+        |(_, package)| {
+            package
+                .dependencies
+                .keys()
+                .filter_map(|dependency| {
+                    packages
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(dependency))
+                        .map(|(name, dependency)| (name.as_str(), dependency))
+                })
+                .collect()
+        },
+        |_, _| (),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = r#"{
+  "version": 1,
+  "dependencies": {
+    "net6.0": {
+      "Serilog.Sinks.Console": {
+        "type": "Direct",
+        "requested": "[4.1.0, )",
+        "resolved": "4.1.0",
+        "contentHash": "K6N5q+5fetjnJPvCmkWOpJ/V8IEIoMIB1s86OzBrbZwTyHxdx3pmz4H+8+hbhlwT3/mPjGTvfwgDLtjq2xazmw==",
+        "dependencies": {
+          "Serilog": "2.10.0"
+        }
+      },
+      "Serilog": {
+        "type": "Transitive",
+        "resolved": "2.10.0",
+        "contentHash": "+QX0hmf37a0/OZLxM3wL7V6/ADvC1XihXN4Kq/p6d8lCPfgkRdiuhbWlMaFjR9Av0dy5F0+MBeDmDdRZN/YwQA=="
+      },
+      "My.Library": {
+        "type": "Project",
+        "dependencies": {
+          "Serilog": "[2.10.0, )",
+          "Polly": "[7.2.3, )"
+        }
+      },
+      "Polly": {
+        "type": "Transitive",
+        "resolved": "7.2.3",
+        "contentHash": "DeCY0OFbNdNxsjntr1gTXHJ5pKUwYzp04Er2LLeN3g6pWhffsGuKVfMBLe1lw7x76HrPkLxKEFxBlpRxS2nDEQ=="
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn direct_packages() {
+        let result = NuGetLock::default().soups(LOCKFILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(1, soups.len());
+        assert_eq!("Serilog.Sinks.Console", soups[0].name);
+        assert_eq!("4.1.0", soups[0].version);
+        assert_eq!(
+            Some(&Value::String("[4.1.0, )".to_owned())),
+            soups[0].attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(&Value::String("net6.0".to_owned())),
+            soups[0].attributes.get("target_framework")
+        );
+        assert_eq!(true, soups[0].attributes.contains_key("content_hash"));
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let nuget_lock = NuGetLock {
+            include_transitive: true,
+        };
+        let result = nuget_lock.soups(LOCKFILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(3, soups.len());
+        assert_eq!("Polly", soups[0].name);
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String("My.Library".to_owned())])),
+            soups[0].attributes.get("dependency_path")
+        );
+        assert_eq!("Serilog", soups[1].name);
+        assert_eq!("2.10.0", soups[1].version);
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String(
+                "Serilog.Sinks.Console".to_owned()
+            )])),
+            soups[1].attributes.get("dependency_path")
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let result = NuGetLock::default().soups(r#"{"dependencies": []}"#, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -1,14 +1,78 @@
 use super::SoupParse;
-use crate::soup::model::{Soup, SoupSourceParseError};
+use super::npm_lock::{LockedPackage, NpmLockfile};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
 use serde::Deserialize;
 use serde_json::{Map, Value};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet};
 
-pub struct PackageJson {}
+const RUNTIME_SCOPE: &str = "runtime";
+const OPTIONAL_SCOPE: &str = "optional";
+const PEER_SCOPE: &str = "peer";
+const DEV_SCOPE: &str = "dev";
+
+/// Protocols of specifiers that refer to packages within the repository
+const LOCAL_PROTOCOLS: [&str; 4] = ["file:", "link:", "portal:", "workspace:"];
+/// Prefixes of specifiers that are paths, which npm treats like `file:` specifiers
+const LOCAL_PATH_PREFIXES: [&str; 4] = ["./", "../", "/", "~/"];
+/// Protocols of specifiers that refer to git repositories
+const GIT_PROTOCOLS: [&str; 6] = ["git+", "git:", "github:", "gitlab:", "bitbucket:", "gist:"];
+
+#[derive(Default)]
+pub struct PackageJson {
+    /// Lockfile next to the package.json or at the root of its workspace, written by npm, yarn or
+    /// pnpm
+    pub lockfile: Option<NpmLockfile>,
+    /// Directory of the package.json relative to the directory of the lockfile, which is empty
+    /// unless the lockfile is at the root of a workspace
+    pub importer: String,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+    /// Names of the packages in the workspace that the package.json belongs to
+    pub workspace_packages: BTreeSet<String>,
+}
 
 #[derive(Deserialize)]
 struct Content {
-    dependencies: Option<HashMap<String, String>>,
+    dependencies: Option<BTreeMap<String, String>>,
+    #[serde(rename = "devDependencies")]
+    dev_dependencies: Option<BTreeMap<String, String>>,
+    #[serde(rename = "peerDependencies")]
+    peer_dependencies: Option<BTreeMap<String, String>>,
+    #[serde(rename = "optionalDependencies")]
+    optional_dependencies: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct WorkspaceContent {
+    name: Option<String>,
+    workspaces: Option<Workspaces>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Workspaces {
+    Patterns(Vec<String>),
+    /// Workspaces as configured for yarn classic, with options for hoisting
+    Config {
+        packages: Vec<String>,
+    },
+}
+
+/// Patterns of the directories of workspace packages, if the given package.json is the root of
+/// a workspace
+pub fn workspace_patterns(content: &str) -> Option<Vec<String>> {
+    match serde_json::from_str::<WorkspaceContent>(content)
+        .ok()?
+        .workspaces?
+    {
+        Workspaces::Patterns(patterns) => Some(patterns),
+        Workspaces::Config { packages } => Some(packages),
+    }
+}
+
+/// Name of the package, if the given package.json is valid and has a name
+pub fn package_name(content: &str) -> Option<String> {
+    serde_json::from_str::<WorkspaceContent>(content).ok()?.name
 }
 
 impl SoupParse for PackageJson {
@@ -26,21 +90,142 @@ impl SoupParse for PackageJson {
             }
         };
 
-        let soups = match parse_result.dependencies {
-            None => BTreeSet::new(),
-            Some(dependencies) => dependencies
-                .into_iter()
-                .map(|(key, value)| Soup {
-                    name: key,
-                    version: value,
-                    meta: default_meta.clone(),
-                })
-                .collect::<BTreeSet<Soup>>(),
+        let lock = match &self.lockfile {
+            Some(lockfile) => Some(lockfile.parse(&self.importer)?),
+            None => None,
         };
+
+        // Runtime dependencies come first, so that they take precedence over other dependencies
+        // of the same version
+        let groups = [
+            (RUNTIME_SCOPE, parse_result.dependencies),
+            (OPTIONAL_SCOPE, parse_result.optional_dependencies),
+            (PEER_SCOPE, parse_result.peer_dependencies),
+            (DEV_SCOPE, parse_result.dev_dependencies),
+        ];
+        let mut soups = BTreeSet::new();
+        let mut direct_packages: Vec<(&LockedPackage, &str)> = Vec::new();
+        for (scope, dependencies) in groups {
+            for (dependency, specifier) in dependencies.unwrap_or_default() {
+                if self.workspace_packages.contains(&dependency) {
+                    continue;
+                }
+                let (name, requirement, source) = match package(&dependency, &specifier) {
+                    Some(package) => package,
+                    None => continue,
+                };
+                let mut attributes = Map::new();
+                if name != dependency {
+                    attributes.insert("alias".to_owned(), Value::String(dependency.to_owned()));
+                }
+                if let Some(source) = source {
+                    attributes.insert("source".to_owned(), Value::String(source));
+                }
+                let locked_package = lock
+                    .as_ref()
+                    .and_then(|lock| lock.resolve(&dependency, &specifier));
+                let version = match locked_package {
+                    Some(package) => {
+                        attributes.insert("requirement".to_owned(), Value::String(specifier));
+                        insert_lock_attributes(&mut attributes, package);
+                        direct_packages.push((package, scope));
+                        package.version.to_owned()
+                    }
+                    None => requirement,
+                };
+                soups.insert(Soup {
+                    name,
+                    version,
+                    ecosystem: Some(Ecosystem::Npm),
+                    scope: Some(scope.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+
+        if let (Some(lock), true) = (&lock, self.include_transitive) {
+            for transitive in lock.transitive(&direct_packages) {
+                let package = transitive.package;
+                if self.workspace_packages.contains(&package.name) {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                insert_lock_attributes(&mut attributes, package);
+                transitive.insert_dependency_path(&mut attributes);
+                soups.insert(Soup {
+                    name: package.name.to_owned(),
+                    version: package.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Npm),
+                    scope: Some(transitive.scope.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
         Ok(soups)
     }
 }
 
+/// Name, version requirement and source of the package that a dependency specifier refers to,
+/// or `None` for packages within the repository.
+///
+/// Besides version ranges and tags, specifiers may be aliases such as `npm:some-lib@^1.0.0`, git
+/// repositories such as `github:owner/repo#v1.0.0` or `owner/repo#v1.0.0`, where the reference
+/// after `#` is used as the version, and tarball URLs.
+fn package(dependency: &str, specifier: &str) -> Option<(String, String, Option<String>)> {
+    if LOCAL_PROTOCOLS
+        .iter()
+        .chain(LOCAL_PATH_PREFIXES.iter())
+        .any(|prefix| specifier.starts_with(prefix))
+    {
+        return None;
+    }
+    if let Some(alias) = specifier.strip_prefix("npm:") {
+        return match alias.char_indices().skip(1).find(|(_, c)| *c == '@') {
+            Some((index, _)) => Some((
+                alias[..index].to_owned(),
+                alias[index + 1..].to_owned(),
+                None,
+            )),
+            None => Some((alias.to_owned(), "unknown".to_owned(), None)),
+        };
+    }
+    let is_git = GIT_PROTOCOLS
+        .iter()
+        .any(|protocol| specifier.starts_with(protocol))
+        || specifier.ends_with(".git")
+        || (specifier.contains('/') && !specifier.contains(':') && !specifier.contains(' '));
+    if is_git {
+        let (source, reference) = match specifier.split_once('#') {
+            Some((source, reference)) => (source, reference.trim_start_matches("semver:")),
+            None => (specifier, "unknown"),
+        };
+        return Some((
+            dependency.to_owned(),
+            reference.to_owned(),
+            Some(source.to_owned()),
+        ));
+    }
+    if specifier.starts_with("http://") || specifier.starts_with("https://") {
+        return Some((
+            dependency.to_owned(),
+            "unknown".to_owned(),
+            Some(specifier.to_owned()),
+        ));
+    }
+    Some((dependency.to_owned(), specifier.to_owned(), None))
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    if let Some(integrity) = &package.integrity {
+        attributes.insert("integrity".to_owned(), Value::String(integrity.to_owned()));
+    }
+    if let Some(checksum) = &package.checksum {
+        attributes.insert("checksum".to_owned(), Value::String(checksum.to_owned()));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,7 +238,7 @@ mod tests {
                 "some-lib": "^1.0.0"
             }
         }"#;
-        let result = PackageJson {}.soups(content, &Map::new());
+        let result = PackageJson::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(1, soups.len());
@@ -61,6 +246,7 @@ mod tests {
             name: "some-lib".to_owned(),
             version: "^1.0.0".to_owned(),
             meta: Map::new(),
+            ..Default::default()
         };
         assert_eq!(true, soups.contains(&expected_soup));
     }
@@ -73,7 +259,7 @@ mod tests {
                 "another-lib": "6.6.6"
             }
         }"#;
-        let result = PackageJson {}.soups(content, &Map::new());
+        let result = PackageJson::default().soups(content, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(2, soups.len());
@@ -82,11 +268,13 @@ mod tests {
                 name: "some-lib".to_owned(),
                 version: "^1.0.0".to_owned(),
                 meta: Map::new(),
+                ..Default::default()
             },
             Soup {
                 name: "another-lib".to_owned(),
                 version: "6.6.6".to_owned(),
                 meta: Map::new(),
+                ..Default::default()
             },
         ]
         .into_iter()
@@ -101,7 +289,7 @@ mod tests {
     )]
     #[test_case("{}")]
     fn no_dependencies(input: &str) {
-        let result = PackageJson {}.soups(input, &Map::new());
+        let result = PackageJson::default().soups(input, &Map::new());
         assert_eq!(true, result.is_ok());
         let soups = result.unwrap();
         assert_eq!(0, soups.len());
@@ -110,7 +298,161 @@ mod tests {
     #[test_case(r#"{"#)]
     #[test_case("")]
     fn fail_on_bad_json(input: &str) {
-        let result = PackageJson {}.soups(input, &Map::new());
+        let result = PackageJson::default().soups(input, &Map::new());
         assert_eq!(true, result.is_err());
     }
+
+    #[test]
+    fn locked_versions() {
+        let content = r#"{
+            "dependencies": {
+                "some-lib": "^1.0.0",
+                "unlocked-lib": "^2.0.0"
+            }
+        }"#;
+        let lockfile = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "node_modules/some-lib": {
+                    "version": "1.2.3",
+                    "integrity": "sha512-abc",
+                    "dependencies": { "another-lib": "^3.0.0" }
+                },
+                "node_modules/another-lib": { "version": "3.1.0" }
+            }
+        }"#;
+        let package_json = PackageJson {
+            lockfile: Some(NpmLockfile::Npm(lockfile.to_owned())),
+            include_transitive: true,
+            ..Default::default()
+        };
+        let result = package_json.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(3, soups.len());
+        assert_eq!("another-lib", soups[0].name);
+        assert_eq!("3.1.0", soups[0].version);
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String("some-lib".to_owned())])),
+            soups[0].attributes.get("dependency_path")
+        );
+        assert_eq!("some-lib", soups[1].name);
+        assert_eq!("1.2.3", soups[1].version);
+        assert_eq!(
+            Some(&Value::String("^1.0.0".to_owned())),
+            soups[1].attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(&Value::String("sha512-abc".to_owned())),
+            soups[1].attributes.get("integrity")
+        );
+        assert_eq!("unlocked-lib", soups[2].name);
+        assert_eq!("^2.0.0", soups[2].version);
+    }
+
+    #[test]
+    fn dependency_scopes() {
+        let content = r#"{
+            "dependencies": { "some-lib": "^1.0.0" },
+            "devDependencies": { "some-lib": "^1.0.0", "test-lib": "^2.0.0" },
+            "peerDependencies": { "react": ">=18" },
+            "optionalDependencies": { "fsevents": "^2.3.2" }
+        }"#;
+        let result = PackageJson::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let scopes = result
+            .unwrap()
+            .into_iter()
+            .map(|soup| (soup.name, soup.scope.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("fsevents".to_owned(), "optional".to_owned()),
+                ("react".to_owned(), "peer".to_owned()),
+                ("some-lib".to_owned(), "runtime".to_owned()),
+                ("test-lib".to_owned(), "dev".to_owned()),
+            ],
+            scopes
+        );
+    }
+
+    #[test]
+    fn workspace_packages() {
+        let content = r#"{
+            "dependencies": {
+                "some-lib": "^1.0.0",
+                "my-lib": "^0.1.0",
+                "my-other-lib": "workspace:*"
+            }
+        }"#;
+        let package_json = PackageJson {
+            workspace_packages: vec!["my-lib".to_owned()].into_iter().collect(),
+            ..Default::default()
+        };
+        let result = package_json.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(1, soups.len());
+        assert_eq!("some-lib", soups.into_iter().next().unwrap().name);
+    }
+
+    #[test_case("^1.0.0", Some(("some-lib", "^1.0.0", None)) ; "range")]
+    #[test_case("latest", Some(("some-lib", "latest", None)) ; "tag")]
+    #[test_case(">= 1.0.0 < 2.0.0", Some(("some-lib", ">= 1.0.0 < 2.0.0", None)) ; "range_with_spaces")]
+    #[test_case("npm:other-lib@^2.0.0", Some(("other-lib", "^2.0.0", None)) ; "alias")]
+    #[test_case("npm:@scope/lib@~3.1.0", Some(("@scope/lib", "~3.1.0", None)) ; "scoped_alias")]
+    #[test_case(
+        "git+https://github.com/owner/some-lib.git#v1.2.3",
+        Some(("some-lib", "v1.2.3", Some("git+https://github.com/owner/some-lib.git")))
+        ; "git_url"
+    )]
+    #[test_case(
+        "github:owner/some-lib#semver:^1.2.0",
+        Some(("some-lib", "^1.2.0", Some("github:owner/some-lib")))
+        ; "github_semver"
+    )]
+    #[test_case(
+        "owner/some-lib",
+        Some(("some-lib", "unknown", Some("owner/some-lib")))
+        ; "github_shorthand"
+    )]
+    #[test_case(
+        "https://example.com/some-lib-1.0.0.tgz",
+        Some(("some-lib", "unknown", Some("https://example.com/some-lib-1.0.0.tgz")))
+        ; "tarball_url"
+    )]
+    #[test_case("file:../some-lib", None ; "file")]
+    #[test_case("link:../some-lib", None ; "link")]
+    #[test_case("workspace:^", None ; "workspace")]
+    #[test_case("../app", None ; "parent_path")]
+    #[test_case("./lib", None ; "relative_path")]
+    #[test_case("/opt/lib", None ; "absolute_path")]
+    #[test_case("~/lib", None ; "home_path")]
+    fn specifiers(specifier: &str, expected: Option<(&str, &str, Option<&str>)>) {
+        let expected = expected.map(|(name, version, source)| {
+            (
+                name.to_owned(),
+                version.to_owned(),
+                source.map(|source| source.to_owned()),
+            )
+        });
+        assert_eq!(expected, package("some-lib", specifier));
+    }
+
+    #[test_case(r#"{"workspaces": ["packages/*"]}"#, Some(vec!["packages/*"]) ; "array")]
+    #[test_case(
+        r#"{"workspaces": {"packages": ["packages/*"], "nohoist": ["**/react"]}}"#,
+        Some(vec!["packages/*"])
+        ; "yarn_config"
+    )]
+    #[test_case(r#"{"name": "my-app"}"#, None ; "no_workspaces")]
+    fn workspaces(input: &str, expected: Option<Vec<&str>>) {
+        let expected = expected.map(|patterns| {
+            patterns
+                .into_iter()
+                .map(|pattern| pattern.to_owned())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(expected, workspace_patterns(input));
+    }
 }
@@ -0,0 +1,320 @@
+use super::npm_lock::{LockedPackage, NpmLock};
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const NODE_MODULES: &str = "node_modules/";
+
+#[derive(Deserialize)]
+struct Content {
+    /// Installed packages by location, in lockfile version 2 and 3
+    packages: Option<HashMap<String, Package>>,
+    /// Nested dependencies, in lockfile version 1
+    dependencies: Option<HashMap<String, LegacyDependency>>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    version: Option<String>,
+    name: Option<String>,
+    integrity: Option<String>,
+    resolved: Option<String>,
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default, rename = "optionalDependencies")]
+    optional_dependencies: HashMap<String, String>,
+    #[serde(default, rename = "peerDependencies")]
+    peer_dependencies: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct LegacyDependency {
+    version: String,
+    integrity: Option<String>,
+    #[serde(default)]
+    requires: HashMap<String, String>,
+    #[serde(default)]
+    dependencies: HashMap<String, LegacyDependency>,
+}
+
+/// Parses a package-lock.json or npm-shrinkwrap.json, where packages are keyed by their location
+/// in the node_modules tree
+pub fn parse(content: &str, importer: &str) -> Result<NpmLock, SoupSourceParseError> {
+    let content: Content = match serde_json::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid package-lock.json ({})", e),
+            });
+        }
+    };
+    let mut locations: HashMap<String, (LockedPackage, Vec<String>)> = HashMap::new();
+    let mut links: HashMap<String, String> = HashMap::new();
+    match (content.packages, content.dependencies) {
+        (Some(packages), _) => {
+            for (location, package) in packages {
+                if location.is_empty() {
+                    continue;
+                }
+                if package.link {
+                    if let Some(target) = package.resolved {
+                        links.insert(location, target);
+                    }
+                    continue;
+                }
+                let name = match package.name {
+                    Some(name) => name,
+                    None => package_name(&location).to_owned(),
+                };
+                let mut dependency_names = package.dependencies.into_keys().collect::<Vec<_>>();
+                dependency_names.extend(package.optional_dependencies.into_keys());
+                dependency_names.extend(package.peer_dependencies.into_keys());
+                let locked_package = LockedPackage {
+                    name,
+                    version: package.version.unwrap_or_else(|| "unknown".to_owned()),
+                    integrity: package.integrity,
+                    checksum: None,
+                    dependencies: Vec::new(),
+                };
+                locations.insert(location, (locked_package, dependency_names));
+            }
+        }
+        (None, Some(dependencies)) => flatten_legacy("", dependencies, &mut locations),
+        (None, None) => {}
+    }
+
+    let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+    for (location, (_, dependency_names)) in &locations {
+        // Sorted, so that transitive packages get the same path on every scan
+        let mut dependency_names = dependency_names.iter().collect::<Vec<_>>();
+        dependency_names.sort();
+        dependency_names.dedup();
+        let resolved = dependency_names
+            .into_iter()
+            .filter_map(|name| resolve_location(location, name, &locations, &links))
+            .collect();
+        dependencies.insert(location.to_owned(), resolved);
+    }
+    let packages = locations
+        .into_iter()
+        .map(|(location, (mut package, _))| {
+            package.dependencies = dependencies.remove(&location).unwrap_or_default();
+            (location, package)
+        })
+        .collect::<HashMap<_, _>>();
+    // Dependencies of the importer are either installed in its own node_modules or hoisted to
+    // the node_modules at the root
+    let roots = packages
+        .keys()
+        .chain(links.keys())
+        .filter(|location| {
+            let parent = location
+                .rfind(NODE_MODULES)
+                .map(|index| location[..index].trim_end_matches('/'));
+            parent == Some("") || parent == Some(importer)
+        })
+        .filter_map(|location| {
+            let name = package_name(location);
+            resolve_location(importer, name, &packages, &links).map(|key| (name.to_owned(), key))
+        })
+        .collect();
+    Ok(NpmLock {
+        packages,
+        roots,
+        ..Default::default()
+    })
+}
+
+/// Converts the nested dependencies of lockfile version 1 to locations
+fn flatten_legacy(
+    parent: &str,
+    dependencies: HashMap<String, LegacyDependency>,
+    locations: &mut HashMap<String, (LockedPackage, Vec<String>)>,
+) {
+    for (name, dependency) in dependencies {
+        let location = match parent.is_empty() {
+            true => format!("{}{}", NODE_MODULES, name),
+            false => format!("{}/{}{}", parent, NODE_MODULES, name),
+        };
+        flatten_legacy(&location, dependency.dependencies, locations);
+        let locked_package = LockedPackage {
+            name,
+            version: dependency.version,
+            integrity: dependency.integrity,
+            checksum: None,
+            dependencies: Vec::new(),
+        };
+        let dependency_names = dependency.requires.into_keys().collect();
+        locations.insert(location, (locked_package, dependency_names));
+    }
+}
+
+/// Name of the package installed at the given location, e.g. `@scope/lib` for
+/// `node_modules/some-lib/node_modules/@scope/lib`
+fn package_name(location: &str) -> &str {
+    match location.rfind(NODE_MODULES) {
+        Some(index) => &location[index + NODE_MODULES.len()..],
+        None => location,
+    }
+}
+
+/// Location of the package that a package at `location` gets when requiring `name`, following
+/// the node module resolution of looking in node_modules of the location and its ancestors
+fn resolve_location<T>(
+    location: &str,
+    name: &str,
+    locations: &HashMap<String, T>,
+    links: &HashMap<String, String>,
+) -> Option<String> {
+    let mut base = location;
+    loop {
+        let candidate = match base.is_empty() {
+            true => format!("{}{}", NODE_MODULES, name),
+            false => format!("{}/{}{}", base, NODE_MODULES, name),
+        };
+        if locations.contains_key(&candidate) {
+            return Some(candidate);
+        }
+        if let Some(target) = links.get(&candidate) {
+            return locations.contains_key(target).then(|| target.to_owned());
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = match base.rfind(NODE_MODULES) {
+            Some(index) => base[..index].trim_end_matches('/'),
+            None => "",
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCKFILE_V3: &str = r#"{
+        "name": "my-app",
+        "lockfileVersion": 3,
+        "packages": {
+            "": {
+                "name": "my-app",
+                "dependencies": { "some-lib": "^1.0.0", "@scope/lib": "~2.1.0" }
+            },
+            "node_modules/some-lib": {
+                "version": "1.2.3",
+                "resolved": "https://registry.npmjs.org/some-lib/-/some-lib-1.2.3.tgz",
+                "integrity": "sha512-abc",
+                "dependencies": { "another-lib": "^3.0.0" }
+            },
+            "node_modules/some-lib/node_modules/another-lib": {
+                "version": "3.1.0",
+                "integrity": "sha512-def"
+            },
+            "node_modules/another-lib": {
+                "version": "2.0.0",
+                "integrity": "sha512-ghi"
+            },
+            "node_modules/@scope/lib": {
+                "version": "2.1.4",
+                "integrity": "sha512-jkl",
+                "dependencies": { "another-lib": "^2.0.0" }
+            }
+        }
+    }"#;
+
+    const LOCKFILE_V1: &str = r#"{
+        "name": "my-app",
+        "lockfileVersion": 1,
+        "dependencies": {
+            "some-lib": {
+                "version": "1.2.3",
+                "integrity": "sha512-abc",
+                "requires": { "another-lib": "^3.0.0" },
+                "dependencies": {
+                    "another-lib": { "version": "3.1.0", "integrity": "sha512-def" }
+                }
+            },
+            "another-lib": { "version": "2.0.0", "integrity": "sha512-ghi" },
+            "@scope/lib": {
+                "version": "2.1.4",
+                "integrity": "sha512-jkl",
+                "requires": { "another-lib": "^2.0.0" }
+            }
+        }
+    }"#;
+
+    #[test_case(LOCKFILE_V3 ; "v3")]
+    #[test_case(LOCKFILE_V1 ; "v1")]
+    fn resolved_versions(input: &str) {
+        let lock = parse(input, "").unwrap();
+        let some_lib = lock.resolve("some-lib", "^1.0.0").unwrap();
+        assert_eq!("1.2.3", some_lib.version);
+        assert_eq!(Some("sha512-abc".to_owned()), some_lib.integrity);
+        let scoped_lib = lock.resolve("@scope/lib", "~2.1.0").unwrap();
+        assert_eq!("@scope/lib", scoped_lib.name);
+        assert_eq!("2.1.4", scoped_lib.version);
+    }
+
+    #[test_case(LOCKFILE_V3 ; "v3")]
+    #[test_case(LOCKFILE_V1 ; "v1")]
+    fn nested_dependencies(input: &str) {
+        let lock = parse(input, "").unwrap();
+        let direct = vec![
+            (lock.resolve("some-lib", "^1.0.0").unwrap(), "runtime"),
+            (lock.resolve("@scope/lib", "~2.1.0").unwrap(), "runtime"),
+        ];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| (transitive.package.version.as_str(), transitive.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("3.1.0", vec!["some-lib"]), ("2.0.0", vec!["@scope/lib"])],
+            transitive
+        );
+    }
+
+    #[test]
+    fn linked_package() {
+        let input = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "dependencies": { "my-lib": "*" } },
+                "node_modules/my-lib": { "resolved": "packages/my-lib", "link": true },
+                "packages/my-lib": { "name": "my-lib", "version": "0.1.0" }
+            }
+        }"#;
+        let lock = parse(input, "").unwrap();
+        assert_eq!("0.1.0", lock.resolve("my-lib", "*").unwrap().version);
+    }
+
+    #[test]
+    fn workspace_package() {
+        let input = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "workspaces": ["packages/*"] },
+                "node_modules/my-lib": { "resolved": "packages/my-lib", "link": true },
+                "node_modules/some-lib": { "version": "1.2.3" },
+                "packages/my-lib": { "name": "my-lib", "version": "0.1.0" },
+                "packages/my-lib/node_modules/some-lib": { "version": "2.0.1" }
+            }
+        }"#;
+        let root = parse(input, "").unwrap();
+        assert_eq!("1.2.3", root.resolve("some-lib", "^1.0.0").unwrap().version);
+        let workspace = parse(input, "packages/my-lib").unwrap();
+        assert_eq!(
+            "2.0.1",
+            workspace.resolve("some-lib", "^2.0.0").unwrap().version
+        );
+    }
+
+    #[test_case("{" ; "incomplete")]
+    #[test_case(r#"{"packages": []}"# ; "wrong_type")]
+    fn invalid_lockfile(input: &str) {
+        assert_eq!(true, parse(input, "").is_err());
+    }
+}
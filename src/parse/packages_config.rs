@@ -0,0 +1,107 @@
+use super::SoupParse;
+use super::xml::{self, Node};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses packages.config of .NET Framework projects, which lists every installed package,
+/// including the ones that other packages depend on
+pub struct PackagesConfig {}
+
+impl SoupParse for PackagesConfig {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let packages = parse(content)?;
+        let mut soups: BTreeSet<Soup> = BTreeSet::new();
+        for package in packages.children("package") {
+            let name = attribute_value(package, "id")?;
+            let version = attribute_value(package, "version")?;
+            let scope = match package.attribute("developmentDependency") {
+                Some(value) if value.eq_ignore_ascii_case("true") => "dev",
+                _ => "runtime",
+            };
+            let mut attributes = Map::new();
+            if let Some(target_framework) = package.attribute("targetFramework") {
+                attributes.insert(
+                    "target_framework".to_owned(),
+                    Value::String(target_framework.to_owned()),
+                );
+            }
+            soups.insert(Soup {
+                name,
+                version,
+                ecosystem: Some(Ecosystem::NuGet),
+                scope: Some(scope.to_owned()),
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+        Ok(soups)
+    }
+}
+
+fn parse(content: &str) -> Result<Node, SoupSourceParseError> {
+    match xml::parse(content)?
+        .children
+        .into_iter()
+        .find(|node| node.name == "packages")
+    {
+        Some(packages) => Ok(packages),
+        None => Err(SoupSourceParseError {
+            message: "Missing packages element".to_owned(),
+        }),
+    }
+}
+
+fn attribute_value(package: &Node, key: &str) -> Result<String, SoupSourceParseError> {
+    match package.attribute(key) {
+        Some(value) => Ok(value.to_owned()),
+        None => Err(SoupSourceParseError {
+            message: format!("Missing required attribute: {}", key),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packages() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<packages>
+  <package id="Newtonsoft.Json" version="13.0.1" targetFramework="net48" />
+  <package id="StyleCop.Analyzers" version="1.1.118" targetFramework="net48" developmentDependency="true" />
+</packages>
+        "#;
+        let result = PackagesConfig {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(2, soups.len());
+        assert_eq!("Newtonsoft.Json", soups[0].name);
+        assert_eq!("13.0.1", soups[0].version);
+        assert_eq!(Some("runtime".to_owned()), soups[0].scope);
+        assert_eq!(
+            Some(&Value::String("net48".to_owned())),
+            soups[0].attributes.get("target_framework")
+        );
+        assert_eq!("StyleCop.Analyzers", soups[1].name);
+        assert_eq!(Some("dev".to_owned()), soups[1].scope);
+    }
+
+    #[test]
+    fn missing_version() {
+        let content = r#"<packages><package id="Newtonsoft.Json" /></packages>"#;
+        let result = PackagesConfig {}.soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
+    #[test]
+    fn missing_packages_element() {
+        let result = PackagesConfig {}.soups(r#"<package id="Newtonsoft.Json" />"#, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
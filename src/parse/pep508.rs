@@ -0,0 +1,289 @@
+use serde_json::{Map, Value};
+
+const UNKNOWN_VERSION: &str = "unknown";
+/// Schemes of URLs that refer to version control repositories, e.g. `git+https://`
+const VCS_SCHEMES: [&str; 4] = ["git+", "hg+", "svn+", "bzr+"];
+
+/// A dependency on a Python package, as declared in requirements files, pyproject.toml or Pipfile
+#[derive(Debug, PartialEq)]
+pub struct Requirement {
+    pub name: String,
+    /// Version specifier such as `>=1.0,<2`, empty if any version is accepted
+    pub specifier: String,
+    /// URL of the repository or archive that the package is installed from
+    pub url: Option<String>,
+    /// Environment marker such as `python_version < "3.11"`
+    pub marker: Option<String>,
+}
+
+impl Requirement {
+    /// Parses a PEP 508 requirement such as `requests[security]>=2.8.1 ; python_version < "3.11"`
+    /// or `pip @ git+https://github.com/pypa/pip.git@22.0`
+    pub fn parse(requirement: &str) -> Option<Requirement> {
+        let (requirement, marker) = match requirement.split_once(';') {
+            Some((requirement, marker)) => (requirement.trim(), Some(marker.trim())),
+            None => (requirement.trim(), None),
+        };
+        let end = requirement
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "-_.".contains(c)))
+            .unwrap_or(requirement.len());
+        let name = &requirement[..end];
+        if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let mut rest = requirement[end..].trim_start();
+        if rest.starts_with('[') {
+            rest = rest[rest.find(']')? + 1..].trim_start();
+        }
+        let (specifier, url) = match rest.strip_prefix('@') {
+            Some(url) => (String::new(), Some(url.trim().to_owned())),
+            None => (
+                rest.trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect::<String>(),
+                None,
+            ),
+        };
+        if !specifier.is_empty() && !specifier.starts_with(['<', '>', '=', '!', '~']) {
+            return None;
+        }
+        Some(Requirement {
+            name: name.to_owned(),
+            specifier,
+            url,
+            marker: marker
+                .filter(|marker| !marker.is_empty())
+                .map(|marker| marker.to_owned()),
+        })
+    }
+
+    /// Parses a dependency of Poetry or Pipenv, which is either a version constraint such as
+    /// `^1.2` or a table with e.g. `version`, `git` and `rev`, or `url` keys. Dependencies on
+    /// local paths are within the repository, hence `None`.
+    pub fn from_toml(name: &str, value: &toml::Value) -> Option<Requirement> {
+        let table = match value {
+            toml::Value::String(constraint) => {
+                return Some(Requirement {
+                    name: name.to_owned(),
+                    specifier: specifier(constraint),
+                    url: None,
+                    marker: None,
+                });
+            }
+            // Multiple constraints, e.g. for different Python versions
+            toml::Value::Array(constraints) => {
+                return Requirement::from_toml(name, constraints.first()?);
+            }
+            toml::Value::Table(table) => table,
+            _ => return None,
+        };
+        let string = |key: &str| table.get(key).and_then(|value| value.as_str());
+        let url = match string("git") {
+            Some(git) => {
+                let git = match git.starts_with("git+") {
+                    true => git.to_owned(),
+                    false => format!("git+{}", git),
+                };
+                match ["rev", "tag", "branch", "ref"]
+                    .iter()
+                    .find_map(|key| string(key))
+                {
+                    Some(reference) => Some(format!("{}@{}", git, reference)),
+                    None => Some(git),
+                }
+            }
+            None => string("url").or(string("file")).map(|url| url.to_owned()),
+        };
+        let is_local =
+            table.contains_key("path") || url.as_ref().is_some_and(|url| !url.contains("://"));
+        if is_local {
+            return None;
+        }
+        Some(Requirement {
+            name: name.to_owned(),
+            specifier: specifier(string("version").unwrap_or_default()),
+            url,
+            marker: string("markers").map(|marker| marker.to_owned()),
+        })
+    }
+
+    /// Version of the package, which is the exact version if pinned with `==`, the reference of
+    /// a VCS URL, e.g. `v1.0` for `git+https://github.com/owner/repo.git@v1.0`, or the specifier
+    pub fn version(&self) -> String {
+        if let Some(url) = &self.url {
+            return match split_vcs_url(url) {
+                Some((_, Some(reference))) => reference.to_owned(),
+                _ => UNKNOWN_VERSION.to_owned(),
+            };
+        }
+        if self.specifier.is_empty() {
+            return UNKNOWN_VERSION.to_owned();
+        }
+        let pinned = self
+            .specifier
+            .strip_prefix("===")
+            .or_else(|| self.specifier.strip_prefix("=="));
+        match pinned {
+            Some(version) if !version.contains([',', '*']) => version.to_owned(),
+            _ => self.specifier.to_owned(),
+        }
+    }
+
+    /// URL that the package is installed from, without the VCS reference and fragment
+    pub fn source(&self) -> Option<String> {
+        let url = self.url.as_ref()?;
+        match split_vcs_url(url) {
+            Some((repository, _)) => Some(repository.to_owned()),
+            None => url.split('#').next().map(|url| url.to_owned()),
+        }
+    }
+
+    /// Attributes of a SOUP for the requirement, i.e. its source and environment marker
+    pub fn attributes(&self) -> Map<String, Value> {
+        let mut attributes = Map::new();
+        if let Some(source) = self.source() {
+            attributes.insert("source".to_owned(), Value::String(source));
+        }
+        if let Some(marker) = &self.marker {
+            attributes.insert("marker".to_owned(), Value::String(marker.to_owned()));
+        }
+        attributes
+    }
+}
+
+/// Normalized package name as specified by PEP 503, under which names are compared, e.g.
+/// `typing-extensions` for `Typing_Extensions`
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '-' | '_' | '.' => {
+                if !normalized.ends_with('-') {
+                    normalized.push('-');
+                }
+            }
+            _ => normalized.push(c.to_ascii_lowercase()),
+        }
+    }
+    normalized
+}
+
+fn specifier(constraint: &str) -> String {
+    match constraint.trim() {
+        "*" => String::new(),
+        constraint => constraint.chars().filter(|c| !c.is_whitespace()).collect(),
+    }
+}
+
+/// Repository and reference of a VCS URL, or `None` if the URL does not refer to a repository
+fn split_vcs_url(url: &str) -> Option<(&str, Option<&str>)> {
+    let url = url.split('#').next()?;
+    let (scheme, rest) = url.split_once("://")?;
+    if !VCS_SCHEMES.iter().any(|vcs| scheme.starts_with(vcs)) {
+        return None;
+    }
+    // The reference follows the last at sign of the path, as the host may contain a user
+    let path_start = scheme.len() + 3 + rest.find('/').unwrap_or(rest.len());
+    match url[path_start..].rfind('@') {
+        Some(index) => Some((
+            &url[..path_start + index],
+            Some(&url[path_start + index + 1..]),
+        )),
+        None => Some((url, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("requests==2.31.0", "requests", "2.31.0" ; "pinned")]
+    #[test_case("requests === 2.31.0", "requests", "2.31.0" ; "arbitrary_equality")]
+    #[test_case("requests[security,socks]>=2.8.1,<3", "requests", ">=2.8.1,<3" ; "extras")]
+    #[test_case("requests (>=2.8.1)", "requests", ">=2.8.1" ; "parenthesized")]
+    #[test_case("requests==2.*", "requests", "==2.*" ; "wildcard")]
+    #[test_case("Typing_Extensions", "Typing_Extensions", "unknown" ; "unversioned")]
+    fn versions(input: &str, name: &str, version: &str) {
+        let requirement = Requirement::parse(input).unwrap();
+        assert_eq!(name, requirement.name);
+        assert_eq!(version, requirement.version());
+    }
+
+    #[test]
+    fn marker() {
+        let requirement = Requirement::parse(r#"tomli>=1.1.0; python_version < "3.11""#).unwrap();
+        assert_eq!(">=1.1.0", requirement.specifier);
+        assert_eq!(
+            Some(r#"python_version < "3.11""#.to_owned()),
+            requirement.marker
+        );
+    }
+
+    #[test_case(
+        "pip @ git+https://github.com/pypa/pip.git@22.0#egg=pip",
+        "22.0",
+        "git+https://github.com/pypa/pip.git" ; "vcs_reference"
+    )]
+    #[test_case(
+        "pip @ git+ssh://git@github.com/pypa/pip.git",
+        "unknown",
+        "git+ssh://git@github.com/pypa/pip.git" ; "vcs_user"
+    )]
+    #[test_case(
+        "pip @ https://github.com/pypa/pip/archive/22.0.zip#sha1=da9234ee",
+        "unknown",
+        "https://github.com/pypa/pip/archive/22.0.zip" ; "archive"
+    )]
+    fn urls(input: &str, version: &str, source: &str) {
+        let requirement = Requirement::parse(input).unwrap();
+        assert_eq!("pip", requirement.name);
+        assert_eq!(version, requirement.version());
+        assert_eq!(Some(source.to_owned()), requirement.source());
+    }
+
+    #[test_case("" ; "empty")]
+    #[test_case("./my-lib" ; "path")]
+    #[test_case("git+https://github.com/pypa/pip.git" ; "url")]
+    #[test_case("requests[security" ; "unterminated_extras")]
+    fn invalid_requirements(input: &str) {
+        assert_eq!(None, Requirement::parse(input));
+    }
+
+    #[test_case(r#"version = "^2.31""#, "^2.31", "^2.31" ; "version")]
+    #[test_case(r#"version = "*""#, "", "unknown" ; "any_version")]
+    #[test_case(r#"version = "==2.31.0""#, "==2.31.0", "2.31.0" ; "pinned_version")]
+    #[test_case(
+        r#"version = [{ version = "<2", python = "<3.8" }, { version = "^2.31" }]"#,
+        "<2",
+        "<2" ; "multiple_constraints"
+    )]
+    #[test_case(
+        r#"version = { git = "https://github.com/psf/requests.git", tag = "v2.31.0" }"#,
+        "",
+        "v2.31.0" ; "git"
+    )]
+    fn toml_requirements(input: &str, specifier: &str, version: &str) {
+        let value = toml::from_str::<toml::Table>(input).unwrap()["version"].to_owned();
+        let requirement = Requirement::from_toml("requests", &value).unwrap();
+        assert_eq!(specifier, requirement.specifier);
+        assert_eq!(version, requirement.version());
+    }
+
+    #[test_case(r#"version = { path = "../lib", develop = true }"# ; "path")]
+    #[test_case(r#"version = { file = "../lib-1.0.0.tar.gz" }"# ; "file")]
+    fn local_toml_requirements(input: &str) {
+        let value = toml::from_str::<toml::Table>(input).unwrap()["version"].to_owned();
+        assert_eq!(None, Requirement::from_toml("lib", &value));
+    }
+
+    #[test_case("requests", "requests")]
+    #[test_case("Typing_Extensions", "typing-extensions")]
+    #[test_case("zope.interface", "zope-interface")]
+    #[test_case("a-_.b", "a-b")]
+    fn normalized_names(name: &str, normalized: &str) {
+        assert_eq!(normalized, normalize_name(name));
+    }
+}
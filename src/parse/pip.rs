@@ -0,0 +1,121 @@
+use super::{SoupParse, pep508::Requirement, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses `pip install` commands, including `python -m pip install` and `uv pip install`
+pub struct Pip {}
+
+static OPTIONS_WITH_VALUE: [&str; 22] = [
+    "-r",
+    "--requirement",
+    "-c",
+    "--constraint",
+    "-e",
+    "--editable",
+    "-i",
+    "--index-url",
+    "--extra-index-url",
+    "-f",
+    "--find-links",
+    "-t",
+    "--target",
+    "--prefix",
+    "--root",
+    "--trusted-host",
+    "--platform",
+    "--python-version",
+    "--src",
+    "--cache-dir",
+    "--proxy",
+    "--timeout",
+];
+/// Extensions of distribution files, which are installed from the file system rather than an index
+static ARCHIVE_EXTENSIONS: [&str; 4] = [".whl", ".tar.gz", ".tar.bz2", ".zip"];
+lazy_static! {
+    static ref PIP: Regex = Regex::new(r"^pip[0-9.]*$").unwrap();
+}
+
+impl SoupParse for Pip {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for command in shell::commands(content) {
+            let words = match shell::subcommand_words(
+                &command,
+                |program| PIP.is_match(program),
+                &["install"],
+                &OPTIONS_WITH_VALUE,
+            ) {
+                Some(words) => words,
+                None => continue,
+            };
+            for argument in shell::operands(words, &OPTIONS_WITH_VALUE) {
+                if ARCHIVE_EXTENSIONS
+                    .iter()
+                    .any(|extension| argument.ends_with(extension))
+                {
+                    continue;
+                }
+                if let Some(requirement) = Requirement::parse(argument) {
+                    result.insert(Soup {
+                        name: requirement.name.to_owned(),
+                        version: requirement.version(),
+                        ecosystem: Some(Ecosystem::PyPI),
+                        attributes: requirement.attributes(),
+                        meta: default_meta.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+    use test_case::test_case;
+
+    #[test_case("pip install requests==2.32.3", &[("requests", "2.32.3")])]
+    #[test_case("RUN pip3 install --no-cache-dir 'uvicorn[standard]>=0.30' poetry", &[("poetry", "unknown"), ("uvicorn", ">=0.30")])]
+    #[test_case("python3.12 -m pip install -i https://example.org/simple black==24.4.2", &[("black", "24.4.2")])]
+    #[test_case("uv pip install --system ruff==0.4.8", &[("ruff", "0.4.8")])]
+    #[test_case("pip install -r requirements.txt -e . ./dist/app-1.0-py3-none-any.whl app-1.0.tar.gz", &[])]
+    fn packages(input: &str, expected: &[(&str, &str)]) {
+        let result = Pip {}.soups(input, &Map::new()).unwrap();
+        assert_eq!(expected, versions(&result));
+    }
+
+    #[test]
+    fn source_of_vcs_requirement() {
+        let soups = Pip {}
+            .soups(
+                "pip install 'toolbox @ git+https://github.com/owner/toolbox.git@v1.2'",
+                &Map::new(),
+            )
+            .unwrap();
+        let soup = soups.first().unwrap();
+        assert_eq!("v1.2", soup.version);
+        assert_eq!(
+            Some(&Value::String(
+                "git+https://github.com/owner/toolbox.git".to_owned()
+            )),
+            soup.attributes.get("source")
+        );
+    }
+
+    #[test_case("pip freeze > requirements.txt")]
+    #[test_case("pip install --upgrade")]
+    #[test_case("pipx install poetry")]
+    fn no_install_statement(input: &str) {
+        assert_eq!(0, Pip {}.soups(input, &Map::new()).unwrap().len());
+    }
+}
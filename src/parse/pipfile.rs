@@ -0,0 +1,204 @@
+use super::SoupParse;
+use super::pep508::{Requirement, normalize_name};
+use super::pipfile_lock;
+use super::python_lock::{self, PythonLock};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const DEV_SCOPE: &str = "dev";
+
+#[derive(Default)]
+pub struct Pipfile {
+    /// Content of the Pipfile.lock next to the Pipfile
+    pub lockfile: Option<String>,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    packages: BTreeMap<String, toml::Value>,
+    #[serde(rename = "dev-packages", default)]
+    dev_packages: BTreeMap<String, toml::Value>,
+}
+
+impl SoupParse for Pipfile {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match toml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Pipfile ({})", e),
+                });
+            }
+        };
+        let lock = match &self.lockfile {
+            Some(lockfile) => Some(pipfile_lock::parse(lockfile)?),
+            None => None,
+        };
+        let (default_lock, develop_lock) = match &lock {
+            Some(lock) => (Some(&lock.default), Some(&lock.develop)),
+            None => (None, None),
+        };
+
+        let groups: [(&str, BTreeMap<String, toml::Value>, Option<&PythonLock>); 2] = [
+            (RUNTIME_SCOPE, content.packages, default_lock),
+            (DEV_SCOPE, content.dev_packages, develop_lock),
+        ];
+        let mut soups = BTreeSet::new();
+        let mut visited = HashSet::new();
+        for (scope, packages, lock) in &groups {
+            for (name, value) in packages {
+                let requirement = match Requirement::from_toml(name, value) {
+                    Some(requirement) => requirement,
+                    None => continue,
+                };
+                let (soup, _) = python_lock::requirement_soup(
+                    &requirement,
+                    scope,
+                    Map::new(),
+                    *lock,
+                    default_meta,
+                );
+                visited.insert(normalize_name(name));
+                soups.insert(soup);
+            }
+        }
+
+        // Pipfile.lock does not record which package depends on which, so the remaining packages
+        // of each section are added without a dependency path
+        if self.include_transitive {
+            for (scope, _, lock) in &groups {
+                let packages = lock.map(|lock| lock.packages.iter()).into_iter().flatten();
+                for (normalized_name, package) in packages {
+                    if !visited.insert(normalized_name.to_owned()) {
+                        continue;
+                    }
+                    soups.insert(python_lock::locked_soup(
+                        package,
+                        scope,
+                        Map::new(),
+                        default_meta,
+                    ));
+                }
+            }
+        }
+        Ok(soups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPFILE: &str = r#"
+[[source]]
+url = "https://pypi.org/simple"
+verify_ssl = true
+name = "pypi"
+
+[packages]
+requests = "*"
+toolbox = {git = "https://github.com/owner/toolbox.git", ref = "v0.3.0"}
+my-lib = {path = "./my-lib", editable = true}
+
+[dev-packages]
+pytest = ">=8"
+
+[requires]
+python_version = "3.11"
+"#;
+
+    const LOCKFILE: &str = r#"{
+    "default": {
+        "certifi": {
+            "hashes": ["sha256:c198e21b1289c2ab85ee4e67bb4b4ef3ead0892059901a8d5b622f24a1101e90"],
+            "version": "==2024.7.4"
+        },
+        "requests": {
+            "hashes": ["sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"],
+            "version": "==2.32.3"
+        }
+    },
+    "develop": {
+        "pluggy": {
+            "hashes": [],
+            "version": "==1.5.0"
+        },
+        "pytest": {
+            "hashes": [],
+            "version": "==8.2.2"
+        }
+    }
+}"#;
+
+    #[test]
+    fn declared_packages() {
+        let result = Pipfile::default().soups(PIPFILE, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(3, soups.len());
+        assert_eq!("pytest", soups[0].name);
+        assert_eq!(">=8", soups[0].version);
+        assert_eq!(Some("dev".to_owned()), soups[0].scope);
+        assert_eq!("requests", soups[1].name);
+        assert_eq!("unknown", soups[1].version);
+        assert_eq!(Some("runtime".to_owned()), soups[1].scope);
+        assert_eq!("toolbox", soups[2].name);
+        assert_eq!("v0.3.0", soups[2].version);
+        assert_eq!(
+            Some(&Value::String(
+                "git+https://github.com/owner/toolbox.git".to_owned()
+            )),
+            soups[2].attributes.get("source")
+        );
+    }
+
+    #[test]
+    fn locked_versions() {
+        let pipfile = Pipfile {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: false,
+        };
+        let soups = pipfile.soups(PIPFILE, &Map::new()).unwrap();
+        let requests = soups.iter().find(|soup| soup.name == "requests").unwrap();
+        assert_eq!("2.32.3", requests.version);
+        assert_eq!(None, requests.attributes.get("requirement"));
+        assert_eq!(true, requests.attributes.contains_key("hashes"));
+        let pytest = soups.iter().find(|soup| soup.name == "pytest").unwrap();
+        assert_eq!("8.2.2", pytest.version);
+        assert_eq!(
+            Some(&Value::String(">=8".to_owned())),
+            pytest.attributes.get("requirement")
+        );
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let pipfile = Pipfile {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: true,
+        };
+        let soups = pipfile.soups(PIPFILE, &Map::new()).unwrap();
+        assert_eq!(5, soups.len());
+        let certifi = soups.iter().find(|soup| soup.name == "certifi").unwrap();
+        assert_eq!("2024.7.4", certifi.version);
+        assert_eq!(Some("runtime".to_owned()), certifi.scope);
+        let pluggy = soups.iter().find(|soup| soup.name == "pluggy").unwrap();
+        assert_eq!(Some("dev".to_owned()), pluggy.scope);
+    }
+
+    #[test]
+    fn invalid_pipfile() {
+        let result = Pipfile::default().soups("[packages]\nrequests = ", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use super::pep508::normalize_name;
+use super::python_lock::{LockedPackage, PythonLock};
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Packages resolved by Pipenv for the `[packages]` and `[dev-packages]` of a Pipfile
+pub struct PipfileLock {
+    pub default: PythonLock,
+    pub develop: PythonLock,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    default: BTreeMap<String, Package>,
+    #[serde(default)]
+    develop: BTreeMap<String, Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    version: Option<String>,
+    #[serde(default)]
+    hashes: Vec<String>,
+    git: Option<String>,
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+    file: Option<String>,
+    path: Option<String>,
+}
+
+/// Parses a Pipfile.lock, which lists every package that the project depends on, directly or
+/// indirectly, without recording which package depends on which
+pub fn parse(content: &str) -> Result<PipfileLock, SoupSourceParseError> {
+    let content: Content = match serde_json::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid Pipfile.lock ({})", e),
+            });
+        }
+    };
+    Ok(PipfileLock {
+        default: lock(content.default)?,
+        develop: lock(content.develop)?,
+    })
+}
+
+fn lock(section: BTreeMap<String, Package>) -> Result<PythonLock, SoupSourceParseError> {
+    let mut packages = BTreeMap::new();
+    for (name, package) in section {
+        if package.path.is_some() {
+            continue;
+        }
+        let (version, source) = match (package.version, package.git, package.file) {
+            (Some(version), _, _) => (version.trim_start_matches("==").to_owned(), None),
+            (None, Some(git), _) => (
+                package.reference.unwrap_or("unknown".to_owned()),
+                Some(match git.starts_with("git+") {
+                    true => git,
+                    false => format!("git+{}", git),
+                }),
+            ),
+            (None, None, Some(file)) => ("unknown".to_owned(), Some(file)),
+            (None, None, None) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid Pipfile.lock (missing version of {})", name),
+                });
+            }
+        };
+        packages.insert(
+            normalize_name(&name),
+            LockedPackage {
+                name,
+                version,
+                hashes: package.hashes,
+                source,
+                dependencies: Vec::new(),
+            },
+        );
+    }
+    Ok(PythonLock { packages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let content = r#"{
+    "_meta": {
+        "hash": {"sha256": "0123456789abcdef"},
+        "pipfile-spec": 6,
+        "requires": {"python_version": "3.11"},
+        "sources": [{"name": "pypi", "url": "https://pypi.org/simple", "verify_ssl": true}]
+    },
+    "default": {
+        "requests": {
+            "hashes": [
+                "sha256:55365417734eb18255590a9ff9eb97e9e1da868d4ccd6402399eaf68af20a760",
+                "sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"
+            ],
+            "index": "pypi",
+            "markers": "python_version >= '3.8'",
+            "version": "==2.32.3"
+        },
+        "toolbox": {
+            "git": "https://github.com/owner/toolbox.git",
+            "ref": "4f3b4ad7a0a1c6e1e4b5d9a1c0b7e2f3a4b5c6d7"
+        },
+        "my-lib": {
+            "editable": true,
+            "path": "./my-lib"
+        }
+    },
+    "develop": {
+        "pytest": {
+            "hashes": [],
+            "version": "==8.2.2"
+        }
+    }
+}"#;
+        let lock = parse(content).unwrap();
+        assert_eq!(2, lock.default.packages.len());
+        let requests = lock.default.resolve("requests").unwrap();
+        assert_eq!("2.32.3", requests.version);
+        assert_eq!(2, requests.hashes.len());
+        let toolbox = lock.default.resolve("toolbox").unwrap();
+        assert_eq!("4f3b4ad7a0a1c6e1e4b5d9a1c0b7e2f3a4b5c6d7", toolbox.version);
+        assert_eq!(
+            Some("git+https://github.com/owner/toolbox.git".to_owned()),
+            toolbox.source
+        );
+        assert_eq!("8.2.2", lock.develop.resolve("pytest").unwrap().version);
+    }
+
+    #[test]
+    fn missing_version() {
+        let content = r#"{"default": {"requests": {"hashes": []}}}"#;
+        assert_eq!(true, parse(content).is_err());
+    }
+}
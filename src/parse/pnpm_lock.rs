@@ -0,0 +1,339 @@
+use super::npm_lock::{LockedPackage, NpmLock};
+use crate::soup::model::SoupSourceParseError;
+use serde_yaml_ng::{Mapping, Value};
+use std::collections::HashMap;
+
+const DEPENDENCY_SECTIONS: [&str; 3] = ["dependencies", "devDependencies", "optionalDependencies"];
+
+/// Parses a pnpm-lock.yaml, where packages are keyed by name and version. Keys are formatted as
+/// `/some-lib/1.0.0` in lockfile version 5, `/some-lib@1.0.0` in version 6 and `some-lib@1.0.0`
+/// in version 9, which also moves dependencies of packages to `snapshots`.
+pub fn parse(content: &str, importer: &str) -> Result<NpmLock, SoupSourceParseError> {
+    let content: Value = match serde_yaml_ng::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid pnpm-lock.yaml ({})", e),
+            });
+        }
+    };
+    // Lockfile version 5 separates name and version with a slash instead of an at sign
+    let legacy = match content.get("lockfileVersion") {
+        Some(Value::Number(version)) => version.as_f64().is_some_and(|version| version < 6.0),
+        Some(Value::String(version)) => version.starts_with('5'),
+        _ => false,
+    };
+    let packages = mapping(&content, "packages");
+    let snapshots = mapping(&content, "snapshots");
+    let entries = match snapshots.is_empty() {
+        true => &packages,
+        false => &snapshots,
+    };
+
+    let keys = entries
+        .keys()
+        .filter_map(|key| key.as_str())
+        .collect::<Vec<&str>>();
+    let mut locked_packages = HashMap::new();
+    for (key, entry) in entries {
+        let key = match key.as_str() {
+            Some(key) => key,
+            None => continue,
+        };
+        let (name, version) = match name_version(key, legacy) {
+            Some(name_version) => name_version,
+            None => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid pnpm-lock.yaml (malformed package key: {})", key),
+                });
+            }
+        };
+        let integrity = integrity(entry).or_else(|| {
+            packages
+                .get(format!("{}@{}", name, version).as_str())
+                .and_then(integrity)
+        });
+        let mut dependencies = ["dependencies", "optionalDependencies"]
+            .iter()
+            .flat_map(|section| dependency_references(entry, section))
+            .filter_map(|(name, reference)| package_key(&name, &reference, &keys))
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        locked_packages.insert(
+            key.to_owned(),
+            LockedPackage {
+                name: name.to_owned(),
+                version: version.to_owned(),
+                integrity,
+                checksum: None,
+                dependencies,
+            },
+        );
+    }
+
+    // Dependencies are listed by importer in workspaces and lockfiles from version 9, and at the
+    // top level otherwise
+    let importer = match importer.is_empty() {
+        true => ".",
+        false => importer,
+    };
+    let root = match content
+        .get("importers")
+        .and_then(|importers| importers.get(importer))
+    {
+        Some(root) => root,
+        None => &content,
+    };
+    let roots = DEPENDENCY_SECTIONS
+        .iter()
+        .flat_map(|section| dependency_references(root, section))
+        .filter_map(|(name, reference)| {
+            package_key(&name, &reference, &keys).map(|key| (name, key))
+        })
+        .collect();
+    Ok(NpmLock {
+        packages: locked_packages,
+        roots,
+        ..Default::default()
+    })
+}
+
+fn mapping(value: &Value, key: &str) -> Mapping {
+    match value.get(key).and_then(|value| value.as_mapping()) {
+        Some(mapping) => mapping.to_owned(),
+        None => Mapping::new(),
+    }
+}
+
+fn integrity(entry: &Value) -> Option<String> {
+    entry
+        .get("resolution")
+        .and_then(|resolution| resolution.get("integrity"))
+        .and_then(|integrity| integrity.as_str())
+        .map(|integrity| integrity.to_owned())
+}
+
+/// Names and version references of the dependencies in the given section, where a reference is
+/// either a version, e.g. `1.0.0(react@18.2.0)`, or a mapping with a version in newer lockfiles
+fn dependency_references(entry: &Value, section: &str) -> Vec<(String, String)> {
+    let dependencies = match entry.get(section).and_then(|value| value.as_mapping()) {
+        Some(dependencies) => dependencies,
+        None => return Vec::new(),
+    };
+    dependencies
+        .iter()
+        .filter_map(|(name, reference)| {
+            let reference = match reference.get("version") {
+                Some(version) => version,
+                None => reference,
+            };
+            Some((name.as_str()?.to_owned(), scalar(reference)?))
+        })
+        .collect()
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.to_owned()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Key of the package that a dependency with the given version reference resolves to
+fn package_key(name: &str, reference: &str, keys: &[&str]) -> Option<String> {
+    let candidates = [
+        reference.to_owned(),
+        format!("{}@{}", name, reference),
+        format!("/{}@{}", name, reference),
+        format!("/{}/{}", name, reference),
+    ];
+    candidates
+        .into_iter()
+        .find(|candidate| keys.contains(&candidate.as_str()))
+}
+
+/// Name and version of a package key, without the peer dependencies that pnpm appends to the
+/// version, e.g. `(react@18.2.0)` or, in lockfile version 5, `_react@18.2.0`
+fn name_version(key: &str, legacy: bool) -> Option<(&str, &str)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    match legacy {
+        true => {
+            let separators = match key.starts_with('@') {
+                true => 2,
+                false => 1,
+            };
+            let (index, _) = key.match_indices('/').nth(separators - 1)?;
+            let version = key[index + 1..].split('_').next()?;
+            Some((&key[..index], version))
+        }
+        false => {
+            let (index, _) = key.char_indices().skip(1).find(|(_, c)| *c == '@')?;
+            let version = key[index + 1..].split('(').next()?;
+            Some((&key[..index], version))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCKFILE_V9: &str = r#"
+lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      '@scope/lib':
+        specifier: ~2.1.0
+        version: 2.1.4(react@18.2.0)
+      some-lib:
+        specifier: ^1.0.0
+        version: 1.2.3
+
+packages:
+
+  '@scope/lib@2.1.4':
+    resolution: {integrity: sha512-jkl}
+    peerDependencies:
+      react: '*'
+
+  another-lib@2.0.0:
+    resolution: {integrity: sha512-ghi}
+
+  react@18.2.0:
+    resolution: {integrity: sha512-mno}
+
+  some-lib@1.2.3:
+    resolution: {integrity: sha512-abc}
+
+snapshots:
+
+  '@scope/lib@2.1.4(react@18.2.0)':
+    dependencies:
+      another-lib: 2.0.0
+      react: 18.2.0
+
+  another-lib@2.0.0: {}
+
+  react@18.2.0: {}
+
+  some-lib@1.2.3:
+    dependencies:
+      '@scope/lib': 2.1.4(react@18.2.0)
+"#;
+
+    const LOCKFILE_V6: &str = r#"
+lockfileVersion: '6.0'
+
+dependencies:
+  '@scope/lib':
+    specifier: ~2.1.0
+    version: 2.1.4(react@18.2.0)
+  some-lib:
+    specifier: ^1.0.0
+    version: 1.2.3
+
+packages:
+
+  /@scope/lib@2.1.4(react@18.2.0):
+    resolution: {integrity: sha512-jkl}
+    dependencies:
+      another-lib: 2.0.0
+      react: 18.2.0
+
+  /another-lib@2.0.0:
+    resolution: {integrity: sha512-ghi}
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-mno}
+
+  /some-lib@1.2.3:
+    resolution: {integrity: sha512-abc}
+    dependencies:
+      '@scope/lib': 2.1.4(react@18.2.0)
+"#;
+
+    const LOCKFILE_V5: &str = r#"
+lockfileVersion: 5.4
+
+specifiers:
+  '@scope/lib': ~2.1.0
+  some-lib: ^1.0.0
+
+dependencies:
+  '@scope/lib': 2.1.4_react@18.2.0
+  some-lib: 1.2.3
+
+packages:
+
+  /@scope/lib/2.1.4_react@18.2.0:
+    resolution: {integrity: sha512-jkl}
+    dependencies:
+      another-lib: 2.0.0
+      react: 18.2.0
+
+  /another-lib/2.0.0:
+    resolution: {integrity: sha512-ghi}
+
+  /react/18.2.0:
+    resolution: {integrity: sha512-mno}
+
+  /some-lib/1.2.3:
+    resolution: {integrity: sha512-abc}
+    dependencies:
+      '@scope/lib': 2.1.4_react@18.2.0
+"#;
+
+    #[test_case(LOCKFILE_V9 ; "v9")]
+    #[test_case(LOCKFILE_V6 ; "v6")]
+    #[test_case(LOCKFILE_V5 ; "v5")]
+    fn resolved_versions(input: &str) {
+        let lock = parse(input, "").unwrap();
+        let some_lib = lock.resolve("some-lib", "^1.0.0").unwrap();
+        assert_eq!("1.2.3", some_lib.version);
+        assert_eq!(Some("sha512-abc".to_owned()), some_lib.integrity);
+        let scoped_lib = lock.resolve("@scope/lib", "~2.1.0").unwrap();
+        assert_eq!("@scope/lib", scoped_lib.name);
+        assert_eq!("2.1.4", scoped_lib.version);
+        assert_eq!(Some("sha512-jkl".to_owned()), scoped_lib.integrity);
+    }
+
+    #[test_case(LOCKFILE_V9 ; "v9")]
+    #[test_case(LOCKFILE_V6 ; "v6")]
+    #[test_case(LOCKFILE_V5 ; "v5")]
+    fn transitive_packages(input: &str) {
+        let lock = parse(input, "").unwrap();
+        let direct = vec![(lock.resolve("some-lib", "^1.0.0").unwrap(), "runtime")];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| (transitive.package.name.as_str(), transitive.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("@scope/lib", vec!["some-lib"]),
+                ("another-lib", vec!["some-lib", "@scope/lib"]),
+                ("react", vec!["some-lib", "@scope/lib"]),
+            ],
+            transitive
+        );
+    }
+
+    #[test_case("/string_decoder/1.3.0", true, "string_decoder", "1.3.0" ; "v5")]
+    #[test_case("/@scope/lib/2.1.4_react@18.2.0", true, "@scope/lib", "2.1.4" ; "v5_peer")]
+    #[test_case("/@scope/lib@2.1.4(react@18.2.0)", false, "@scope/lib", "2.1.4" ; "v6_peer")]
+    #[test_case("string_decoder@1.3.0", false, "string_decoder", "1.3.0" ; "v9")]
+    fn package_name_version(key: &str, legacy: bool, name: &str, version: &str) {
+        assert_eq!(Some((name, version)), name_version(key, legacy));
+    }
+
+    #[test_case("lockfileVersion: [" ; "invalid_yaml")]
+    #[test_case("packages:\n  some-lib: {}" ; "malformed_key")]
+    fn invalid_lockfile(input: &str) {
+        assert_eq!(true, parse(input, "").is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use super::pep508::normalize_name;
+use super::python_lock::{LockedPackage, PythonLock};
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Types of package sources that refer to paths within the repository
+const LOCAL_SOURCE_TYPES: [&str; 2] = ["directory", "file"];
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    package: Vec<Package>,
+    metadata: Option<Metadata>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+    #[serde(default)]
+    files: Vec<File>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+    source: Option<Source>,
+}
+
+#[derive(Deserialize)]
+struct Source {
+    #[serde(rename = "type")]
+    source_type: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    /// Files of each package, in lockfiles written before lock version 2.0
+    #[serde(default)]
+    files: BTreeMap<String, Vec<File>>,
+}
+
+#[derive(Deserialize)]
+struct File {
+    hash: String,
+}
+
+/// Parses a poetry.lock, which lists every package that the project depends on, directly or
+/// indirectly, along with the hashes of its files
+pub fn parse(content: &str) -> Result<PythonLock, SoupSourceParseError> {
+    let content: Content = match toml::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid poetry.lock ({})", e),
+            });
+        }
+    };
+    let legacy_files = content
+        .metadata
+        .map(|metadata| metadata.files)
+        .unwrap_or_default();
+
+    let mut packages = BTreeMap::new();
+    for package in content.package {
+        let source = match &package.source {
+            Some(source) if LOCAL_SOURCE_TYPES.contains(&source.source_type.as_str()) => continue,
+            Some(source) if source.source_type == "git" => Some(format!("git+{}", source.url)),
+            Some(source) if source.source_type == "url" => Some(source.url.to_owned()),
+            _ => None,
+        };
+        let files = match package.files.is_empty() {
+            true => legacy_files
+                .get(&package.name)
+                .map(|files| files.as_slice()),
+            false => Some(package.files.as_slice()),
+        };
+        let hashes = files
+            .unwrap_or_default()
+            .iter()
+            .map(|file| file.hash.to_owned())
+            .collect();
+        // Optional dependencies are only installed along with the extras that require them
+        let dependencies = package
+            .dependencies
+            .iter()
+            .filter(|(_, value)| {
+                value
+                    .get("optional")
+                    .and_then(|optional| optional.as_bool())
+                    != Some(true)
+            })
+            .map(|(name, _)| normalize_name(name))
+            .collect();
+        packages.insert(
+            normalize_name(&package.name),
+            LockedPackage {
+                name: package.name,
+                version: package.version,
+                hashes,
+                source,
+                dependencies,
+            },
+        );
+    }
+    Ok(PythonLock { packages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const LOCKFILE: &str = r#"
+# This file is automatically @generated by Poetry 1.8.3 and should not be changed by hand.
+
+[[package]]
+name = "certifi"
+version = "2024.7.4"
+description = "Python package for providing Mozilla's CA Bundle."
+optional = false
+python-versions = ">=3.6"
+files = [
+    {file = "certifi-2024.7.4-py3-none-any.whl", hash = "sha256:c198e21b1289c2ab85ee4e67bb4b4ef3ead0892059901a8d5b622f24a1101e90"},
+]
+
+[[package]]
+name = "my-lib"
+version = "0.1.0"
+description = ""
+optional = false
+python-versions = "*"
+files = []
+develop = true
+
+[package.source]
+type = "directory"
+url = "../my-lib"
+
+[[package]]
+name = "PySocks"
+version = "1.7.1"
+description = "A Python SOCKS client module."
+optional = true
+python-versions = ">=2.7"
+files = []
+
+[[package]]
+name = "requests"
+version = "2.32.3"
+description = "Python HTTP for Humans."
+optional = false
+python-versions = ">=3.8"
+files = [
+    {file = "requests-2.32.3-py3-none-any.whl", hash = "sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"},
+    {file = "requests-2.32.3.tar.gz", hash = "sha256:55365417734eb18255590a9ff9eb97e9e1da868d4ccd6402399eaf68af20a760"},
+]
+
+[package.dependencies]
+certifi = ">=2017.4.17"
+PySocks = {version = ">=1.5.6,<1.5.7 || >1.5.7", optional = true}
+
+[package.extras]
+socks = ["PySocks (>=1.5.6,!=1.5.7)"]
+
+[[package]]
+name = "toolbox"
+version = "0.3.0"
+description = ""
+optional = false
+python-versions = "^3.11"
+files = []
+
+[package.source]
+type = "git"
+url = "https://github.com/owner/toolbox.git"
+reference = "v0.3.0"
+resolved_reference = "4f3b4ad7a0a1c6e1e4b5d9a1c0b7e2f3a4b5c6d7"
+
+[metadata]
+lock-version = "2.0"
+python-versions = "^3.11"
+content-hash = "0123456789abcdef"
+"#;
+
+    const LEGACY_LOCKFILE: &str = r#"
+[[package]]
+name = "certifi"
+version = "2021.10.8"
+description = "Python package for providing Mozilla's CA Bundle."
+category = "main"
+optional = false
+python-versions = "*"
+
+[metadata]
+lock-version = "1.1"
+python-versions = "^3.8"
+content-hash = "0123456789abcdef"
+
+[metadata.files]
+certifi = [
+    {file = "certifi-2021.10.8-py2.py3-none-any.whl", hash = "sha256:d62a0163eb4c2344ac042ab2bdf75399a71a2d8c7d47eac2e2ee91b9d6339569"},
+]
+"#;
+
+    #[test]
+    fn packages() {
+        let lock = parse(LOCKFILE).unwrap();
+        assert_eq!(4, lock.packages.len());
+        let requests = lock.resolve("Requests").unwrap();
+        assert_eq!("2.32.3", requests.version);
+        assert_eq!(2, requests.hashes.len());
+        assert_eq!(vec!["certifi".to_owned()], requests.dependencies);
+        let toolbox = lock.resolve("toolbox").unwrap();
+        assert_eq!(
+            Some("git+https://github.com/owner/toolbox.git".to_owned()),
+            toolbox.source
+        );
+        assert_eq!(true, lock.resolve("my-lib").is_none());
+    }
+
+    #[test]
+    fn legacy_files() {
+        let lock = parse(LEGACY_LOCKFILE).unwrap();
+        let certifi = lock.resolve("certifi").unwrap();
+        assert_eq!(
+            vec![
+                "sha256:d62a0163eb4c2344ac042ab2bdf75399a71a2d8c7d47eac2e2ee91b9d6339569"
+                    .to_owned()
+            ],
+            certifi.hashes
+        );
+    }
+
+    #[test_case("[[package]]\nname = \"certifi\"" ; "missing_version")]
+    #[test_case("package = [" ; "invalid_toml")]
+    fn invalid_lockfile(input: &str) {
+        assert_eq!(true, parse(input).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const DEV_SCOPE: &str = "dev";
+
+/// Parses the dependencies of a Dart or Flutter package (pubspec.yaml), with versions resolved in
+/// the pubspec.lock next to it
+#[derive(Default)]
+pub struct Pubspec {
+    /// Content of the pubspec.lock next to the pubspec.yaml
+    pub lockfile: Option<String>,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    dependencies: Option<BTreeMap<String, YamlValue>>,
+    dev_dependencies: Option<BTreeMap<String, YamlValue>>,
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    description: YamlValue,
+    source: String,
+    version: String,
+}
+
+impl SoupParse for Pubspec {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = parse(content, "pubspec.yaml")?;
+        let locked = match &self.lockfile {
+            Some(lockfile) => parse::<Lockfile>(lockfile, "pubspec.lock")?.packages,
+            None => BTreeMap::new(),
+        };
+        let groups = [
+            (RUNTIME_SCOPE, content.dependencies.unwrap_or_default()),
+            (DEV_SCOPE, content.dev_dependencies.unwrap_or_default()),
+        ];
+        let mut soups = BTreeSet::new();
+        let mut visited = HashSet::new();
+        for (scope, dependencies) in &groups {
+            for (name, value) in dependencies {
+                // SDKs such as Flutter and packages within the repository are no SOUP of their own
+                if value.get("sdk").is_some() || value.get("path").is_some() {
+                    continue;
+                }
+                let requirement = match value {
+                    YamlValue::String(requirement) => Some(requirement.to_owned()),
+                    _ => value
+                        .get("version")
+                        .and_then(|version| version.as_str())
+                        .map(|version| version.to_owned()),
+                };
+                let mut attributes = Map::new();
+                let version = match locked.get(name) {
+                    Some(package) => {
+                        if let Some(requirement) = requirement {
+                            attributes.insert("requirement".to_owned(), Value::String(requirement));
+                        }
+                        insert_lock_attributes(&mut attributes, package);
+                        package.version.to_owned()
+                    }
+                    None => requirement.unwrap_or("unknown".to_owned()),
+                };
+                visited.insert(name.to_owned());
+                soups.insert(Soup {
+                    name: name.to_owned(),
+                    version,
+                    ecosystem: Some(Ecosystem::Pub),
+                    scope: Some(scope.to_string()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+
+        // pubspec.lock does not record whether transitive packages are only needed by dev
+        // dependencies, so they are added as runtime dependencies
+        if self.include_transitive {
+            let transitive = locked
+                .iter()
+                .filter(|(_, package)| package.source != "sdk" && package.source != "path");
+            for (name, package) in transitive {
+                if !visited.insert(name.to_owned()) {
+                    continue;
+                }
+                let mut attributes = Map::new();
+                insert_lock_attributes(&mut attributes, package);
+                soups.insert(Soup {
+                    name: name.to_owned(),
+                    version: package.version.to_owned(),
+                    ecosystem: Some(Ecosystem::Pub),
+                    scope: Some(RUNTIME_SCOPE.to_owned()),
+                    attributes,
+                    meta: default_meta.clone(),
+                });
+            }
+        }
+        Ok(soups)
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    let description = &package.description;
+    match package.source.as_str() {
+        "hosted" => {
+            if let Some(sha256) = description.get("sha256").and_then(|sha256| sha256.as_str()) {
+                attributes.insert(
+                    "checksum".to_owned(),
+                    Value::String(format!("sha256:{}", sha256)),
+                );
+            }
+        }
+        "git" => {
+            if let Some(url) = description.get("url").and_then(|url| url.as_str()) {
+                attributes.insert("source".to_owned(), Value::String(url.to_owned()));
+            }
+            let revision = description
+                .get("resolved-ref")
+                .and_then(|revision| revision.as_str());
+            if let Some(revision) = revision {
+                attributes.insert("revision".to_owned(), Value::String(revision.to_owned()));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse<T: DeserializeOwned>(content: &str, file_name: &str) -> Result<T, SoupSourceParseError> {
+    match serde_yaml_ng::from_str(content) {
+        Ok(content) => Ok(content),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid {} ({})", file_name, e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::scopes;
+    use test_case::test_case;
+
+    const MANIFEST: &str = r#"
+name: companion_app
+environment:
+  sdk: ">=3.3.0 <4.0.0"
+dependencies:
+  flutter:
+    sdk: flutter
+  http: ^1.2.1
+  ble_driver:
+    git:
+      url: https://github.com/acme/ble_driver.git
+      ref: v0.4.0
+  shared_models:
+    path: ../shared_models
+dev_dependencies:
+  lints: ^4.0.0
+"#;
+
+    const LOCKFILE: &str = r#"
+packages:
+  ble_driver:
+    dependency: "direct main"
+    description:
+      path: "."
+      ref: "v0.4.0"
+      resolved-ref: "3c0f2a4e8b1d5f7a9c2e4b6d8f0a1c3e5b7d9f1a"
+      url: "https://github.com/acme/ble_driver.git"
+    source: git
+    version: "0.4.0"
+  flutter:
+    dependency: "direct main"
+    description: flutter
+    source: sdk
+    version: "0.0.0"
+  http:
+    dependency: "direct main"
+    description:
+      name: http
+      sha256: "761a297c042deedc1ffbb156d6e2af13886bb305c2a343a4d972504cd67dd938"
+      url: "https://pub.dev"
+    source: hosted
+    version: "1.2.1"
+  http_parser:
+    dependency: transitive
+    description:
+      name: http_parser
+      sha256: "2aa08ce0341cc9b354a498388e30986515406668dbcc4f7c950c3e715496693b"
+      url: "https://pub.dev"
+    source: hosted
+    version: "4.0.2"
+  lints:
+    dependency: "direct dev"
+    description:
+      name: lints
+      sha256: "976c774dd944a42e83e2467f4cc670daef7eed6295b10b36ae8c85bcbf828235"
+      url: "https://pub.dev"
+    source: hosted
+    version: "4.0.0"
+sdks:
+  dart: ">=3.3.0 <4.0.0"
+"#;
+
+    #[test]
+    fn declared_versions() {
+        let result = Pubspec::default().soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(
+            vec![
+                ("ble_driver", "unknown", "runtime"),
+                ("http", "^1.2.1", "runtime"),
+                ("lints", "^4.0.0", "dev"),
+            ],
+            scopes(&result)
+        );
+    }
+
+    #[test_case(false, &[
+        ("ble_driver", "0.4.0", "runtime"),
+        ("http", "1.2.1", "runtime"),
+        ("lints", "4.0.0", "dev"),
+    ])]
+    #[test_case(true, &[
+        ("ble_driver", "0.4.0", "runtime"),
+        ("http", "1.2.1", "runtime"),
+        ("http_parser", "4.0.2", "runtime"),
+        ("lints", "4.0.0", "dev"),
+    ])]
+    fn locked_versions(include_transitive: bool, soups: &[(&str, &str, &str)]) {
+        let pubspec = Pubspec {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive,
+        };
+        let result = pubspec.soups(MANIFEST, &Map::new()).unwrap();
+        assert_eq!(soups, scopes(&result));
+    }
+
+    #[test]
+    fn locked_attributes() {
+        let pubspec = Pubspec {
+            lockfile: Some(LOCKFILE.to_owned()),
+            include_transitive: false,
+        };
+        let soups = pubspec.soups(MANIFEST, &Map::new()).unwrap();
+        let ble_driver = soups.iter().find(|soup| soup.name == "ble_driver").unwrap();
+        assert_eq!(
+            Some(&Value::String(
+                "3c0f2a4e8b1d5f7a9c2e4b6d8f0a1c3e5b7d9f1a".to_owned()
+            )),
+            ble_driver.attributes.get("revision")
+        );
+        let http = soups.iter().find(|soup| soup.name == "http").unwrap();
+        assert_eq!(
+            Some(&Value::String(
+                "sha256:761a297c042deedc1ffbb156d6e2af13886bb305c2a343a4d972504cd67dd938"
+                    .to_owned()
+            )),
+            http.attributes.get("checksum")
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let pubspec = Pubspec {
+            lockfile: Some("packages: [".to_owned()),
+            include_transitive: false,
+        };
+        assert_eq!(true, pubspec.soups(MANIFEST, &Map::new()).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use super::SoupParse;
+use super::pep508::{Requirement, normalize_name};
+use super::python_lock::{self, LockedPackage, PythonLockfile};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RUNTIME_SCOPE: &str = "runtime";
+const OPTIONAL_SCOPE: &str = "optional";
+const DEV_SCOPE: &str = "dev";
+
+#[derive(Default)]
+pub struct PyProject {
+    /// Lockfile next to the pyproject.toml, written by Poetry, or at the root of its workspace,
+    /// written by uv
+    pub lockfile: Option<PythonLockfile>,
+    /// Whether to include packages that dependencies depend on, as resolved in the lockfile
+    pub include_transitive: bool,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    project: Option<Project>,
+    /// Dependency groups as specified by PEP 735
+    #[serde(rename = "dependency-groups", default)]
+    dependency_groups: BTreeMap<String, Vec<toml::Value>>,
+    tool: Option<Tool>,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    name: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(rename = "optional-dependencies", default)]
+    optional_dependencies: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct Tool {
+    poetry: Option<Poetry>,
+    uv: Option<Uv>,
+}
+
+#[derive(Deserialize)]
+struct Poetry {
+    name: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+    #[serde(rename = "dev-dependencies", default)]
+    dev_dependencies: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    group: BTreeMap<String, PoetryGroup>,
+}
+
+#[derive(Deserialize)]
+struct PoetryGroup {
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct Uv {
+    #[serde(default)]
+    sources: BTreeMap<String, toml::Value>,
+    #[serde(rename = "dev-dependencies", default)]
+    dev_dependencies: Vec<String>,
+}
+
+impl SoupParse for PyProject {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match toml::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid pyproject.toml structure ({})", e),
+                });
+            }
+        };
+        let lock = match &self.lockfile {
+            Some(lockfile) => Some(lockfile.parse()?),
+            None => None,
+        };
+
+        // Runtime dependencies come first, so that they take precedence over other dependencies
+        // of the same version
+        let mut soups = BTreeSet::new();
+        let mut direct_packages: Vec<(&LockedPackage, &str)> = Vec::new();
+        for (requirement, scope, attributes) in requirements(content) {
+            let (soup, locked_package) = python_lock::requirement_soup(
+                &requirement,
+                scope,
+                attributes,
+                lock.as_ref(),
+                default_meta,
+            );
+            if let Some(package) = locked_package {
+                direct_packages.push((package, scope));
+            }
+            soups.insert(soup);
+        }
+
+        if let (Some(lock), true) = (&lock, self.include_transitive) {
+            for transitive in lock.transitive(&direct_packages) {
+                let package = transitive.package;
+                let mut attributes = Map::new();
+                transitive.insert_dependency_path(&mut attributes);
+                soups.insert(python_lock::locked_soup(
+                    package,
+                    transitive.scope,
+                    attributes,
+                    default_meta,
+                ));
+            }
+        }
+        Ok(soups)
+    }
+}
+
+/// Requirements declared in the `[project]` table, dependency groups and the configuration of
+/// Poetry and uv, along with their scope and attributes, excluding the project itself and
+/// packages within the repository
+fn requirements(content: Content) -> Vec<(Requirement, &'static str, Map<String, Value>)> {
+    let mut requirements = Vec::new();
+    let mut project_names = Vec::new();
+    let mut local_packages = Vec::new();
+    let (poetry, uv) = match content.tool {
+        Some(tool) => (tool.poetry, tool.uv),
+        None => (None, None),
+    };
+
+    if let Some(project) = content.project {
+        project_names.extend(project.name);
+        for dependency in project.dependencies {
+            requirements.extend(pep508(&dependency, RUNTIME_SCOPE, Map::new()));
+        }
+        for (extra, dependencies) in project.optional_dependencies {
+            let mut attributes = Map::new();
+            attributes.insert("extra".to_owned(), Value::String(extra));
+            for dependency in dependencies {
+                requirements.extend(pep508(&dependency, OPTIONAL_SCOPE, attributes.clone()));
+            }
+        }
+    }
+    for (group, dependencies) in content.dependency_groups {
+        let mut attributes = Map::new();
+        attributes.insert("group".to_owned(), Value::String(group));
+        // Tables such as `{include-group = "test"}` include other groups
+        for dependency in dependencies.iter().filter_map(|value| value.as_str()) {
+            requirements.extend(pep508(dependency, DEV_SCOPE, attributes.clone()));
+        }
+    }
+    if let Some(poetry) = poetry {
+        project_names.extend(poetry.name);
+        for (name, value) in &poetry.dependencies {
+            // The supported Python versions are declared as a dependency on python
+            if name == "python" {
+                continue;
+            }
+            let optional = value
+                .get("optional")
+                .and_then(|optional| optional.as_bool());
+            let scope = match optional {
+                Some(true) => OPTIONAL_SCOPE,
+                _ => RUNTIME_SCOPE,
+            };
+            requirements.extend(poetry_requirement(name, value, scope, Map::new()));
+        }
+        for (name, value) in &poetry.dev_dependencies {
+            requirements.extend(poetry_requirement(name, value, DEV_SCOPE, Map::new()));
+        }
+        for (group, poetry_group) in poetry.group {
+            let mut attributes = Map::new();
+            attributes.insert("group".to_owned(), Value::String(group));
+            for (name, value) in &poetry_group.dependencies {
+                requirements.extend(poetry_requirement(
+                    name,
+                    value,
+                    DEV_SCOPE,
+                    attributes.clone(),
+                ));
+            }
+        }
+    }
+    if let Some(uv) = uv {
+        for dependency in uv.dev_dependencies {
+            requirements.extend(pep508(&dependency, DEV_SCOPE, Map::new()));
+        }
+        for (name, source) in uv.sources {
+            if source.get("workspace").is_some() || source.get("path").is_some() {
+                local_packages.push(normalize_name(&name));
+            }
+        }
+    }
+
+    let excluded = project_names
+        .iter()
+        .map(|name| normalize_name(name))
+        .chain(local_packages)
+        .collect::<Vec<_>>();
+    requirements
+        .into_iter()
+        .filter(|(requirement, _, _)| !excluded.contains(&normalize_name(&requirement.name)))
+        .collect()
+}
+
+fn pep508(
+    dependency: &str,
+    scope: &'static str,
+    attributes: Map<String, Value>,
+) -> Option<(Requirement, &'static str, Map<String, Value>)> {
+    Requirement::parse(dependency).map(|requirement| (requirement, scope, attributes))
+}
+
+fn poetry_requirement(
+    name: &str,
+    value: &toml::Value,
+    scope: &'static str,
+    attributes: Map<String, Value>,
+) -> Option<(Requirement, &'static str, Map<String, Value>)> {
+    Requirement::from_toml(name, value).map(|requirement| (requirement, scope, attributes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup::model::Ecosystem;
+    use test_case::test_case;
+
+    const PEP_621: &str = r#"
+[project]
+name = "my-app"
+version = "0.1.0"
+dependencies = [
+    "anyio>=4.4",
+    "toolbox @ git+https://github.com/owner/toolbox.git@v0.3.0",
+    'tomli>=1.1.0; python_version < "3.11"',
+]
+
+[project.optional-dependencies]
+socks = ["PySocks>=1.5.6"]
+all = ["my-app[socks]"]
+
+[dependency-groups]
+test = ["pytest>=8", {include-group = "lint"}]
+lint = ["ruff==0.5.0"]
+"#;
+
+    const POETRY: &str = r#"
+[tool.poetry]
+name = "my-app"
+version = "0.1.0"
+
+[tool.poetry.dependencies]
+python = "^3.11"
+requests = "^2.32"
+PySocks = { version = "^1.7", optional = true }
+toolbox = { git = "https://github.com/owner/toolbox.git", tag = "v0.3.0" }
+my-lib = { path = "../my-lib", develop = true }
+
+[tool.poetry.group.test.dependencies]
+pytest = "^8.2"
+
+[tool.poetry.extras]
+socks = ["PySocks"]
+"#;
+
+    const POETRY_LOCK: &str = r#"
+[[package]]
+name = "certifi"
+version = "2024.7.4"
+files = []
+
+[[package]]
+name = "pysocks"
+version = "1.7.1"
+files = []
+
+[[package]]
+name = "pytest"
+version = "8.2.2"
+files = []
+
+[package.dependencies]
+pluggy = ">=1.5,<2.0"
+
+[[package]]
+name = "pluggy"
+version = "1.5.0"
+files = []
+
+[[package]]
+name = "requests"
+version = "2.32.3"
+files = [
+    {file = "requests-2.32.3-py3-none-any.whl", hash = "sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"},
+]
+
+[package.dependencies]
+certifi = ">=2017.4.17"
+"#;
+
+    fn soup<'a>(soups: &'a BTreeSet<Soup>, name: &str) -> &'a Soup {
+        soups.iter().find(|soup| soup.name == name).unwrap()
+    }
+
+    #[test]
+    fn project_dependencies() {
+        let result = PyProject::default().soups(PEP_621, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(6, soups.len());
+        assert_eq!(">=4.4", soup(&soups, "anyio").version);
+        assert_eq!(Some(Ecosystem::PyPI), soup(&soups, "anyio").ecosystem);
+        assert_eq!(Some("runtime".to_owned()), soup(&soups, "anyio").scope);
+        let toolbox = soup(&soups, "toolbox");
+        assert_eq!("v0.3.0", toolbox.version);
+        assert_eq!(
+            Some(&Value::String(
+                "git+https://github.com/owner/toolbox.git".to_owned()
+            )),
+            toolbox.attributes.get("source")
+        );
+        assert_eq!(
+            Some(&Value::String(r#"python_version < "3.11""#.to_owned())),
+            soup(&soups, "tomli").attributes.get("marker")
+        );
+        let pysocks = soup(&soups, "PySocks");
+        assert_eq!(Some("optional".to_owned()), pysocks.scope);
+        assert_eq!(
+            Some(&Value::String("socks".to_owned())),
+            pysocks.attributes.get("extra")
+        );
+        let ruff = soup(&soups, "ruff");
+        assert_eq!("0.5.0", ruff.version);
+        assert_eq!(Some("dev".to_owned()), ruff.scope);
+        assert_eq!(
+            Some(&Value::String("lint".to_owned())),
+            ruff.attributes.get("group")
+        );
+        assert_eq!(Some("dev".to_owned()), soup(&soups, "pytest").scope);
+    }
+
+    #[test]
+    fn poetry_dependencies() {
+        let result = PyProject::default().soups(POETRY, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap();
+        assert_eq!(4, soups.len());
+        assert_eq!("^2.32", soup(&soups, "requests").version);
+        assert_eq!(Some("optional".to_owned()), soup(&soups, "PySocks").scope);
+        assert_eq!("v0.3.0", soup(&soups, "toolbox").version);
+        let pytest = soup(&soups, "pytest");
+        assert_eq!(Some("dev".to_owned()), pytest.scope);
+        assert_eq!(
+            Some(&Value::String("test".to_owned())),
+            pytest.attributes.get("group")
+        );
+    }
+
+    #[test]
+    fn uv_sources() {
+        let content = r#"
+[project]
+name = "my-app"
+dependencies = ["my-lib", "anyio"]
+
+[tool.uv]
+dev-dependencies = ["pytest>=8"]
+
+[tool.uv.sources]
+my-lib = { workspace = true }
+"#;
+        let soups = PyProject::default().soups(content, &Map::new()).unwrap();
+        assert_eq!(2, soups.len());
+        assert_eq!("unknown", soup(&soups, "anyio").version);
+        assert_eq!(Some("dev".to_owned()), soup(&soups, "pytest").scope);
+    }
+
+    #[test]
+    fn locked_versions() {
+        let pyproject = PyProject {
+            lockfile: Some(PythonLockfile::Poetry(POETRY_LOCK.to_owned())),
+            include_transitive: false,
+        };
+        let soups = pyproject.soups(POETRY, &Map::new()).unwrap();
+        assert_eq!(4, soups.len());
+        let requests = soup(&soups, "requests");
+        assert_eq!("2.32.3", requests.version);
+        assert_eq!(
+            Some(&Value::String("^2.32".to_owned())),
+            requests.attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String(
+                "sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"
+                    .to_owned()
+            )])),
+            requests.attributes.get("hashes")
+        );
+        assert_eq!("1.7.1", soup(&soups, "PySocks").version);
+    }
+
+    #[test]
+    fn transitive_dependencies() {
+        let pyproject = PyProject {
+            lockfile: Some(PythonLockfile::Poetry(POETRY_LOCK.to_owned())),
+            include_transitive: true,
+        };
+        let soups = pyproject.soups(POETRY, &Map::new()).unwrap();
+        assert_eq!(6, soups.len());
+        let certifi = soup(&soups, "certifi");
+        assert_eq!("2024.7.4", certifi.version);
+        assert_eq!(Some("runtime".to_owned()), certifi.scope);
+        assert_eq!(
+            Some(&Value::Array(vec![Value::String("requests".to_owned())])),
+            certifi.attributes.get("dependency_path")
+        );
+        assert_eq!(Some("dev".to_owned()), soup(&soups, "pluggy").scope);
+    }
+
+    #[test_case("[project]\ndependencies = \"anyio\"" ; "invalid_structure")]
+    #[test_case("[project" ; "invalid_toml")]
+    fn invalid_pyproject(content: &str) {
+        let result = PyProject::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        let pyproject = PyProject {
+            lockfile: Some(PythonLockfile::Uv("[[package]]\nname = 1".to_owned())),
+            include_transitive: false,
+        };
+        assert_eq!(true, pyproject.soups(PEP_621, &Map::new()).is_err());
+    }
+}
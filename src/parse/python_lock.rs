@@ -0,0 +1,208 @@
+use super::pep508::{Requirement, normalize_name};
+use super::{dependency_graph, poetry_lock, strongest_scope, uv_lock};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Content of a lockfile that resolves the dependencies of a pyproject.toml
+pub enum PythonLockfile {
+    Poetry(String),
+    Uv(String),
+}
+
+impl PythonLockfile {
+    pub fn parse(&self) -> Result<PythonLock, SoupSourceParseError> {
+        match self {
+            PythonLockfile::Poetry(content) => poetry_lock::parse(content),
+            PythonLockfile::Uv(content) => uv_lock::parse(content),
+        }
+    }
+}
+
+/// Packages resolved by Poetry, uv or Pipenv, independent of the lockfile format
+#[derive(Default)]
+pub struct PythonLock {
+    /// Locked packages by normalized name
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Hashes of the distribution files of the package, e.g. `sha256:...`
+    pub hashes: Vec<String>,
+    /// URL of the repository or archive that the package is installed from, unless it is
+    /// installed from a package index
+    pub source: Option<String>,
+    /// Normalized names of the packages that this package depends on
+    pub dependencies: Vec<String>,
+}
+
+pub type TransitivePackage<'a> =
+    dependency_graph::TransitivePackage<'a, &'a LockedPackage, &'a str>;
+
+impl PythonLock {
+    pub fn resolve(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.get(&normalize_name(name))
+    }
+
+    /// Packages that the given packages depend on, directly or indirectly, with the strongest
+    /// scope of the given packages that they are reached from
+    pub fn transitive<'a>(
+        &'a self,
+        direct: &[(&'a LockedPackage, &'a str)],
+    ) -> Vec<TransitivePackage<'a>> {
+        dependency_graph::transitive(
+            direct,
+            |package| package.name.as_str(),
+            |package| normalize_name(&package.name),
+            |package| {
+                package
+                    .dependencies
+                    .iter()
+                    .filter_map(|dependency| self.packages.get(dependency))
+                    .collect()
+            },
+            strongest_scope,
+        )
+    }
+}
+
+/// SOUP of a declared requirement, with the version and attributes of the package that it
+/// resolves to in the lockfile, if any
+pub fn requirement_soup<'a>(
+    requirement: &Requirement,
+    scope: &str,
+    mut attributes: Map<String, Value>,
+    lock: Option<&'a PythonLock>,
+    default_meta: &Map<String, Value>,
+) -> (Soup, Option<&'a LockedPackage>) {
+    attributes.extend(requirement.attributes());
+    let locked_package = lock.and_then(|lock| lock.resolve(&requirement.name));
+    let version = match locked_package {
+        Some(package) => {
+            if !requirement.specifier.is_empty() {
+                attributes.insert(
+                    "requirement".to_owned(),
+                    Value::String(requirement.specifier.to_owned()),
+                );
+            }
+            insert_lock_attributes(&mut attributes, package);
+            package.version.to_owned()
+        }
+        None => requirement.version(),
+    };
+    let soup = Soup {
+        name: requirement.name.to_owned(),
+        version,
+        ecosystem: Some(Ecosystem::PyPI),
+        scope: Some(scope.to_owned()),
+        attributes,
+        meta: default_meta.clone(),
+    };
+    (soup, locked_package)
+}
+
+/// SOUP of a package that is only in the lockfile, as declared requirements depend on it
+pub fn locked_soup(
+    package: &LockedPackage,
+    scope: &str,
+    mut attributes: Map<String, Value>,
+    default_meta: &Map<String, Value>,
+) -> Soup {
+    insert_lock_attributes(&mut attributes, package);
+    Soup {
+        name: package.name.to_owned(),
+        version: package.version.to_owned(),
+        ecosystem: Some(Ecosystem::PyPI),
+        scope: Some(scope.to_owned()),
+        attributes,
+        meta: default_meta.clone(),
+    }
+}
+
+fn insert_lock_attributes(attributes: &mut Map<String, Value>, package: &LockedPackage) {
+    if let Some(source) = &package.source {
+        attributes.insert("source".to_owned(), Value::String(source.to_owned()));
+    }
+    if !package.hashes.is_empty() {
+        attributes.insert(
+            "hashes".to_owned(),
+            Value::Array(
+                package
+                    .hashes
+                    .iter()
+                    .map(|hash| Value::String(hash.to_owned()))
+                    .collect(),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, dependencies: &[&str]) -> (String, LockedPackage) {
+        let package = LockedPackage {
+            name: name.to_owned(),
+            version: "1.0.0".to_owned(),
+            hashes: Vec::new(),
+            source: None,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        };
+        (normalize_name(name), package)
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let lock = PythonLock {
+            packages: vec![
+                package("A", &["c"]),
+                package("b", &["c", "d"]),
+                package("c", &["d", "e"]),
+                package("d", &["a"]),
+                package("e", &["missing"]),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let direct = vec![
+            (lock.resolve("a").unwrap(), "runtime"),
+            (lock.resolve("b").unwrap(), "runtime"),
+        ];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| (transitive.package.name.as_str(), transitive.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("c", vec!["A"]), ("d", vec!["b"]), ("e", vec!["A", "c"])],
+            transitive
+        );
+    }
+
+    #[test]
+    fn resolved_requirement() {
+        let lock = PythonLock {
+            packages: vec![package("Typing_Extensions", &[])]
+                .into_iter()
+                .collect(),
+        };
+        let requirement = Requirement::parse("typing-extensions>=4").unwrap();
+        let (soup, locked_package) = requirement_soup(
+            &requirement,
+            "runtime",
+            Map::new(),
+            Some(&lock),
+            &Map::new(),
+        );
+        assert_eq!(true, locked_package.is_some());
+        assert_eq!("typing-extensions", soup.name);
+        assert_eq!("1.0.0", soup.version);
+        assert_eq!(
+            Some(&Value::String(">=4".to_owned())),
+            soup.attributes.get("requirement")
+        );
+    }
+}
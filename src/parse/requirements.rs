@@ -0,0 +1,322 @@
+use super::SoupParse;
+use super::pep508::Requirement;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum depth of nested `-r` includes, guarding against include cycles
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Parses pip requirements files such as requirements.txt or requirements-dev.txt
+#[derive(Default)]
+pub struct Requirements {
+    /// Path of the requirements file, which files included with `-r` are relative to
+    pub path: Option<PathBuf>,
+}
+
+impl SoupParse for Requirements {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut soups = BTreeSet::new();
+        parse(content, self.path.as_deref(), 0, default_meta, &mut soups)?;
+        Ok(soups)
+    }
+}
+
+fn parse(
+    content: &str,
+    path: Option<&Path>,
+    depth: usize,
+    default_meta: &Map<String, Value>,
+    soups: &mut BTreeSet<Soup>,
+) -> Result<(), SoupSourceParseError> {
+    for line in logical_lines(content) {
+        if let Some(include) = option_value(&line, &["-r", "--requirement"]) {
+            let include_path = match path.and_then(|path| path.parent()) {
+                Some(dir) => dir.join(include),
+                None => continue,
+            };
+            if depth >= MAX_INCLUDE_DEPTH {
+                return Err(SoupSourceParseError {
+                    message: format!(
+                        "Too deeply nested requirements files at {}",
+                        include_path.display()
+                    ),
+                });
+            }
+            let include_content = match fs::read_to_string(&include_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    return Err(SoupSourceParseError {
+                        message: format!(
+                            "Unable to read included requirements file {} ({})",
+                            include_path.display(),
+                            e
+                        ),
+                    });
+                }
+            };
+            parse(
+                &include_content,
+                Some(&include_path),
+                depth + 1,
+                default_meta,
+                soups,
+            )?;
+            continue;
+        }
+        // Only editable installs from repositories are packages, as opposed to local projects
+        let line = match option_value(&line, &["-e", "--editable"]) {
+            Some(editable) if editable.contains("://") => editable.to_owned(),
+            Some(_) => continue,
+            None => line,
+        };
+        // Constraints files, indexes and other global options do not install packages
+        if line.starts_with('-') {
+            continue;
+        }
+
+        // Options of a requirement follow it, e.g. `requests==2.32.3 --hash=sha256:...`
+        let (requirement, options) = match line.find(" -") {
+            Some(index) => (&line[..index], &line[index..]),
+            None => (line.as_str(), ""),
+        };
+        let requirement = match url_requirement(requirement) {
+            Some(requirement) => requirement,
+            None => match Requirement::parse(requirement) {
+                Some(requirement) => requirement,
+                None if is_local_path(requirement) => continue,
+                None => {
+                    return Err(SoupSourceParseError {
+                        message: format!("Invalid requirement: {}", requirement),
+                    });
+                }
+            },
+        };
+        let mut attributes = requirement.attributes();
+        let hashes = options
+            .split_whitespace()
+            .filter_map(|option| option.strip_prefix("--hash="))
+            .map(|hash| Value::String(hash.to_owned()))
+            .collect::<Vec<_>>();
+        if !hashes.is_empty() {
+            attributes.insert("hashes".to_owned(), Value::Array(hashes));
+        }
+        soups.insert(Soup {
+            name: requirement.name.to_owned(),
+            version: requirement.version(),
+            ecosystem: Some(Ecosystem::PyPI),
+            scope: None,
+            attributes,
+            meta: default_meta.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Lines without comments, where lines ending with a backslash are joined with the next line
+fn logical_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for line in content.lines() {
+        // Comments start at the beginning of the line or after whitespace, so that URL fragments
+        // such as `#egg=name` are kept
+        let line = match line.char_indices().find(|(index, c)| {
+            *c == '#' && (*index == 0 || line[..*index].ends_with(char::is_whitespace))
+        }) {
+            Some((index, _)) => &line[..index],
+            None => line,
+        };
+        match line.trim_end().strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line);
+                let logical_line = current.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !logical_line.is_empty() {
+                    lines.push(logical_line);
+                }
+                current.clear();
+            }
+        }
+    }
+    lines
+}
+
+/// Value of an option such as `-r base.txt`, `-rbase.txt` or `--requirement=base.txt`
+fn option_value<'a>(line: &'a str, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| {
+        let value = line.strip_prefix(name)?;
+        match name.starts_with("--") {
+            true if !value.starts_with([' ', '=']) => None,
+            _ => Some(value.trim_start_matches([' ', '=']).trim()),
+        }
+    })
+}
+
+/// Requirement of a URL without a name, such as
+/// `git+https://github.com/owner/repo.git@v1.0#egg=repo`, named after its `egg` fragment or the
+/// last segment of its path
+fn url_requirement(requirement: &str) -> Option<Requirement> {
+    let (scheme, rest) = requirement.split_once("://")?;
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+')
+    {
+        return None;
+    }
+    let egg = rest
+        .split_once('#')
+        .and_then(|(_, fragment)| {
+            fragment
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("egg="))
+        })
+        .map(|egg| egg.split(['[', '=']).next().unwrap_or(egg));
+    let name = match egg {
+        Some(egg) => egg,
+        None => {
+            let stem = rest
+                .split(['#', '?'])
+                .next()?
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()?
+                .split('@')
+                .next()?
+                .trim_end_matches(".git");
+            // Archives are named after the package and its version, e.g. `toolbox-0.3.0.tar.gz`
+            match stem
+                .match_indices('-')
+                .find(|(index, _)| stem[index + 1..].starts_with(|c: char| c.is_ascii_digit()))
+            {
+                Some((index, _)) => &stem[..index],
+                None => stem,
+            }
+        }
+    };
+    Some(Requirement {
+        name: name.to_owned(),
+        specifier: String::new(),
+        url: Some(requirement.to_owned()),
+        marker: None,
+    })
+}
+
+fn is_local_path(requirement: &str) -> bool {
+    requirement.starts_with(['.', '/'])
+        || [".whl", ".tar.gz", ".zip"]
+            .iter()
+            .any(|extension| requirement.ends_with(extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn requirements() {
+        let content = r#"
+# Runtime dependencies
+--index-url https://pypi.org/simple
+-c constraints.txt
+requests==2.32.3 \
+    --hash=sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6 \
+    --hash=sha256:55365417734eb18255590a9ff9eb97e9e1da868d4ccd6402399eaf68af20a760
+tomli>=1.1.0; python_version < "3.11"  # only needed before 3.11
+anyio
+-e ./my-lib
+.
+"#;
+        let result = Requirements::default().soups(content, &Map::new());
+        assert_eq!(true, result.is_ok());
+        let soups = result.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(3, soups.len());
+        assert_eq!("anyio", soups[0].name);
+        assert_eq!("unknown", soups[0].version);
+        assert_eq!(Some(Ecosystem::PyPI), soups[0].ecosystem);
+        assert_eq!("requests", soups[1].name);
+        assert_eq!("2.32.3", soups[1].version);
+        assert_eq!(
+            Some(&Value::Array(vec![
+                Value::String(
+                    "sha256:70761cfe03c773ceb22aa2f671b4757976145175cdfca038c02654d061d6dcc6"
+                        .to_owned()
+                ),
+                Value::String(
+                    "sha256:55365417734eb18255590a9ff9eb97e9e1da868d4ccd6402399eaf68af20a760"
+                        .to_owned()
+                ),
+            ])),
+            soups[1].attributes.get("hashes")
+        );
+        assert_eq!("tomli", soups[2].name);
+        assert_eq!(">=1.1.0", soups[2].version);
+        assert_eq!(
+            Some(&Value::String(r#"python_version < "3.11""#.to_owned())),
+            soups[2].attributes.get("marker")
+        );
+    }
+
+    #[test_case(
+        "git+https://github.com/owner/toolbox.git@v0.3.0#egg=toolbox",
+        "toolbox",
+        "v0.3.0",
+        "git+https://github.com/owner/toolbox.git" ; "egg"
+    )]
+    #[test_case(
+        "-e git+https://github.com/owner/toolbox.git@v0.3.0",
+        "toolbox",
+        "v0.3.0",
+        "git+https://github.com/owner/toolbox.git" ; "editable"
+    )]
+    #[test_case(
+        "toolbox @ git+ssh://git@github.com/owner/toolbox.git",
+        "toolbox",
+        "unknown",
+        "git+ssh://git@github.com/owner/toolbox.git" ; "named"
+    )]
+    #[test_case(
+        "https://example.com/packages/toolbox-0.3.0.tar.gz",
+        "toolbox",
+        "unknown",
+        "https://example.com/packages/toolbox-0.3.0.tar.gz" ; "archive"
+    )]
+    fn urls(content: &str, name: &str, version: &str, source: &str) {
+        let soups = Requirements::default()
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(1, soups.len());
+        assert_eq!(name, soups[0].name);
+        assert_eq!(version, soups[0].version);
+        assert_eq!(
+            Some(&Value::String(source.to_owned())),
+            soups[0].attributes.get("source")
+        );
+    }
+
+    #[test_case("-r base.txt", Some("base.txt"))]
+    #[test_case("-rbase.txt", Some("base.txt"))]
+    #[test_case("--requirement=base.txt", Some("base.txt"))]
+    #[test_case("--requirements.txt", None)]
+    fn include_options(line: &str, value: Option<&str>) {
+        assert_eq!(value, option_value(line, &["-r", "--requirement"]));
+    }
+
+    #[test]
+    fn invalid_requirement() {
+        let result = Requirements::default().soups("requests[security", &Map::new());
+        assert_eq!(true, result.is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use super::{SoupParse, shell};
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parses `yum install`, `dnf install` and `microdnf install` commands of RPM based images, such
+/// as Red Hat UBI or Fedora
+pub struct Rpm {}
+
+static PROGRAMS: [&str; 3] = ["yum", "dnf", "microdnf"];
+static OPTIONS_WITH_VALUE: [&str; 12] = [
+    "-c",
+    "--config",
+    "-x",
+    "--exclude",
+    "--enablerepo",
+    "--disablerepo",
+    "--repo",
+    "--repoid",
+    "--releasever",
+    "--installroot",
+    "--setopt",
+    "--repofrompath",
+];
+lazy_static! {
+    /// `name`, `name-version` or `name-version-release`, where both version and release start
+    /// with a digit, e.g. `python3-pip-21.2.3-7.el9` but not `java-17-openjdk`
+    static ref PACKAGE: Regex = Regex::new(
+        r"^(?P<name>[a-zA-Z0-9_+][a-zA-Z0-9+\._\-]*?)(?:-(?P<version>[0-9][^\s\-]*(?:-[0-9][^\s\-]*)?))?$"
+    )
+    .unwrap();
+}
+
+impl SoupParse for Rpm {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut result: BTreeSet<Soup> = BTreeSet::new();
+        for command in shell::commands(content) {
+            let words = match shell::subcommand_words(
+                &command,
+                |program| PROGRAMS.contains(&program),
+                &["install"],
+                &OPTIONS_WITH_VALUE,
+            ) {
+                Some(words) => words,
+                None => continue,
+            };
+            for package in shell::operands(words, &OPTIONS_WITH_VALUE) {
+                // Local or remote .rpm files, package groups (`@group`) and provides such as
+                // `/usr/bin/python3` don't name a package of the repositories
+                if package.contains('/') || package.starts_with('@') || package.ends_with(".rpm") {
+                    continue;
+                }
+                if let Some(captures) = PACKAGE.captures(package) {
+                    result.insert(Soup {
+                        name: captures["name"].to_owned(),
+                        version: match captures.name("version") {
+                            Some(version) => version.as_str().to_owned(),
+                            None => "unknown".to_owned(),
+                        },
+                        ecosystem: Some(Ecosystem::Rpm),
+                        meta: default_meta.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::versions;
+    use test_case::test_case;
+
+    #[test_case("yum install -y httpd-2.4.57", &[("httpd", "2.4.57")])]
+    #[test_case("RUN dnf -y install python3-pip-21.2.3-7.el9 git", &[("git", "unknown"), ("python3-pip", "21.2.3-7.el9")])]
+    #[test_case("microdnf install --nodocs --setopt install_weak_deps=0 shadow-utils", &[("shadow-utils", "unknown")])]
+    #[test_case("dnf install -y libstdc++-devel-11.4.1", &[("libstdc++-devel", "11.4.1")])]
+    #[test_case("microdnf install java-17-openjdk-headless", &[("java-17-openjdk-headless", "unknown")])]
+    #[test_case("/usr/bin/yum install -y @development https://example.org/tool.rpm /usr/bin/python3", &[])]
+    fn packages(input: &str, expected: &[(&str, &str)]) {
+        let result = Rpm {}.soups(input, &Map::new()).unwrap();
+        assert_eq!(expected, versions(&result));
+    }
+
+    #[test_case("yum update -y")]
+    #[test_case("dnf module enable nodejs:20")]
+    #[test_case("microdnf clean all")]
+    fn no_install_statement(input: &str) {
+        assert_eq!(0, Rpm {}.soups(input, &Map::new()).unwrap().len());
+    }
+}
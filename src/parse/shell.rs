@@ -0,0 +1,211 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref COMMENT_LINE: Regex = Regex::new(r"(?m)^[ \t]*#.*\r?\n").unwrap();
+    static ref LINE_CONTINUATION: Regex = Regex::new(r"\\[ \t]*(?:#.*)?\r?\n").unwrap();
+}
+
+/// Splits shell scripts, such as the content of a Dockerfile, into commands consisting of words.
+///
+/// Line continuations are joined, quotes are removed from words and commands are separated by
+/// line breaks as well as the `;`, `&&`, `||`, `|` and `&` operators.
+pub fn commands(script: &str) -> Vec<Vec<String>> {
+    let script = normalize(script);
+    let mut commands: Vec<Vec<String>> = Vec::new();
+    let mut command: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = script.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => word.push(escaped),
+                            Some(other) => {
+                                word.push('\\');
+                                word.push(other);
+                            }
+                            None => word.push('\\'),
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            '#' if word.is_none() => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                end_word(&mut word, &mut command);
+                end_command(&mut command, &mut commands);
+            }
+            ' ' | '\t' | '\r' => end_word(&mut word, &mut command),
+            '\n' | ';' | '&' | '|' | '(' | ')' => {
+                end_word(&mut word, &mut command);
+                end_command(&mut command, &mut commands);
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    end_word(&mut word, &mut command);
+    end_command(&mut command, &mut commands);
+    commands
+}
+
+/// Words following the given subcommand of a program within a command, e.g. `-y curl` of
+/// `RUN apt-get -o Debug::pkgProblemResolver=yes install -y curl`. Options preceding the
+/// subcommand are skipped, along with the values of those in `options_with_value`.
+pub fn subcommand_words<'a, P>(
+    command: &'a [String],
+    is_program: P,
+    subcommands: &[&str],
+    options_with_value: &[&str],
+) -> Option<&'a [String]>
+where
+    P: Fn(&str) -> bool,
+{
+    let program = command
+        .iter()
+        .position(|word| is_program(program_name(word)))?;
+    let mut index = program + 1;
+    while let Some(word) = command.get(index) {
+        if options_with_value.contains(&word.as_str()) {
+            index += 2;
+        } else if word.starts_with('-') {
+            index += 1;
+        } else if subcommands.contains(&word.as_str()) {
+            return Some(&command[index + 1..]);
+        } else {
+            return None;
+        }
+    }
+    None
+}
+
+/// Words that are neither options nor values of those in `options_with_value`
+pub fn operands<'a>(words: &'a [String], options_with_value: &[&str]) -> Vec<&'a String> {
+    let mut operands = Vec::new();
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if options_with_value.contains(&word.as_str()) {
+            words.next();
+        } else if !word.starts_with('-') {
+            operands.push(word);
+        }
+    }
+    operands
+}
+
+/// Name of the program that a word invokes, without the directory, e.g. `pip` for
+/// `/usr/bin/pip`
+pub fn program_name(word: &str) -> &str {
+    match word.rsplit_once('/') {
+        Some((_, program)) => program,
+        None => word,
+    }
+}
+
+fn normalize(script: &str) -> String {
+    let result = COMMENT_LINE.replace_all(script, "");
+    let result = LINE_CONTINUATION.replace_all(&result, " ");
+    result.to_string()
+}
+
+fn end_word(word: &mut Option<String>, command: &mut Vec<String>) {
+    if let Some(word) = word.take() {
+        command.push(word);
+    }
+}
+
+fn end_command(command: &mut Vec<String>, commands: &mut Vec<Vec<String>>) {
+    if !command.is_empty() {
+        commands.push(std::mem::take(command));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test_case("apt-get update && apt-get install curl" ; "and")]
+    #[test_case("apt-get update; apt-get install curl" ; "semicolon")]
+    #[test_case("apt-get update || apt-get install curl" ; "or")]
+    #[test_case("apt-get update\napt-get install curl" ; "line_break")]
+    #[test_case("apt-get update \\\n && apt-get install curl" ; "line_continuation")]
+    #[test_case("apt-get update \\\r\n && apt-get install curl" ; "line_continuation_crlf")]
+    #[test_case("apt-get update \\ # Some comment\n && apt-get install curl" ; "line_continuation_with_comment")]
+    #[test_case("apt-get update \\\n # Some comment\n && apt-get install curl" ; "comment_line_in_continuation")]
+    fn separated_commands(input: &str) {
+        assert_eq!(
+            vec![
+                words(&["apt-get", "update"]),
+                words(&["apt-get", "install", "curl"])
+            ],
+            commands(input)
+        );
+    }
+
+    #[test_case(r#"echo "some text" 'more text'"#, &["echo", "some text", "more text"])]
+    #[test_case(r#"echo "a \"quoted\" text""#, &["echo", r#"a "quoted" text"#])]
+    #[test_case(r#"echo some\ text"#, &["echo", "some text"])]
+    #[test_case(r#"echo "a;b" 'c&&d'"#, &["echo", "a;b", "c&&d"])]
+    #[test_case("echo text # Some comment", &["echo", "text"])]
+    fn quoted_words(input: &str, expected: &[&str]) {
+        assert_eq!(vec![words(expected)], commands(input));
+    }
+
+    #[test_case("RUN apt-get install -y curl", Some(&["-y", "curl"]))]
+    #[test_case("/usr/bin/apt-get -o Foo=bar install curl", Some(&["curl"]))]
+    #[test_case("apt-get -q install", Some(&[]))]
+    #[test_case("apt-get update", None)]
+    #[test_case("apt-get -o install", None)]
+    #[test_case("echo install", None)]
+    fn words_of_subcommand(input: &str, expected: Option<&[&str]>) {
+        let command = commands(input).remove(0);
+        assert_eq!(
+            expected.map(words),
+            subcommand_words(
+                &command,
+                |program| program == "apt-get",
+                &["install"],
+                &["-o"]
+            )
+            .map(|words| words.to_vec())
+        );
+    }
+
+    #[test]
+    fn operands_without_options() {
+        assert_eq!(
+            vec!["curl", "git"],
+            operands(
+                &words(&["-y", "curl", "-t", "jammy", "--quiet", "git"]),
+                &["-t"]
+            )
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use hcl::{Body, Value as HclValue};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+const DEFAULT_REGISTRY: &str = "registry.terraform.io";
+const DEFAULT_NAMESPACE: &str = "hashicorp";
+
+/// Parses the providers required in `terraform { required_providers { .. } }` blocks and the
+/// modules called in `module` blocks of a Terraform configuration file (`*.tf`)
+pub struct Terraform {
+    /// Content of the `.terraform.lock.hcl` in the directory of the configuration
+    pub lockfile: Option<String>,
+}
+
+struct LockedProvider {
+    address: String,
+    version: String,
+    hashes: Vec<String>,
+}
+
+impl SoupParse for Terraform {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let body = parse(content, "configuration")?;
+        let locked = match &self.lockfile {
+            Some(lockfile) => locked_providers(&parse(lockfile, "lock file")?),
+            None => Vec::new(),
+        };
+        let mut soups = BTreeSet::new();
+        let required_providers = body
+            .blocks()
+            .filter(|block| block.identifier() == "terraform")
+            .flat_map(|block| block.body().blocks())
+            .filter(|block| block.identifier() == "required_providers")
+            .flat_map(|block| block.body().attributes());
+        for provider in required_providers {
+            let (source, requirement) = match HclValue::from(provider.expr().clone()) {
+                // Prior to Terraform 0.13, providers were required by version only
+                HclValue::String(requirement) => (None, Some(requirement)),
+                HclValue::Object(object) => (
+                    object.get("source").and_then(as_string),
+                    object.get("version").and_then(as_string),
+                ),
+                _ => continue,
+            };
+            let source =
+                source.unwrap_or_else(|| format!("{}/{}", DEFAULT_NAMESPACE, provider.key()));
+            let address = provider_address(&source);
+            let mut attributes = Map::new();
+            let version = match locked.iter().find(|locked| locked.address == address) {
+                Some(locked) => {
+                    if let Some(requirement) = requirement {
+                        attributes.insert("requirement".to_owned(), Value::String(requirement));
+                    }
+                    if !locked.hashes.is_empty() {
+                        attributes.insert(
+                            "hashes".to_owned(),
+                            Value::Array(
+                                locked
+                                    .hashes
+                                    .iter()
+                                    .map(|hash| Value::String(hash.to_owned()))
+                                    .collect(),
+                            ),
+                        );
+                    }
+                    locked.version.to_owned()
+                }
+                None => requirement.unwrap_or("unknown".to_owned()),
+            };
+            soups.insert(Soup {
+                name: source,
+                version,
+                ecosystem: Some(Ecosystem::Terraform),
+                scope: None,
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+        for module in body.blocks().filter(|block| block.identifier() == "module") {
+            let attribute = |key: &str| {
+                module
+                    .body()
+                    .attributes()
+                    .find(|attribute| attribute.key() == key)
+                    .and_then(|attribute| as_string(&HclValue::from(attribute.expr().clone())))
+            };
+            let source = match attribute("source") {
+                Some(source) => source,
+                None => continue,
+            };
+            // Modules within the repository are no SOUP of their own
+            if source.starts_with("./") || source.starts_with("../") {
+                continue;
+            }
+            let mut attributes = Map::new();
+            if let Some(label) = module.labels().first() {
+                attributes.insert("alias".to_owned(), Value::String(label.as_str().to_owned()));
+            }
+            // Only registry modules have a version, others may be pinned by a `?ref=` of their
+            // source, e.g. `git::https://example.com/vpc.git?ref=v1.2.0`
+            let (name, version) = match (attribute("version"), source.split_once("?ref=")) {
+                (Some(version), _) => (source, version),
+                (None, Some((name, git_ref))) => (name.to_owned(), git_ref.to_owned()),
+                (None, None) => (source, "unknown".to_owned()),
+            };
+            soups.insert(Soup {
+                name,
+                version,
+                ecosystem: Some(Ecosystem::Terraform),
+                scope: None,
+                attributes,
+                meta: default_meta.clone(),
+            });
+        }
+        Ok(soups)
+    }
+}
+
+fn parse(content: &str, kind: &str) -> Result<Body, SoupSourceParseError> {
+    match hcl::parse(content) {
+        Ok(body) => Ok(body),
+        Err(e) => Err(SoupSourceParseError {
+            message: format!("Invalid Terraform {} ({})", kind, e),
+        }),
+    }
+}
+
+fn locked_providers(lockfile: &Body) -> Vec<LockedProvider> {
+    lockfile
+        .blocks()
+        .filter(|block| block.identifier() == "provider")
+        .filter_map(|block| {
+            let address = block.labels().first()?.as_str().to_owned();
+            let mut version = None;
+            let mut hashes = Vec::new();
+            for attribute in block.body().attributes() {
+                match (attribute.key(), HclValue::from(attribute.expr().clone())) {
+                    ("version", HclValue::String(value)) => version = Some(value),
+                    ("hashes", HclValue::Array(values)) => {
+                        hashes = values.iter().filter_map(as_string).collect()
+                    }
+                    _ => {}
+                }
+            }
+            Some(LockedProvider {
+                address,
+                version: version?,
+                hashes,
+            })
+        })
+        .collect()
+}
+
+/// Fully qualified address of a provider source, e.g. `registry.terraform.io/hashicorp/aws` for
+/// `hashicorp/aws`, as used in lock files
+fn provider_address(source: &str) -> String {
+    match source.split('/').count() {
+        2 => format!("{}/{}", DEFAULT_REGISTRY, source),
+        _ => source.to_owned(),
+    }
+}
+
+fn as_string(value: &HclValue) -> Option<String> {
+    value.as_str().map(|value| value.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CONFIGURATION: &str = r#"
+terraform {
+  required_version = ">= 1.5"
+
+  required_providers {
+    aws = {
+      source  = "hashicorp/aws"
+      version = "~> 5.0"
+    }
+    cloudflare = {
+      source = "cloudflare/cloudflare"
+    }
+    random = "~> 3.5"
+  }
+}
+
+module "vpc" {
+  source  = "terraform-aws-modules/vpc/aws"
+  version = "5.8.1"
+
+  cidr = "10.0.0.0/16"
+}
+
+module "dns" {
+  source = "git::https://example.com/infra/dns.git?ref=v1.2.0"
+}
+
+module "network" {
+  source = "./modules/network"
+}
+
+resource "aws_s3_bucket" "logs" {
+  bucket = "logs"
+}
+"#;
+
+    const LOCKFILE: &str = r#"
+# This file is maintained automatically by "terraform init".
+# Manual edits may be lost in future updates.
+
+provider "registry.terraform.io/hashicorp/aws" {
+  version     = "5.54.1"
+  constraints = "~> 5.0"
+  hashes = [
+    "h1:+aq386lQKbFKwMdDNbDm7WVZQnY2WsSQxX2r6nbp5Pk=",
+    "zh:0c0a2bd5b6a0d1c0e0e2d3b2d0a3f4e5d6c7b8a9f0e1d2c3b4a5968778695a4b",
+  ]
+}
+"#;
+
+    fn soups(lockfile: Option<&str>) -> Vec<Soup> {
+        Terraform {
+            lockfile: lockfile.map(|lockfile| lockfile.to_owned()),
+        }
+        .soups(CONFIGURATION, &Map::new())
+        .unwrap()
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn declared_versions() {
+        let soups = soups(None);
+        assert_eq!(
+            vec![
+                ("cloudflare/cloudflare", "unknown"),
+                ("git::https://example.com/infra/dns.git", "v1.2.0"),
+                ("hashicorp/aws", "~> 5.0"),
+                ("hashicorp/random", "~> 3.5"),
+                ("terraform-aws-modules/vpc/aws", "5.8.1"),
+            ],
+            soups
+                .iter()
+                .map(|soup| (soup.name.as_str(), soup.version.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&Value::String("vpc".to_owned())),
+            soups[4].attributes.get("alias")
+        );
+    }
+
+    #[test]
+    fn locked_versions() {
+        let soups = soups(Some(LOCKFILE));
+        let aws = soups
+            .iter()
+            .find(|soup| soup.name == "hashicorp/aws")
+            .unwrap();
+        assert_eq!("5.54.1", aws.version);
+        assert_eq!(
+            Some(&Value::String("~> 5.0".to_owned())),
+            aws.attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(2),
+            aws.attributes
+                .get("hashes")
+                .and_then(|hashes| hashes.as_array())
+                .map(|hashes| hashes.len())
+        );
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        assert_eq!(
+            true,
+            Terraform { lockfile: None }
+                .soups("module \"vpc\" {", &Map::new())
+                .is_err()
+        );
+    }
+
+    #[test_case("hashicorp/aws", "registry.terraform.io/hashicorp/aws")]
+    #[test_case("registry.example.com/team/aws", "registry.example.com/team/aws")]
+    fn provider_addresses(source: &str, expected: &str) {
+        assert_eq!(expected, provider_address(source));
+    }
+}
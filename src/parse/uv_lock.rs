@@ -0,0 +1,188 @@
+use super::pep508::normalize_name;
+use super::python_lock::{LockedPackage, PythonLock};
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Kinds of package sources that refer to projects within the repository
+const LOCAL_SOURCES: [&str; 4] = ["editable", "virtual", "path", "directory"];
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    package: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: Option<String>,
+    #[serde(default)]
+    source: BTreeMap<String, String>,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+    sdist: Option<Artifact>,
+    #[serde(default)]
+    wheels: Vec<Artifact>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    hash: Option<String>,
+}
+
+/// Parses a uv.lock, which lists every package of the workspace that it belongs to, including
+/// the workspace members themselves
+pub fn parse(content: &str) -> Result<PythonLock, SoupSourceParseError> {
+    let content: Content = match toml::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid uv.lock ({})", e),
+            });
+        }
+    };
+
+    let mut packages = BTreeMap::new();
+    for package in content.package {
+        if LOCAL_SOURCES
+            .iter()
+            .any(|source| package.source.contains_key(*source))
+        {
+            continue;
+        }
+        let version = match package.version {
+            Some(version) => version,
+            None => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid uv.lock (missing version of {})", package.name),
+                });
+            }
+        };
+        // Git sources carry the requested reference as query and the commit as fragment
+        let source = match (package.source.get("git"), package.source.get("url")) {
+            (Some(git), _) => git
+                .split(['?', '#'])
+                .next()
+                .map(|repository| format!("git+{}", repository)),
+            (None, Some(url)) => Some(url.to_owned()),
+            (None, None) => None,
+        };
+        let hashes = package
+            .sdist
+            .iter()
+            .chain(package.wheels.iter())
+            .filter_map(|artifact| artifact.hash.to_owned())
+            .collect();
+        let mut dependencies = package
+            .dependencies
+            .iter()
+            .map(|dependency| normalize_name(&dependency.name))
+            .collect::<Vec<_>>();
+        dependencies.sort();
+        dependencies.dedup();
+        packages
+            .entry(normalize_name(&package.name))
+            .or_insert(LockedPackage {
+                name: package.name,
+                version,
+                hashes,
+                source,
+                dependencies,
+            });
+    }
+    Ok(PythonLock { packages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = r#"
+version = 1
+requires-python = ">=3.12"
+
+[[package]]
+name = "anyio"
+version = "4.4.0"
+source = { registry = "https://pypi.org/simple" }
+dependencies = [
+    { name = "idna" },
+    { name = "sniffio" },
+]
+sdist = { url = "https://files.pythonhosted.org/packages/anyio-4.4.0.tar.gz", hash = "sha256:5aadc6a1bbb7cdb0bede386cac5e2940f5e2ff3aa20277e991cf028e0585ce94", size = 163930 }
+wheels = [
+    { url = "https://files.pythonhosted.org/packages/anyio-4.4.0-py3-none-any.whl", hash = "sha256:c1b2d8f46a8a812513012e1107cb0e68c17159a7a594208005a57dc776e1bdc7", size = 86780 },
+]
+
+[[package]]
+name = "idna"
+version = "3.7"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+source = { editable = "." }
+dependencies = [
+    { name = "anyio" },
+    { name = "toolbox" },
+]
+
+[[package]]
+name = "sniffio"
+version = "1.3.1"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "toolbox"
+version = "0.3.0"
+source = { git = "https://github.com/owner/toolbox?tag=v0.3.0#4f3b4ad7a0a1c6e1e4b5d9a1c0b7e2f3a4b5c6d7" }
+"#;
+
+    #[test]
+    fn packages() {
+        let lock = parse(LOCKFILE).unwrap();
+        assert_eq!(4, lock.packages.len());
+        assert_eq!(true, lock.resolve("my-app").is_none());
+        let anyio = lock.resolve("anyio").unwrap();
+        assert_eq!("4.4.0", anyio.version);
+        assert_eq!(2, anyio.hashes.len());
+        assert_eq!(None, anyio.source);
+        let toolbox = lock.resolve("toolbox").unwrap();
+        assert_eq!(
+            Some("git+https://github.com/owner/toolbox".to_owned()),
+            toolbox.source
+        );
+    }
+
+    #[test]
+    fn transitive_packages() {
+        let lock = parse(LOCKFILE).unwrap();
+        let direct = vec![(lock.resolve("anyio").unwrap(), "runtime")];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| (transitive.package.name.as_str(), transitive.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("idna", vec!["anyio"]), ("sniffio", vec!["anyio"])],
+            transitive
+        );
+    }
+
+    #[test]
+    fn missing_version() {
+        let content = r#"
+[[package]]
+name = "anyio"
+source = { registry = "https://pypi.org/simple" }
+"#;
+        assert_eq!(true, parse(content).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use super::SoupParse;
+use crate::soup::model::{Ecosystem, Soup, SoupSourceParseError};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+const RUNTIME_SCOPE: &str = "runtime";
+const BUILD_SCOPE: &str = "build";
+
+/// Parses the dependencies of a vcpkg manifest (vcpkg.json)
+pub struct Vcpkg {}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+    #[serde(default)]
+    overrides: Vec<Override>,
+    #[serde(rename = "builtin-baseline")]
+    builtin_baseline: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Dependency {
+    Name(String),
+    Details {
+        name: String,
+        #[serde(rename = "version>=")]
+        minimum_version: Option<String>,
+        #[serde(default)]
+        host: bool,
+    },
+}
+
+/// Overrides pin a port to a version, given in one of the version schemes of vcpkg
+#[derive(Deserialize)]
+struct Override {
+    name: String,
+    version: Option<String>,
+    #[serde(rename = "version-semver")]
+    version_semver: Option<String>,
+    #[serde(rename = "version-date")]
+    version_date: Option<String>,
+    #[serde(rename = "version-string")]
+    version_string: Option<String>,
+    #[serde(rename = "port-version")]
+    port_version: Option<u32>,
+}
+
+impl Override {
+    fn version(&self) -> Option<String> {
+        let version = self
+            .version
+            .as_ref()
+            .or(self.version_semver.as_ref())
+            .or(self.version_date.as_ref())
+            .or(self.version_string.as_ref())?;
+        Some(match self.port_version {
+            Some(port_version) if port_version > 0 => format!("{}#{}", version, port_version),
+            _ => version.to_owned(),
+        })
+    }
+}
+
+impl SoupParse for Vcpkg {
+    fn soups(
+        &self,
+        content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let content: Content = match serde_json::from_str(content) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid vcpkg manifest ({})", e),
+                });
+            }
+        };
+        Ok(content
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                let (name, minimum_version, host) = match dependency {
+                    Dependency::Name(name) => (name, None, false),
+                    Dependency::Details {
+                        name,
+                        minimum_version,
+                        host,
+                    } => (name, minimum_version.as_ref(), *host),
+                };
+                let requirement = minimum_version.map(|version| format!(">={}", version));
+                let mut attributes = Map::new();
+                // Unless overridden, the versions of ports are those of the baseline commit of
+                // the vcpkg registry, or newer ones if required
+                if let Some(baseline) = &content.builtin_baseline {
+                    attributes.insert("baseline".to_owned(), Value::String(baseline.to_owned()));
+                }
+                let overridden = content
+                    .overrides
+                    .iter()
+                    .find(|item| &item.name == name)
+                    .and_then(|item| item.version());
+                let version = match (overridden, requirement) {
+                    (Some(version), Some(requirement)) => {
+                        attributes.insert("requirement".to_owned(), Value::String(requirement));
+                        version
+                    }
+                    (Some(version), None) | (None, Some(version)) => version,
+                    (None, None) => "unknown".to_owned(),
+                };
+                Soup {
+                    name: name.to_owned(),
+                    version,
+                    ecosystem: Some(Ecosystem::Vcpkg),
+                    scope: Some(match host {
+                        true => BUILD_SCOPE.to_owned(),
+                        false => RUNTIME_SCOPE.to_owned(),
+                    }),
+                    attributes,
+                    meta: default_meta.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependencies() {
+        let content = r#"{
+            "name": "firmware-tools",
+            "version": "1.0.0",
+            "dependencies": [
+                "fmt",
+                { "name": "openssl", "version>=": "3.0.8" },
+                { "name": "zlib", "version>=": "1.2.13", "platform": "!windows" },
+                { "name": "vcpkg-cmake", "host": true }
+            ],
+            "overrides": [
+                { "name": "fmt", "version": "10.1.1" },
+                { "name": "zlib", "version": "1.3", "port-version": 1 }
+            ],
+            "builtin-baseline": "3426db05b996481ca31e95fff3734cf23e0f51bc"
+        }"#;
+        let soups = Vcpkg {}
+            .soups(content, &Map::new())
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("fmt", "10.1.1", "runtime"),
+                ("openssl", ">=3.0.8", "runtime"),
+                ("vcpkg-cmake", "unknown", "build"),
+                ("zlib", "1.3#1", "runtime"),
+            ],
+            soups
+                .iter()
+                .map(|soup| (
+                    soup.name.as_str(),
+                    soup.version.as_str(),
+                    soup.scope.as_deref().unwrap()
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&Value::String(">=1.2.13".to_owned())),
+            soups[3].attributes.get("requirement")
+        );
+        assert_eq!(
+            Some(&Value::String(
+                "3426db05b996481ca31e95fff3734cf23e0f51bc".to_owned()
+            )),
+            soups[0].attributes.get("baseline")
+        );
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        assert_eq!(
+            true,
+            Vcpkg {}.soups("{\"dependencies\": {", &Map::new()).is_err()
+        );
+    }
+}
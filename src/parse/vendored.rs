@@ -0,0 +1,160 @@
+use super::{SoupParse, cmake};
+use crate::soup::model::{Soup, SoupSourceParseError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Names of files that hold nothing but the version of a library
+pub const VERSION_FILES: [&str; 3] = ["VERSION", "VERSION.txt", "version.txt"];
+
+lazy_static! {
+    static ref VERSION_STRING: Regex =
+        Regex::new(r#"(?m)^\s*#\s*define\s+\w*VERSION(?:_STRING|_STR)?\s+"v?(?P<version>\d[^"]*)""#)
+            .unwrap();
+    static ref VERSION_PART: Regex = Regex::new(
+        r"(?m)^\s*#\s*define\s+\w*?(?:VERSION_(?P<suffixed>MAJOR|MINOR|PATCH)|(?P<prefixed>MAJOR|MINOR|PATCH)_VERSION)\s+\(?(?P<number>\d+)"
+    )
+    .unwrap();
+}
+
+/// Reports a library that is copied into a directory such as `third_party/`, with its version
+/// taken from the first of its files that hints at it
+pub struct VendoredLibrary {
+    /// Name of the directory of the library
+    pub name: String,
+    /// Paths, relative to the directory of the library, and contents of files that may hint at
+    /// its version, i.e. version files, CMakeLists.txt and version headers
+    pub hints: Vec<(String, String)>,
+}
+
+impl SoupParse for VendoredLibrary {
+    fn soups(
+        &self,
+        _content: &str,
+        default_meta: &Map<String, Value>,
+    ) -> Result<BTreeSet<Soup>, SoupSourceParseError> {
+        let mut attributes = Map::new();
+        let hint = self
+            .hints
+            .iter()
+            .find_map(|(path, content)| Some((path, version_hint(path, content)?)));
+        let version = match hint {
+            Some((path, version)) => {
+                attributes.insert("version_hint".to_owned(), Value::String(path.to_owned()));
+                version
+            }
+            None => "unknown".to_owned(),
+        };
+        Ok(BTreeSet::from([Soup {
+            name: self.name.to_owned(),
+            version,
+            ecosystem: None,
+            scope: None,
+            attributes,
+            meta: default_meta.clone(),
+        }]))
+    }
+}
+
+/// Version that a file hints at, depending on its kind
+fn version_hint(path: &str, content: &str) -> Option<String> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if VERSION_FILES.contains(&file_name) {
+        return content
+            .lines()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty())
+            .map(|line| line.to_owned());
+    }
+    if file_name == "CMakeLists.txt" {
+        return project_version(content);
+    }
+    header_version(content)
+}
+
+/// Version of `project(<name> VERSION <version> ...)`
+fn project_version(content: &str) -> Option<String> {
+    cmake::commands(content)
+        .ok()?
+        .into_iter()
+        .filter(|(name, _)| name == "project")
+        .find_map(|(_, arguments)| {
+            let index = arguments
+                .iter()
+                .position(|argument| argument == "VERSION")?;
+            arguments.get(index + 1).cloned()
+        })
+}
+
+/// Version of a header, defined either as string, e.g. `#define LIB_VERSION_STRING "1.2.3"`, or
+/// by its parts, e.g. `#define LIB_VERSION_MAJOR 1`
+fn header_version(content: &str) -> Option<String> {
+    if let Some(captures) = VERSION_STRING.captures(content) {
+        return Some(captures["version"].to_owned());
+    }
+    let part = |name: &str| {
+        VERSION_PART
+            .captures_iter(content)
+            .find(|captures| {
+                captures
+                    .name("suffixed")
+                    .or(captures.name("prefixed"))
+                    .is_some_and(|part| part.as_str() == name)
+            })
+            .map(|captures| captures["number"].to_owned())
+    };
+    let mut version = vec![part("MAJOR")?, part("MINOR")?];
+    version.extend(part("PATCH"));
+    Some(version.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("VERSION", "\n2.6.0\n", Some("2.6.0"))]
+    #[test_case(
+        "CMakeLists.txt",
+        "cmake_minimum_required(VERSION 3.5)\nproject(lwIP VERSION 2.2.0 LANGUAGES C)\n",
+        Some("2.2.0")
+    )]
+    #[test_case("CMakeLists.txt", "project(lwIP C)\n", None)]
+    #[test_case(
+        "include/mbedtls/version.h",
+        "#define MBEDTLS_VERSION_MAJOR  3\n#define MBEDTLS_VERSION_MINOR  6\n#define MBEDTLS_VERSION_PATCH  0\n#define MBEDTLS_VERSION_STRING         \"3.6.0\"\n",
+        Some("3.6.0")
+    )]
+    #[test_case(
+        "src/version.h",
+        "#define FREERTOS_MAJOR_VERSION 11\n#define FREERTOS_MINOR_VERSION 1\n",
+        Some("11.1")
+    )]
+    #[test_case("src/version.h", "#define BUILD_DATE \"2024-06-01\"\n", None)]
+    fn hints(path: &str, content: &str, expected: Option<&str>) {
+        assert_eq!(expected.map(|v| v.to_owned()), version_hint(path, content));
+    }
+
+    #[test]
+    fn first_hint() {
+        let library = VendoredLibrary {
+            name: "lwip".to_owned(),
+            hints: vec![
+                ("CMakeLists.txt".to_owned(), "project(lwIP C)\n".to_owned()),
+                (
+                    "src/include/lwip/version.h".to_owned(),
+                    "#define LWIP_VERSION_MAJOR 2\n#define LWIP_VERSION_MINOR 2\n#define LWIP_VERSION_REVISION 0\n"
+                        .to_owned(),
+                ),
+            ],
+        };
+        let soups = library.soups("", &Map::new()).unwrap();
+        let soup = soups.first().unwrap();
+        assert_eq!(("lwip", "2.2"), (soup.name.as_str(), soup.version.as_str()));
+        assert_eq!(
+            Some(&Value::String("src/include/lwip/version.h".to_owned())),
+            soup.attributes.get("version_hint")
+        );
+    }
+}
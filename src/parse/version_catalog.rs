@@ -0,0 +1,215 @@
+use crate::soup::model::SoupSourceParseError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Libraries of a Gradle version catalog such as `gradle/libs.versions.toml`, by the accessor
+/// that build files reference them with, e.g. `spring.boot.starter` for `libs.spring.boot.starter`
+pub struct VersionCatalog {
+    libraries: HashMap<String, Library>,
+    bundles: HashMap<String, Vec<String>>,
+}
+
+pub struct Library {
+    pub group: String,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    versions: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    libraries: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    bundles: BTreeMap<String, Vec<String>>,
+}
+
+impl VersionCatalog {
+    /// Libraries that a reference such as `libs.spring.boot.starter` or `libs.bundles.jackson`
+    /// refers to
+    pub fn libraries(&self, reference: &str) -> Vec<&Library> {
+        let reference = reference.strip_prefix("libs.").unwrap_or(reference);
+        let reference = reference.strip_suffix(".get").unwrap_or(reference);
+        match reference.strip_prefix("bundles.") {
+            Some(bundle) => self
+                .bundles
+                .get(bundle)
+                .into_iter()
+                .flatten()
+                .filter_map(|alias| self.libraries.get(alias))
+                .collect(),
+            None => self.libraries.get(reference).into_iter().collect(),
+        }
+    }
+}
+
+pub fn parse(content: &str) -> Result<VersionCatalog, SoupSourceParseError> {
+    let content: Content = match toml::from_str(content) {
+        Ok(content) => content,
+        Err(e) => {
+            return Err(SoupSourceParseError {
+                message: format!("Invalid version catalog ({})", e),
+            });
+        }
+    };
+    let versions = content
+        .versions
+        .iter()
+        .filter_map(|(alias, version)| Some((alias.to_owned(), version_of(version, None)?)))
+        .collect::<HashMap<String, String>>();
+
+    let mut libraries = HashMap::new();
+    for (alias, library) in &content.libraries {
+        let (module, version) = match library {
+            toml::Value::String(notation) => {
+                let mut parts = notation.splitn(3, ':');
+                let module = format!(
+                    "{}:{}",
+                    parts.next().unwrap_or(""),
+                    parts.next().unwrap_or("")
+                );
+                (module, parts.next().map(|version| version.to_owned()))
+            }
+            toml::Value::Table(table) => {
+                let string = |key: &str| table.get(key).and_then(|value| value.as_str());
+                let module = match (string("module"), string("group"), string("name")) {
+                    (Some(module), _, _) => module.to_owned(),
+                    (None, Some(group), Some(name)) => format!("{}:{}", group, name),
+                    _ => return Err(invalid_library(alias)),
+                };
+                let version = table
+                    .get("version")
+                    .and_then(|version| version_of(version, Some(&versions)));
+                (module, version)
+            }
+            _ => return Err(invalid_library(alias)),
+        };
+        let (group, name) = match module.split_once(':') {
+            Some((group, name)) if !group.is_empty() && !name.is_empty() => (group, name),
+            _ => return Err(invalid_library(alias)),
+        };
+        libraries.insert(
+            accessor(alias),
+            Library {
+                group: group.to_owned(),
+                name: name.to_owned(),
+                version,
+            },
+        );
+    }
+    let bundles = content
+        .bundles
+        .into_iter()
+        .map(|(alias, libraries)| {
+            let libraries = libraries.iter().map(|library| accessor(library)).collect();
+            (accessor(&alias), libraries)
+        })
+        .collect();
+    Ok(VersionCatalog { libraries, bundles })
+}
+
+/// Version of a library or a `[versions]` entry, which is either a version or a table of rich
+/// version constraints, e.g. `{ strictly = "[1.0, 2.0[", prefer = "1.5" }`, or a reference to a
+/// `[versions]` entry
+fn version_of(value: &toml::Value, versions: Option<&HashMap<String, String>>) -> Option<String> {
+    match value {
+        toml::Value::String(version) => Some(version.to_owned()),
+        toml::Value::Table(table) => {
+            if let (Some(reference), Some(versions)) = (table.get("ref"), versions) {
+                return versions.get(reference.as_str()?).cloned();
+            }
+            ["strictly", "require", "prefer"]
+                .iter()
+                .find_map(|key| table.get(*key).and_then(|value| value.as_str()))
+                .map(|version| version.to_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Accessor of an alias, where `-`, `_` and `.` all separate segments, e.g. `spring.boot` for
+/// `spring-boot`
+fn accessor(alias: &str) -> String {
+    alias.replace(['-', '_'], ".")
+}
+
+fn invalid_library(alias: &str) -> SoupSourceParseError {
+    SoupSourceParseError {
+        message: format!("Invalid version catalog (malformed library {})", alias),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CATALOG: &str = r#"
+[versions]
+jackson = "2.17.1"
+junit = { strictly = "[5.10, 6.0[", prefer = "5.10.2" }
+
+[libraries]
+jackson-databind = { module = "com.fasterxml.jackson.core:jackson-databind", version.ref = "jackson" }
+jackson-annotations = { group = "com.fasterxml.jackson.core", name = "jackson-annotations", version.ref = "jackson" }
+junit-jupiter = { module = "org.junit.jupiter:junit-jupiter", version.ref = "junit" }
+guava = "com.google.guava:guava:33.2.1-jre"
+slf4j_api = { module = "org.slf4j:slf4j-api", version = { require = "2.0.13" } }
+spring-boot-starter = { module = "org.springframework.boot:spring-boot-starter" }
+
+[bundles]
+jackson = ["jackson-databind", "jackson-annotations"]
+
+[plugins]
+spring-boot = { id = "org.springframework.boot", version = "3.3.0" }
+"#;
+
+    #[test_case("libs.guava", "com.google.guava", "guava", Some("33.2.1-jre"))]
+    #[test_case(
+        "libs.jackson.databind",
+        "com.fasterxml.jackson.core",
+        "jackson-databind",
+        Some("2.17.1")
+    )]
+    #[test_case(
+        "libs.junit.jupiter",
+        "org.junit.jupiter",
+        "junit-jupiter",
+        Some("[5.10, 6.0[")
+    )]
+    #[test_case("libs.slf4j.api", "org.slf4j", "slf4j-api", Some("2.0.13"))]
+    #[test_case(
+        "libs.spring.boot.starter.get",
+        "org.springframework.boot",
+        "spring-boot-starter",
+        None
+    )]
+    fn libraries(reference: &str, group: &str, name: &str, version: Option<&str>) {
+        let catalog = parse(CATALOG).unwrap();
+        let libraries = catalog.libraries(reference);
+        assert_eq!(1, libraries.len());
+        assert_eq!(group, libraries[0].group);
+        assert_eq!(name, libraries[0].name);
+        assert_eq!(version, libraries[0].version.as_deref());
+    }
+
+    #[test]
+    fn bundles() {
+        let catalog = parse(CATALOG).unwrap();
+        let names = catalog
+            .libraries("libs.bundles.jackson")
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["jackson-databind", "jackson-annotations"], names);
+        assert_eq!(0, catalog.libraries("libs.versions.jackson").len());
+    }
+
+    #[test_case("[libraries]\nguava = \"com.google.guava\"" ; "missing_name")]
+    #[test_case("[libraries]\nguava = { version = \"1.0\" }" ; "missing_module")]
+    #[test_case("[libraries" ; "invalid_toml")]
+    fn invalid_catalog(content: &str) {
+        assert_eq!(true, parse(content).is_err());
+    }
+}
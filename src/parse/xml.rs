@@ -0,0 +1,127 @@
+use crate::soup::model::SoupSourceParseError;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion, escape::resolve_predefined_entity};
+use std::path::Path;
+#[cfg(test)]
+use std::{collections::HashMap, path::PathBuf};
+
+/// Reads the file at the given path, if it exists, e.g. a parent POM or an imported project
+pub type Load<'a> = dyn Fn(&Path) -> Option<String> + 'a;
+
+/// Reads files from the given paths and contents instead of the file system
+#[cfg(test)]
+pub fn load_from(files: &[(&str, &str)]) -> impl Fn(&Path) -> Option<String> {
+    let files = files
+        .iter()
+        .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+        .collect::<HashMap<PathBuf, String>>();
+    move |path: &Path| files.get(path).cloned()
+}
+
+/// An element of an XML document, without namespace prefixes
+pub struct Node {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    pub text: String,
+}
+
+impl Node {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First child element with the given name
+    pub fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Child elements with the given name
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Trimmed text of the first child element with the given name, if not empty
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .map(|child| child.text.trim())
+            .filter(|text| !text.is_empty())
+    }
+}
+
+/// Parses an XML document into a tree of elements, where the returned node is the document
+/// itself, having the root element as its child
+pub fn parse(content: &str) -> Result<Node, SoupSourceParseError> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().expand_empty_elements = true;
+    let mut stack: Vec<Node> = vec![Node {
+        name: String::new(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+        text: String::new(),
+    }];
+    loop {
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(e) => {
+                return Err(SoupSourceParseError {
+                    message: format!("Invalid XML structure {}", e),
+                });
+            }
+        };
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                let attributes = e
+                    .attributes()
+                    .filter_map(|attribute| attribute.ok())
+                    .filter_map(|attribute| {
+                        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref())
+                            .to_string();
+                        let value = attribute.normalized_value(XmlVersion::Implicit1_0).ok()?;
+                        Some((key, value.to_string()))
+                    })
+                    .collect();
+                stack.push(Node {
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                    text: String::new(),
+                });
+            }
+            Event::End(_) if stack.len() > 1 => {
+                let node = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(node);
+            }
+            Event::Text(e) => {
+                if let (Some(node), Ok(text)) = (stack.last_mut(), e.xml10_content()) {
+                    node.text.push_str(&text);
+                }
+            }
+            Event::CData(e) => {
+                if let (Some(node), Ok(text)) = (stack.last_mut(), e.decode()) {
+                    node.text.push_str(&text);
+                }
+            }
+            Event::GeneralRef(e) => {
+                let text = match e.resolve_char_ref() {
+                    Ok(Some(c)) => Some(c.to_string()),
+                    _ => e
+                        .decode()
+                        .ok()
+                        .and_then(|name| resolve_predefined_entity(&name))
+                        .map(|text| text.to_owned()),
+                };
+                if let (Some(node), Some(text)) = (stack.last_mut(), text) {
+                    node.text.push_str(&text);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(stack.swap_remove(0))
+}
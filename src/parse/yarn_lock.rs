@@ -0,0 +1,277 @@
+use super::npm_lock::{LockedPackage, NpmLock};
+use crate::soup::model::SoupSourceParseError;
+use std::collections::HashMap;
+
+const DEPENDENCY_SECTIONS: [&str; 2] = ["dependencies", "optionalDependencies"];
+
+#[derive(Default)]
+struct Entry {
+    descriptors: Vec<String>,
+    version: Option<String>,
+    integrity: Option<String>,
+    checksum: Option<String>,
+    link_type: Option<String>,
+    dependencies: Vec<(String, String)>,
+}
+
+/// Parses a yarn.lock, either in the format of yarn classic or the YAML subset written by yarn
+/// berry, where packages are keyed by the descriptors, e.g. `some-lib@^1.0.0`, that resolve to
+/// them
+pub fn parse(content: &str) -> Result<NpmLock, SoupSourceParseError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut section: Option<String> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indentation = line.len() - trimmed.len();
+        if indentation == 0 {
+            let descriptors = trimmed
+                .trim_end_matches(':')
+                .split(',')
+                .map(|descriptor| descriptor.trim().trim_matches('"').to_owned())
+                .collect();
+            entries.push(Entry {
+                descriptors,
+                ..Default::default()
+            });
+            section = None;
+            continue;
+        }
+        let entry = match entries.last_mut() {
+            Some(entry) => entry,
+            None => {
+                return Err(SoupSourceParseError {
+                    message: format!(
+                        "Invalid yarn.lock (unexpected indentation on line {})",
+                        index + 1
+                    ),
+                });
+            }
+        };
+        let (key, value) = key_value(trimmed);
+        match (indentation, value) {
+            (2, None) => section = Some(key),
+            (2, Some(value)) => {
+                section = None;
+                match key.as_str() {
+                    "version" => entry.version = Some(value),
+                    "integrity" => entry.integrity = Some(value),
+                    "checksum" => entry.checksum = Some(value),
+                    "linkType" => entry.link_type = Some(value),
+                    _ => {}
+                }
+            }
+            (_, Some(value)) => {
+                if let Some(section) = &section {
+                    if DEPENDENCY_SECTIONS.contains(&section.as_str()) {
+                        entry.dependencies.push((key, value));
+                    }
+                }
+            }
+            (_, None) => {}
+        }
+    }
+
+    // Workspaces and linked directories are soft links to sources within the repository
+    let entries = entries
+        .into_iter()
+        .filter(|entry| entry.link_type.as_deref() != Some("soft"))
+        .filter_map(|entry| {
+            let key = entry.descriptors.first()?.to_owned();
+            let name = descriptor_name(&key)?.to_owned();
+            entry
+                .version
+                .to_owned()
+                .map(|version| (key, name, version, entry))
+        })
+        .collect::<Vec<_>>();
+    let descriptors = entries
+        .iter()
+        .flat_map(|(key, _, _, entry)| {
+            entry
+                .descriptors
+                .iter()
+                .map(move |descriptor| (descriptor.to_owned(), key.to_owned()))
+        })
+        .collect::<HashMap<String, String>>();
+    let packages = entries
+        .into_iter()
+        .map(|(key, name, version, entry)| {
+            let mut dependencies = entry
+                .dependencies
+                .iter()
+                .filter_map(|(name, range)| {
+                    descriptors
+                        .get(&format!("{}@{}", name, range))
+                        .or_else(|| descriptors.get(&format!("{}@npm:{}", name, range)))
+                        .cloned()
+                })
+                .collect::<Vec<_>>();
+            dependencies.sort();
+            let package = LockedPackage {
+                name,
+                version,
+                integrity: entry.integrity,
+                checksum: entry.checksum,
+                dependencies,
+            };
+            (key, package)
+        })
+        .collect();
+    Ok(NpmLock {
+        packages,
+        descriptors,
+        ..Default::default()
+    })
+}
+
+/// Splits lines such as `version "1.0.0"`, `version: 1.0.0` and `"@scope/lib" "^1.0.0"`
+fn key_value(line: &str) -> (String, Option<String>) {
+    let (key, rest) = match line.strip_prefix('"') {
+        Some(quoted) => match quoted.find('"') {
+            Some(end) => (&quoted[..end], &quoted[end + 1..]),
+            None => (quoted, ""),
+        },
+        None => match line.find([':', ' ']) {
+            Some(end) => (&line[..end], &line[end..]),
+            None => (line, ""),
+        },
+    };
+    let value = rest.trim_start_matches(':').trim().trim_matches('"');
+    match value.is_empty() {
+        true => (key.to_owned(), None),
+        false => (key.to_owned(), Some(value.to_owned())),
+    }
+}
+
+/// Name of the package in a descriptor, e.g. `@scope/lib` for `@scope/lib@npm:^1.0.0`
+fn descriptor_name(descriptor: &str) -> Option<&str> {
+    descriptor
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '@')
+        .map(|(index, _)| &descriptor[..index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const CLASSIC: &str = r#"
+# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@scope/lib@^2.1.0", "@scope/lib@~2.1.0":
+  version "2.1.4"
+  resolved "https://registry.yarnpkg.com/@scope/lib/-/lib-2.1.4.tgz#abc"
+  integrity sha512-jkl
+  dependencies:
+    another-lib "^2.0.0"
+
+another-lib@^2.0.0:
+  version "2.0.0"
+  resolved "https://registry.yarnpkg.com/another-lib/-/another-lib-2.0.0.tgz#def"
+  integrity sha512-ghi
+
+some-lib@^1.0.0:
+  version "1.2.3"
+  resolved "https://registry.yarnpkg.com/some-lib/-/some-lib-1.2.3.tgz#ghi"
+  integrity sha512-abc
+  dependencies:
+    "@scope/lib" "^2.1.0"
+"#;
+
+    const BERRY: &str = r#"
+# This file is generated by running "yarn install" inside your project.
+# Manual changes might be lost - proceed with caution!
+
+__metadata:
+  version: 8
+  cacheKey: 10c0
+
+"@scope/lib@npm:^2.1.0, @scope/lib@npm:~2.1.0":
+  version: 2.1.4
+  resolution: "@scope/lib@npm:2.1.4"
+  dependencies:
+    another-lib: "npm:^2.0.0"
+  checksum: 10c0/jkl
+  languageName: node
+  linkType: hard
+
+"another-lib@npm:^2.0.0":
+  version: 2.0.0
+  resolution: "another-lib@npm:2.0.0"
+  checksum: 10c0/ghi
+  languageName: node
+  linkType: hard
+
+"my-app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "my-app@workspace:."
+  dependencies:
+    some-lib: "npm:^1.0.0"
+  languageName: unknown
+  linkType: soft
+
+"some-lib@npm:^1.0.0":
+  version: 1.2.3
+  resolution: "some-lib@npm:1.2.3"
+  dependencies:
+    "@scope/lib": "npm:^2.1.0"
+  checksum: 10c0/abc
+  languageName: node
+  linkType: hard
+"#;
+
+    #[test_case(CLASSIC ; "classic")]
+    #[test_case(BERRY ; "berry")]
+    fn resolved_versions(input: &str) {
+        let lock = parse(input).unwrap();
+        assert_eq!(3, lock.packages.len());
+        let some_lib = lock.resolve("some-lib", "^1.0.0").unwrap();
+        assert_eq!("1.2.3", some_lib.version);
+        let scoped_lib = lock.resolve("@scope/lib", "~2.1.0").unwrap();
+        assert_eq!("@scope/lib", scoped_lib.name);
+        assert_eq!("2.1.4", scoped_lib.version);
+        assert_eq!(true, lock.resolve("some-lib", "^2.0.0").is_none());
+    }
+
+    #[test]
+    fn integrity_and_checksum() {
+        let classic = parse(CLASSIC).unwrap();
+        let some_lib = classic.resolve("some-lib", "^1.0.0").unwrap();
+        assert_eq!(Some("sha512-abc".to_owned()), some_lib.integrity);
+        let berry = parse(BERRY).unwrap();
+        let some_lib = berry.resolve("some-lib", "^1.0.0").unwrap();
+        assert_eq!(Some("10c0/abc".to_owned()), some_lib.checksum);
+    }
+
+    #[test_case(CLASSIC ; "classic")]
+    #[test_case(BERRY ; "berry")]
+    fn transitive_packages(input: &str) {
+        let lock = parse(input).unwrap();
+        let direct = vec![(lock.resolve("some-lib", "^1.0.0").unwrap(), "runtime")];
+        let transitive = lock
+            .transitive(&direct)
+            .into_iter()
+            .map(|transitive| (transitive.package.name.as_str(), transitive.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("@scope/lib", vec!["some-lib"]),
+                ("another-lib", vec!["some-lib", "@scope/lib"]),
+            ],
+            transitive
+        );
+    }
+
+    #[test]
+    fn invalid_lockfile() {
+        assert_eq!(true, parse("  version \"1.0.0\"").is_err());
+    }
+}
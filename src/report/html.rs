@@ -0,0 +1,136 @@
+use crate::report::{Report, Table};
+use std::fmt::Write;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #999; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th { background-color: #eee; }";
+
+pub fn render(report: &Report) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html>")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>SOUP report</title>")?;
+    writeln!(out, "<style>\n{}\n</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>SOUP report</h1>")?;
+    writeln!(out, "<h2>Summary</h2>")?;
+    writeln!(out, "<p>Number of SOUPs: {}</p>", report.summary.total)?;
+    write_table(
+        &mut out,
+        &Table {
+            title: String::new(),
+            columns: vec!["Ecosystem".to_owned(), "SOUPs".to_owned()],
+            rows: report
+                .summary
+                .ecosystems
+                .iter()
+                .map(|(ecosystem, count)| vec![ecosystem.to_owned(), count.to_string()])
+                .collect(),
+        },
+    )?;
+    writeln!(out, "<h3>SOUPs with empty meta properties</h3>")?;
+    match report.summary.incomplete.is_empty() {
+        true => writeln!(out, "<p>None</p>")?,
+        false => {
+            writeln!(out, "<ul>")?;
+            for soup in &report.summary.incomplete {
+                writeln!(
+                    out,
+                    "<li>{} {}: {}</li>",
+                    escape(&soup.name),
+                    escape(&soup.version),
+                    escape(&soup.empty_keys.join(", "))
+                )?;
+            }
+            writeln!(out, "</ul>")?;
+        }
+    }
+    for table in &report.tables {
+        writeln!(out, "<h2>{}</h2>", escape(&table.title))?;
+        write_table(&mut out, table)?;
+    }
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(out)
+}
+
+fn write_table(out: &mut String, table: &Table) -> std::fmt::Result {
+    writeln!(out, "<table>")?;
+    write!(out, "<tr>")?;
+    for column in &table.columns {
+        write!(out, "<th>{}</th>", escape(column))?;
+    }
+    writeln!(out, "</tr>")?;
+    for row in &table.rows {
+        write!(out, "<tr>")?;
+        for cell in row {
+            write!(out, "<td>{}</td>", escape(cell))?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</table>")?;
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ReportOptions;
+    use crate::report::tests::create_contexts;
+
+    #[test]
+    fn html() {
+        let options = ReportOptions {
+            columns: None,
+            deduplicate: true,
+            excluded_scopes: Vec::new(),
+        };
+        let report = Report::new(&create_contexts(), &options);
+        let result = render(&report);
+        assert_eq!(true, result.is_ok());
+        let html = result.unwrap();
+        assert_eq!(true, html.starts_with("<!DOCTYPE html>\n<html>\n"));
+        assert_eq!(
+            true,
+            html.contains("<tr><td>docker</td><td>1</td></tr>\n<tr><td>npm</td><td>1</td></tr>")
+        );
+        assert_eq!(
+            true,
+            html.contains("<li>postgres 14.4: requirements, manufacturer</li>")
+        );
+        assert_eq!(
+            true,
+            html.contains(
+                "<tr><th>Name</th><th>Version</th><th>rationale</th><th>requirements</th><th>manufacturer</th><th>Found in</th></tr>"
+            )
+        );
+        assert_eq!(
+            true,
+            html.contains(
+                "<tr><td>postgres</td><td>14.4</td><td>Store data</td><td></td><td></td><td>src/Dockerfile, test/Dockerfile</td></tr>"
+            )
+        );
+        assert_eq!(true, html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            "&lt;script&gt;&quot;a&quot; &amp; &#39;b&#39;&lt;/script&gt;",
+            escape("<script>\"a\" & 'b'</script>")
+        );
+    }
+}
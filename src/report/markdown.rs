@@ -0,0 +1,124 @@
+use crate::report::{Report, Table};
+use std::fmt::Write;
+
+pub fn render(report: &Report) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "# SOUP report")?;
+    writeln!(out)?;
+    writeln!(out, "## Summary")?;
+    writeln!(out)?;
+    writeln!(out, "Number of SOUPs: {}", report.summary.total)?;
+    writeln!(out)?;
+    write_table(
+        &mut out,
+        &Table {
+            title: String::new(),
+            columns: vec!["Ecosystem".to_owned(), "SOUPs".to_owned()],
+            rows: report
+                .summary
+                .ecosystems
+                .iter()
+                .map(|(ecosystem, count)| vec![ecosystem.to_owned(), count.to_string()])
+                .collect(),
+        },
+    )?;
+    writeln!(out)?;
+    writeln!(out, "### SOUPs with empty meta properties")?;
+    writeln!(out)?;
+    if report.summary.incomplete.is_empty() {
+        writeln!(out, "None")?;
+    }
+    for soup in &report.summary.incomplete {
+        writeln!(
+            out,
+            "- {} {}: {}",
+            escape(&soup.name),
+            escape(&soup.version),
+            escape(&soup.empty_keys.join(", "))
+        )?;
+    }
+    for table in &report.tables {
+        writeln!(out)?;
+        writeln!(out, "## {}", escape(&table.title))?;
+        writeln!(out)?;
+        write_table(&mut out, table)?;
+    }
+    Ok(out)
+}
+
+fn write_table(out: &mut String, table: &Table) -> std::fmt::Result {
+    let columns = table
+        .columns
+        .iter()
+        .map(|column| escape(column))
+        .collect::<Vec<String>>();
+    writeln!(out, "| {} |", columns.join(" | "))?;
+    writeln!(out, "|{}", " --- |".repeat(columns.len()))?;
+    for row in &table.rows {
+        let cells = row.iter().map(|cell| escape(cell)).collect::<Vec<String>>();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ReportOptions;
+    use crate::report::tests::create_contexts;
+
+    #[test]
+    fn markdown() {
+        let options = ReportOptions {
+            columns: Some(vec!["rationale".to_owned()]),
+            deduplicate: false,
+            excluded_scopes: Vec::new(),
+        };
+        let report = Report::new(&create_contexts(), &options);
+        let result = render(&report);
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            r#"# SOUP report
+
+## Summary
+
+Number of SOUPs: 2
+
+| Ecosystem | SOUPs |
+| --- | --- |
+| docker | 1 |
+| npm | 1 |
+
+### SOUPs with empty meta properties
+
+None
+
+## src/Dockerfile
+
+| Name | Version | rationale |
+| --- | --- | --- |
+| postgres | 14.4 | Store data |
+
+## src/package.json
+
+| Name | Version | rationale |
+| --- | --- | --- |
+| some-lib | 1.0.0 | Do this \| that |
+
+## test/Dockerfile
+
+| Name | Version | rationale |
+| --- | --- | --- |
+| postgres | 14.4 | Store data |
+"#,
+            result.unwrap()
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use crate::export::unique_soups;
+use crate::soup::model::{Soup, SoupContexts};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub mod html;
+pub mod markdown;
+
+const UNKNOWN_ECOSYSTEM: &str = "unknown";
+
+pub struct ReportOptions {
+    /// Meta keys to include as columns, defaults to all meta keys found
+    pub columns: Option<Vec<String>>,
+    /// Whether to list SOUPs found in several contexts once, instead of one table per context
+    pub deduplicate: bool,
+    /// Scopes of SOUPs to leave out, e.g. `dev` to exclude SOUPs only used for development
+    pub excluded_scopes: Vec<String>,
+}
+
+pub struct Report {
+    pub tables: Vec<Table>,
+    pub summary: Summary,
+}
+
+pub struct Table {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub struct Summary {
+    pub total: usize,
+    pub ecosystems: BTreeMap<String, usize>,
+    pub incomplete: Vec<IncompleteSoup>,
+}
+
+pub struct IncompleteSoup {
+    pub name: String,
+    pub version: String,
+    pub empty_keys: Vec<String>,
+}
+
+impl Report {
+    pub fn new(contexts: &SoupContexts, options: &ReportOptions) -> Report {
+        let contexts = &SoupContexts {
+            contexts: contexts
+                .contexts()
+                .iter()
+                .map(|(path, soups)| {
+                    let soups = soups
+                        .iter()
+                        .filter(|soup| match &soup.scope {
+                            Some(scope) => !options.excluded_scopes.contains(scope),
+                            None => true,
+                        })
+                        .cloned()
+                        .collect();
+                    (path.to_owned(), soups)
+                })
+                .collect(),
+        };
+        let meta_keys = match &options.columns {
+            Some(columns) => columns.to_owned(),
+            None => meta_keys(contexts),
+        };
+        let unique_soups = unique_soups(contexts);
+
+        let tables = match options.deduplicate {
+            true => {
+                let mut columns = vec!["Name".to_owned(), "Version".to_owned()];
+                columns.extend(meta_keys.iter().cloned());
+                columns.push("Found in".to_owned());
+                let rows = unique_soups
+                    .iter()
+                    .map(|(soup, paths)| {
+                        let mut row = row(soup, &meta_keys);
+                        let paths = paths.iter().map(|p| p.as_str()).collect::<Vec<&str>>();
+                        row.push(paths.join(", "));
+                        row
+                    })
+                    .collect();
+                vec![Table {
+                    title: "SOUPs".to_owned(),
+                    columns,
+                    rows,
+                }]
+            }
+            false => contexts
+                .contexts()
+                .iter()
+                .filter(|(_, soups)| !soups.is_empty())
+                .map(|(path, soups)| {
+                    let mut columns = vec!["Name".to_owned(), "Version".to_owned()];
+                    columns.extend(meta_keys.iter().cloned());
+                    Table {
+                        title: path.to_owned(),
+                        columns,
+                        rows: soups.iter().map(|soup| row(soup, &meta_keys)).collect(),
+                    }
+                })
+                .collect(),
+        };
+
+        let mut ecosystems = BTreeMap::new();
+        for (soup, _) in &unique_soups {
+            let ecosystem = match soup.ecosystem {
+                Some(ecosystem) => ecosystem.to_string(),
+                None => UNKNOWN_ECOSYSTEM.to_owned(),
+            };
+            *ecosystems.entry(ecosystem).or_insert(0) += 1;
+        }
+        let incomplete = unique_soups
+            .iter()
+            .filter_map(|(soup, _)| {
+                let empty_keys = meta_keys
+                    .iter()
+                    .filter(|key| meta_value(soup, key).is_empty())
+                    .cloned()
+                    .collect::<Vec<String>>();
+                match empty_keys.is_empty() {
+                    true => None,
+                    false => Some(IncompleteSoup {
+                        name: soup.name.to_owned(),
+                        version: soup.version.to_owned(),
+                        empty_keys,
+                    }),
+                }
+            })
+            .collect();
+
+        Report {
+            tables,
+            summary: Summary {
+                total: unique_soups.len(),
+                ecosystems,
+                incomplete,
+            },
+        }
+    }
+}
+
+fn meta_keys(contexts: &SoupContexts) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for soup in contexts.contexts().values().flatten() {
+        for key in soup.meta.keys() {
+            if !keys.contains(key) {
+                keys.push(key.to_owned());
+            }
+        }
+    }
+    keys
+}
+
+fn row(soup: &Soup, meta_keys: &[String]) -> Vec<String> {
+    let mut row = vec![soup.name.to_owned(), soup.version.to_owned()];
+    row.extend(meta_keys.iter().map(|key| meta_value(soup, key)));
+    row
+}
+
+fn meta_value(soup: &Soup, key: &str) -> String {
+    match soup.meta.get(key) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(value)) => value.trim().to_owned(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup::model::Ecosystem;
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    pub fn create_contexts() -> SoupContexts {
+        let postgres = Soup {
+            name: "postgres".to_owned(),
+            version: "14.4".to_owned(),
+            ecosystem: Some(Ecosystem::Docker),
+            meta: json!({"rationale": "Store data", "requirements": ""})
+                .as_object()
+                .unwrap()
+                .clone(),
+            ..Default::default()
+        };
+        let some_lib = Soup {
+            name: "some-lib".to_owned(),
+            version: "1.0.0".to_owned(),
+            ecosystem: Some(Ecosystem::Npm),
+            meta: json!({"rationale": "Do this | that", "manufacturer": "Acme"})
+                .as_object()
+                .unwrap()
+                .clone(),
+            ..Default::default()
+        };
+        SoupContexts {
+            contexts: vec![
+                (
+                    "src/Dockerfile".to_owned(),
+                    vec![postgres.clone()]
+                        .into_iter()
+                        .collect::<BTreeSet<Soup>>(),
+                ),
+                (
+                    "src/package.json".to_owned(),
+                    vec![some_lib].into_iter().collect::<BTreeSet<Soup>>(),
+                ),
+                (
+                    "test/Dockerfile".to_owned(),
+                    vec![postgres].into_iter().collect::<BTreeSet<Soup>>(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn table_per_context() {
+        let options = ReportOptions {
+            columns: None,
+            deduplicate: false,
+            excluded_scopes: Vec::new(),
+        };
+        let report = Report::new(&create_contexts(), &options);
+        assert_eq!(3, report.tables.len());
+        let table = &report.tables[1];
+        assert_eq!("src/package.json", table.title);
+        assert_eq!(
+            vec![
+                "Name",
+                "Version",
+                "rationale",
+                "requirements",
+                "manufacturer"
+            ],
+            table.columns
+        );
+        assert_eq!(
+            vec![vec!["some-lib", "1.0.0", "Do this | that", "", "Acme"]],
+            table.rows
+        );
+    }
+
+    #[test]
+    fn deduplicated_table() {
+        let options = ReportOptions {
+            columns: Some(vec!["rationale".to_owned()]),
+            deduplicate: true,
+            excluded_scopes: Vec::new(),
+        };
+        let report = Report::new(&create_contexts(), &options);
+        assert_eq!(1, report.tables.len());
+        let table = &report.tables[0];
+        assert_eq!(
+            vec!["Name", "Version", "rationale", "Found in"],
+            table.columns
+        );
+        assert_eq!(
+            vec![
+                vec!["some-lib", "1.0.0", "Do this | that", "src/package.json"],
+                vec![
+                    "postgres",
+                    "14.4",
+                    "Store data",
+                    "src/Dockerfile, test/Dockerfile"
+                ],
+            ],
+            table.rows
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let options = ReportOptions {
+            columns: None,
+            deduplicate: false,
+            excluded_scopes: Vec::new(),
+        };
+        let report = Report::new(&create_contexts(), &options);
+        assert_eq!(2, report.summary.total);
+        assert_eq!(
+            vec![("docker".to_owned(), 1), ("npm".to_owned(), 1)],
+            report.summary.ecosystems.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(2, report.summary.incomplete.len());
+        assert_eq!("some-lib", report.summary.incomplete[0].name);
+        assert_eq!(
+            vec!["requirements"],
+            report.summary.incomplete[0].empty_keys
+        );
+        assert_eq!("postgres", report.summary.incomplete[1].name);
+        assert_eq!(
+            vec!["requirements", "manufacturer"],
+            report.summary.incomplete[1].empty_keys
+        );
+    }
+
+    #[test]
+    fn excluded_scopes() {
+        let mut contexts = create_contexts();
+        contexts
+            .contexts
+            .get_mut("src/package.json")
+            .unwrap()
+            .insert(Soup {
+                name: "some-test-lib".to_owned(),
+                version: "2.0.0".to_owned(),
+                ecosystem: Some(Ecosystem::Npm),
+                scope: Some("dev".to_owned()),
+                ..Default::default()
+            });
+        let options = ReportOptions {
+            columns: None,
+            deduplicate: false,
+            excluded_scopes: vec!["dev".to_owned()],
+        };
+        let report = Report::new(&contexts, &options);
+        assert_eq!(2, report.summary.total);
+        assert_eq!(1, report.tables[1].rows.len());
+        assert_eq!("some-lib", report.tables[1].rows[0][0]);
+    }
+}
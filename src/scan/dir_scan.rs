@@ -1,29 +1,57 @@
 use crate::{
     parse::{
-        SoupParse, apt::Apt, cargo::Cargo, csproj::CsProj, docker_base::DockerBase,
-        package_json::PackageJson,
+        SoupParse, apk::Apk, apt::Apt, cargo, cargo::Cargo, cmake::CMake, composer::Composer,
+        conan::Conan, conan::ConanFormat, csproj::CsProj, docker_base::DockerBase, docker_compose,
+        docker_compose::DockerCompose, docker_download::DockerDownload, gem::Gem, gemfile::Gemfile,
+        git_modules::GitModules, github_actions::GitHubActions, gitlab_ci::GitLabCi, go_mod::GoMod,
+        gradle::Gradle, helm::HelmChart, kubernetes, kubernetes::Kubernetes, maven::Pom, mix::Mix,
+        npm_global::NpmGlobal, npm_lock::NpmLockfile, nuget_lock::NuGetLock, package_json,
+        package_json::PackageJson, packages_config::PackagesConfig, pip::Pip, pipfile::Pipfile,
+        pubspec::Pubspec, pyproject::PyProject, python_lock::PythonLockfile,
+        requirements::Requirements, rpm::Rpm, terraform::Terraform, vcpkg::Vcpkg, vendored,
+        vendored::VendoredLibrary,
     },
+    scan::git,
     soup::model::{Soup, SoupContexts, SouperIoError},
     utils,
 };
 use serde_json::{Map, Value};
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs,
-    io::Error,
+    io::{Error, ErrorKind},
     path::{Path, PathBuf},
 };
 
-const GLOBAL_EXCLUDE_DIRS: [&str; 3] = ["node_modules", "bin", "obj"];
+const GLOBAL_EXCLUDE_DIRS: [&str; 5] = [".git", "node_modules", "bin", "obj", ".terraform"];
+/// Directories that package managers install dependencies into, along with the manifest next to
+/// them that they are installed for
+const INSTALL_DIRS: [(&str, &str); 4] = [
+    ("vendor", "composer.json"),
+    ("vendor/bundle", "Gemfile"),
+    ("deps", "mix.exs"),
+    ("_build", "mix.exs"),
+];
 
 pub type SoupParsers = Vec<Box<dyn SoupParse>>;
 
+#[derive(Default)]
+pub struct ScanOptions {
+    /// Whether to include transitive dependencies resolved in lock files
+    pub include_transitive: bool,
+    /// Values of Dockerfile build arguments, as given to `docker build --build-arg`
+    pub build_args: HashMap<String, String>,
+    /// Names of directories that libraries are vendored into, one directory per library
+    pub vendor_dirs: Vec<String>,
+}
+
 pub fn scan(
     dir: &PathBuf,
     exclude_dirs: &Vec<PathBuf>,
     default_meta: Map<String, Value>,
+    options: &ScanOptions,
 ) -> Result<SoupContexts, SouperIoError> {
-    let path_parsers = match scan_dirs_recursively(dir, exclude_dirs) {
+    let path_parsers = match scan_dirs_recursively(dir, dir, exclude_dirs, options) {
         Ok(path_parsers) => path_parsers,
         Err(e) => {
             return Err(SouperIoError {
@@ -34,7 +62,13 @@ pub fn scan(
     let contexts = path_parsers
         .into_iter()
         .map(|(path, parsers)| {
-            let file_content = match fs::read_to_string(&path) {
+            // Vendored libraries are directories, parsed from the files found in them while
+            // scanning
+            let file_content = match path.is_dir() {
+                true => Ok(String::new()),
+                false => fs::read_to_string(&path),
+            };
+            let file_content = match file_content {
                 Ok(content) => content,
                 Err(e) => {
                     return Err(SouperIoError {
@@ -62,11 +96,13 @@ pub fn scan(
 }
 
 fn scan_dirs_recursively(
+    dir: &PathBuf,
     root: &PathBuf,
     exclude_dirs: &Vec<PathBuf>,
+    options: &ScanOptions,
 ) -> Result<Vec<(PathBuf, SoupParsers)>, Error> {
     let mut sources: Vec<(PathBuf, Vec<Box<dyn SoupParse>>)> = Vec::new();
-    'entries: for entry in fs::read_dir(root)? {
+    'entries: for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
         let file_type = entry.file_type()?;
@@ -82,23 +118,207 @@ fn scan_dirs_recursively(
                     continue 'entries;
                 }
             }
-            let mut content = scan_dirs_recursively(&path, exclude_dirs)?;
+            if is_install_dir(&path) {
+                continue;
+            }
+            if options
+                .vendor_dirs
+                .iter()
+                .any(|name| file_name == name.as_str())
+            {
+                sources.append(&mut vendored_libraries(&path)?);
+            }
+            let mut content = scan_dirs_recursively(&path, root, exclude_dirs, options)?;
             sources.append(&mut content);
             continue;
         }
         if file_type.is_file() {
             match file_name.to_str() {
                 Some("package.json") => {
-                    sources.push((path, vec![Box::new(PackageJson {})]));
+                    let package_json = package_json_parser(&path, root, options)?;
+                    sources.push((path, vec![Box::new(package_json)]));
                 }
                 Some("Cargo.toml") => {
-                    sources.push((path, vec![Box::new(Cargo {})]));
+                    let workspace_manifest =
+                        find_upwards(&path, root, "Cargo.toml", cargo::is_workspace_manifest)?
+                            .map(|(_, content)| content);
+                    let lockfile = find_upwards(&path, root, "Cargo.lock", |_| true)?
+                        .map(|(_, content)| content);
+                    sources.push((
+                        path,
+                        vec![Box::new(Cargo {
+                            workspace_manifest,
+                            lockfile,
+                            include_transitive: options.include_transitive,
+                        })],
+                    ));
+                }
+                Some("go.mod") => {
+                    let go_sum = path.with_file_name("go.sum");
+                    let go_sum = match go_sum.is_file() {
+                        true => Some(fs::read_to_string(go_sum)?),
+                        false => None,
+                    };
+                    sources.push((path, vec![Box::new(GoMod { go_sum })]));
+                }
+                Some("pom.xml") => {
+                    sources.push((path.to_owned(), vec![Box::new(Pom { path: Some(path) })]));
+                }
+                Some("build.gradle") | Some("build.gradle.kts") => {
+                    let properties = find_upwards(&path, root, "gradle.properties", |_| true)?
+                        .map(|(_, content)| content);
+                    let version_catalog =
+                        find_upwards(&path, root, "gradle/libs.versions.toml", |_| true)?
+                            .map(|(_, content)| content);
+                    sources.push((
+                        path,
+                        vec![Box::new(Gradle {
+                            properties,
+                            version_catalog,
+                        })],
+                    ));
+                }
+                Some("packages.config") => {
+                    sources.push((path, vec![Box::new(PackagesConfig {})]));
+                }
+                Some("packages.lock.json") => {
+                    let nuget_lock = NuGetLock {
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(nuget_lock)]));
+                }
+                Some("pyproject.toml") => {
+                    let pyproject = PyProject {
+                        lockfile: python_lockfile(&path, root)?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(pyproject)]));
+                }
+                Some("Pipfile") => {
+                    let lockfile = path.with_file_name("Pipfile.lock");
+                    let lockfile = match lockfile.is_file() {
+                        true => Some(fs::read_to_string(lockfile)?),
+                        false => None,
+                    };
+                    let pipfile = Pipfile {
+                        lockfile,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(pipfile)]));
+                }
+                Some(file_name_str)
+                    if file_name_str.starts_with("requirements")
+                        && file_name_str.ends_with(".txt") =>
+                {
+                    sources.push((
+                        path.to_owned(),
+                        vec![Box::new(Requirements { path: Some(path) })],
+                    ));
                 }
                 Some(file_name_str) if file_name_str.contains(".csproj") => {
-                    sources.push((path, vec![Box::new(CsProj {})]));
+                    let mut implicit_imports = Vec::new();
+                    for file_name in ["Directory.Build.props", "Directory.Packages.props"] {
+                        if let Some((import, _)) = find_upwards(&path, root, file_name, |_| true)? {
+                            implicit_imports.push(import);
+                        }
+                    }
+                    sources.push((
+                        path.to_owned(),
+                        vec![Box::new(CsProj {
+                            path: Some(path),
+                            root: Some(root.to_owned()),
+                            implicit_imports,
+                        })],
+                    ));
                 }
                 Some(file_name_str) if file_name_str.contains("Dockerfile") => {
-                    sources.push((path, vec![Box::new(DockerBase {}), Box::new(Apt {})]));
+                    sources.push((path, dockerfile_parsers(options)));
+                }
+                Some("Chart.yaml") => {
+                    let lockfile = read_sibling(&path, "Chart.lock")?;
+                    sources.push((path, vec![Box::new(HelmChart { lockfile })]));
+                }
+                // Charts of API version v1 declare their dependencies in a separate file
+                Some("requirements.yaml") if path.with_file_name("Chart.yaml").is_file() => {
+                    let lockfile = read_sibling(&path, "requirements.lock")?;
+                    sources.push((path, vec![Box::new(HelmChart { lockfile })]));
+                }
+                Some(file_name_str) if is_workflow(&path, file_name_str) => {
+                    sources.push((path, vec![Box::new(GitHubActions {})]));
+                }
+                Some("action.yml") | Some("action.yaml") => {
+                    sources.push((path, vec![Box::new(GitHubActions {})]));
+                }
+                Some(".gitmodules") => {
+                    let commits = git::submodule_commits(dir)?;
+                    sources.push((path, vec![Box::new(GitModules { commits })]));
+                }
+                Some("vcpkg.json") => {
+                    sources.push((path, vec![Box::new(Vcpkg {})]));
+                }
+                Some("conanfile.txt") | Some("conanfile.py") => {
+                    let format = match file_name.to_str() {
+                        Some("conanfile.py") => ConanFormat::Recipe,
+                        _ => ConanFormat::Text,
+                    };
+                    let conan = Conan {
+                        format,
+                        lockfile: read_sibling(&path, "conan.lock")?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(conan)]));
+                }
+                Some(file_name_str)
+                    if file_name_str == "CMakeLists.txt" || file_name_str.ends_with(".cmake") =>
+                {
+                    sources.push((path, vec![Box::new(CMake {})]));
+                }
+                Some("Gemfile") => {
+                    let gemfile = Gemfile {
+                        lockfile: read_sibling(&path, "Gemfile.lock")?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(gemfile)]));
+                }
+                Some("composer.json") => {
+                    let composer = Composer {
+                        lockfile: read_sibling(&path, "composer.lock")?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(composer)]));
+                }
+                Some("pubspec.yaml") => {
+                    let pubspec = Pubspec {
+                        lockfile: read_sibling(&path, "pubspec.lock")?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(pubspec)]));
+                }
+                Some("mix.exs") => {
+                    let mix = Mix {
+                        lockfile: read_sibling(&path, "mix.lock")?,
+                        include_transitive: options.include_transitive,
+                    };
+                    sources.push((path, vec![Box::new(mix)]));
+                }
+                Some(file_name_str) if file_name_str.ends_with(".tf") => {
+                    let lockfile = read_sibling(&path, ".terraform.lock.hcl")?;
+                    sources.push((path, vec![Box::new(Terraform { lockfile })]));
+                }
+                Some(".gitlab-ci.yml") => {
+                    sources.push((path, vec![Box::new(GitLabCi {})]));
+                }
+                Some(file_name_str) if docker_compose::is_compose_file(file_name_str) => {
+                    for dockerfile in compose_dockerfiles(&path, root)? {
+                        sources.push((dockerfile, dockerfile_parsers(options)));
+                    }
+                    sources.push((path, vec![Box::new(DockerCompose {})]));
+                }
+                Some(file_name_str)
+                    if (file_name_str.ends_with(".yaml") || file_name_str.ends_with(".yml"))
+                        && is_kubernetes_manifest(&path)? =>
+                {
+                    sources.push((path, vec![Box::new(Kubernetes {})]));
                 }
                 _ => {}
             }
@@ -107,6 +327,309 @@ fn scan_dirs_recursively(
     Ok(sources)
 }
 
+fn is_install_dir(path: &Path) -> bool {
+    INSTALL_DIRS.iter().any(|(install_dir, manifest)| {
+        let depth = Path::new(install_dir).components().count();
+        path.ends_with(install_dir)
+            && path
+                .ancestors()
+                .nth(depth)
+                .is_some_and(|dir| dir.join(manifest).is_file())
+    })
+}
+
+/// Whether a YAML file is a Kubernetes manifest. Files that aren't UTF-8 encoded are none, nor
+/// are they YAML at all.
+fn is_kubernetes_manifest(path: &Path) -> Result<bool, Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(kubernetes::is_manifest(&content)),
+        Err(e) if e.kind() == ErrorKind::InvalidData => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Content of the file with the given name next to `path`, if any
+fn read_sibling(path: &Path, file_name: &str) -> Result<Option<String>, Error> {
+    let sibling = path.with_file_name(file_name);
+    match sibling.is_file() {
+        true => Ok(Some(fs::read_to_string(sibling)?)),
+        false => Ok(None),
+    }
+}
+
+fn dockerfile_parsers(options: &ScanOptions) -> SoupParsers {
+    vec![
+        Box::new(DockerBase {
+            build_args: options.build_args.clone(),
+        }),
+        Box::new(DockerDownload {
+            build_args: options.build_args.clone(),
+        }),
+        Box::new(Apt {}),
+        Box::new(Apk {}),
+        Box::new(Rpm {}),
+        Box::new(Pip {}),
+        Box::new(NpmGlobal {}),
+        Box::new(Gem {}),
+    ]
+}
+
+/// Whether a file is a GitHub Actions workflow, i.e. a YAML file in `.github/workflows`
+fn is_workflow(path: &Path, file_name: &str) -> bool {
+    (file_name.ends_with(".yml") || file_name.ends_with(".yaml"))
+        && path
+            .parent()
+            .is_some_and(|dir| dir.ends_with(".github/workflows"))
+}
+
+/// Libraries vendored into a directory, except for submodules, which `.gitmodules` covers, and
+/// packages that Go modules or Composer vendor, which their lock files cover
+fn vendored_libraries(dir: &Path) -> Result<Vec<(PathBuf, SoupParsers)>, Error> {
+    if dir.join("modules.txt").is_file() || dir.join("autoload.php").is_file() {
+        return Ok(Vec::new());
+    }
+    let mut libraries: Vec<(PathBuf, SoupParsers)> = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !entry.file_type()?.is_dir()
+            || name.starts_with('.')
+            || path.join(".git").exists()
+            || is_install_dir(&path)
+        {
+            continue;
+        }
+        let hints = version_hints(&path)?;
+        libraries.push((path, vec![Box::new(VendoredLibrary { name, hints })]));
+    }
+    Ok(libraries)
+}
+
+/// Files of a vendored library that may hint at its version, in order of precedence: version
+/// files and CMakeLists.txt at its root, then version headers at most three directories deep
+fn version_hints(library: &Path) -> Result<Vec<(String, String)>, Error> {
+    let mut hints = Vec::new();
+    for file_name in vendored::VERSION_FILES.iter().chain(&["CMakeLists.txt"]) {
+        let path = library.join(file_name);
+        if path.is_file() {
+            hints.push((file_name.to_string(), read_lossy(&path)?));
+        }
+    }
+    let mut headers = Vec::new();
+    let mut dirs = vec![(library.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_lowercase();
+            if entry.file_type()?.is_dir() {
+                if depth < 3 && !file_name.starts_with('.') {
+                    dirs.push((entry.path(), depth + 1));
+                }
+            } else if file_name.ends_with("version.h") || file_name.ends_with("version.hpp") {
+                headers.push(entry.path());
+            }
+        }
+    }
+    headers.sort();
+    for header in headers {
+        let content = read_lossy(&header)?;
+        let path = header.strip_prefix(library).unwrap_or(&header);
+        hints.push((path.to_string_lossy().replace('\\', "/"), content));
+    }
+    Ok(hints)
+}
+
+/// Content of a file of a vendored library, which need not be UTF-8 encoded, e.g. headers in
+/// Latin-1
+fn read_lossy(path: &Path) -> Result<String, Error> {
+    Ok(String::from_utf8_lossy(&fs::read(path)?).into_owned())
+}
+
+/// Dockerfiles within `root` that services of the given Compose file are built from, if not
+/// named like a Dockerfile and thereby found by the scan itself
+fn compose_dockerfiles(path: &Path, root: &Path) -> Result<Vec<PathBuf>, Error> {
+    let dockerfiles = match docker_compose::dockerfiles(&fs::read_to_string(path)?) {
+        Ok(dockerfiles) => dockerfiles,
+        // Invalid Compose files are reported when parsed for SOUPs
+        Err(_) => return Ok(Vec::new()),
+    };
+    let canonical_root = fs::canonicalize(root)?;
+    let mut paths = Vec::new();
+    for dockerfile in dockerfiles {
+        let dockerfile = path.with_file_name("").join(dockerfile);
+        let is_named_dockerfile = dockerfile
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .is_some_and(|file_name| file_name.contains("Dockerfile"));
+        if is_named_dockerfile || !dockerfile.is_file() {
+            continue;
+        }
+        if let Ok(relative) = fs::canonicalize(&dockerfile)?.strip_prefix(&canonical_root) {
+            paths.push(root.join(relative));
+        }
+    }
+    Ok(paths)
+}
+
+/// Path and content of the closest file with the given name that satisfies the predicate,
+/// searching the directory of `path` and its parent directories up to `root`.
+fn find_upwards<P>(
+    path: &Path,
+    root: &Path,
+    file_name: &str,
+    predicate: P,
+) -> Result<Option<(PathBuf, String)>, Error>
+where
+    P: Fn(&str) -> bool,
+{
+    let mut dir = path.parent();
+    while let Some(current_dir) = dir {
+        let candidate = current_dir.join(file_name);
+        if candidate.is_file() {
+            let content = fs::read_to_string(&candidate)?;
+            if predicate(&content) {
+                return Ok(Some((candidate, content)));
+            }
+        }
+        if current_dir == root {
+            break;
+        }
+        dir = current_dir.parent();
+    }
+    Ok(None)
+}
+
+/// Parser for the given package.json, with the lockfile next to it or at the root of the npm
+/// workspace that it belongs to
+fn package_json_parser(
+    path: &Path,
+    root: &Path,
+    options: &ScanOptions,
+) -> Result<PackageJson, Error> {
+    let dir = match path.parent() {
+        Some(dir) => dir,
+        None => return Ok(PackageJson::default()),
+    };
+    let mut workspace_dir = None;
+    let mut workspace_packages = BTreeSet::new();
+    let workspace = find_upwards(path, root, "package.json", |content| {
+        package_json::workspace_patterns(content).is_some()
+    })?;
+    if let Some((workspace_path, workspace_content)) = workspace {
+        let workspace_root = workspace_path.parent().unwrap_or(root);
+        let patterns = package_json::workspace_patterns(&workspace_content).unwrap_or_default();
+        let mut member_dirs = Vec::new();
+        for pattern in patterns {
+            let segments = pattern
+                .split('/')
+                .filter(|segment| !segment.is_empty() && *segment != ".")
+                .collect::<Vec<_>>();
+            member_dirs.append(&mut expand_pattern(workspace_root, &segments)?);
+        }
+        if workspace_root == dir || member_dirs.iter().any(|member_dir| member_dir == dir) {
+            for member_dir in member_dirs {
+                let member_path = member_dir.join("package.json");
+                if member_path.is_file() {
+                    let content = fs::read_to_string(member_path)?;
+                    workspace_packages.extend(package_json::package_name(&content));
+                }
+            }
+            workspace_dir = Some(workspace_root.to_path_buf());
+        }
+    }
+
+    let mut lockfile = npm_lockfile(dir)?;
+    let mut importer = String::new();
+    if let (None, Some(workspace_dir)) = (&lockfile, &workspace_dir) {
+        lockfile = npm_lockfile(workspace_dir)?;
+        importer = match utils::relative_path(dir, workspace_dir) {
+            Ok(relative_path) => relative_path.to_string_lossy().replace('\\', "/"),
+            Err(_e) => String::new(),
+        };
+    }
+    Ok(PackageJson {
+        lockfile,
+        importer,
+        include_transitive: options.include_transitive,
+        workspace_packages,
+    })
+}
+
+/// Directories matching the segments of a workspace pattern such as `packages/*` or `apps/**`
+fn expand_pattern(dir: &Path, segments: &[&str]) -> Result<Vec<PathBuf>, Error> {
+    let (segment, rest) = match segments.split_first() {
+        Some(first) => first,
+        None => return Ok(vec![dir.to_path_buf()]),
+    };
+    if segment.starts_with('!') {
+        return Ok(Vec::new());
+    }
+    if !segment.contains('*') {
+        let next = dir.join(segment);
+        return match next.is_dir() {
+            true => expand_pattern(&next, rest),
+            false => Ok(Vec::new()),
+        };
+    }
+    let mut dirs = match *segment == "**" {
+        true => expand_pattern(dir, rest)?,
+        false => Vec::new(),
+    };
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if !entry.file_type()?.is_dir() || GLOBAL_EXCLUDE_DIRS.contains(&file_name.as_ref()) {
+            continue;
+        }
+        match *segment == "**" {
+            true => dirs.append(&mut expand_pattern(&entry.path(), segments)?),
+            false if utils::wildcard_match(segment, &file_name) => {
+                dirs.append(&mut expand_pattern(&entry.path(), rest)?)
+            }
+            false => {}
+        }
+    }
+    Ok(dirs)
+}
+
+/// Lockfile in the given directory, preferring npm over yarn and pnpm
+fn npm_lockfile(dir: &Path) -> Result<Option<NpmLockfile>, Error> {
+    for file_name in [
+        "package-lock.json",
+        "npm-shrinkwrap.json",
+        "yarn.lock",
+        "pnpm-lock.yaml",
+    ] {
+        let candidate = dir.join(file_name);
+        if !candidate.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(candidate)?;
+        let lockfile = match file_name {
+            "yarn.lock" => NpmLockfile::Yarn(content),
+            "pnpm-lock.yaml" => NpmLockfile::Pnpm(content),
+            _ => NpmLockfile::Npm(content),
+        };
+        return Ok(Some(lockfile));
+    }
+    Ok(None)
+}
+
+/// Lockfile of the given pyproject.toml, which Poetry writes next to it and uv at the root of the
+/// workspace
+fn python_lockfile(path: &Path, root: &Path) -> Result<Option<PythonLockfile>, Error> {
+    let poetry_lock = path.with_file_name("poetry.lock");
+    if poetry_lock.is_file() {
+        return Ok(Some(PythonLockfile::Poetry(fs::read_to_string(
+            poetry_lock,
+        )?)));
+    }
+    Ok(find_upwards(path, root, "uv.lock", |_| true)?
+        .map(|(_, content)| PythonLockfile::Uv(content)))
+}
+
 fn relative_path<P: AsRef<Path>>(full_path: P, root_path: P) -> Result<String, SouperIoError> {
     let relative_path = match utils::relative_path(full_path.as_ref(), root_path.as_ref()) {
         Ok(relative_path) => relative_path,
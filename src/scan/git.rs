@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    path::{Path, PathBuf},
+};
+
+/// Mode of index entries that pin a submodule to a commit
+const GITLINK_MODE: u32 = 0o160000;
+/// Length of the fixed part of index entries, up to and including their flags
+const ENTRY_LENGTH: usize = 62;
+const EXTENDED_FLAG: u16 = 0x4000;
+
+/// Commits that the submodules of a repository are pinned to, by the path of the submodule, as
+/// recorded in the index of its git directory. Repositories without git directory, e.g. exported
+/// source archives, pin none.
+pub fn submodule_commits(work_tree: &Path) -> Result<HashMap<String, String>, Error> {
+    let index = match git_dir(work_tree)? {
+        Some(git_dir) => git_dir.join("index"),
+        None => return Ok(HashMap::new()),
+    };
+    if !index.is_file() {
+        return Ok(HashMap::new());
+    }
+    match gitlinks(&fs::read(&index)?) {
+        Some(commits) => Ok(commits),
+        None => {
+            eprintln!(
+                "Warning: unable to read the commits of submodules from {}",
+                index.display()
+            );
+            Ok(HashMap::new())
+        }
+    }
+}
+
+fn git_dir(work_tree: &Path) -> Result<Option<PathBuf>, Error> {
+    let dot_git = work_tree.join(".git");
+    if dot_git.is_dir() {
+        return Ok(Some(dot_git));
+    }
+    // Submodules and linked work trees refer to their git directory, e.g.
+    // `gitdir: ../.git/modules/lib`
+    if dot_git.is_file() {
+        let content = fs::read_to_string(&dot_git)?;
+        if let Some(git_dir) = content.trim().strip_prefix("gitdir:") {
+            return Ok(Some(work_tree.join(git_dir.trim())));
+        }
+    }
+    Ok(None)
+}
+
+/// Paths and commits of the gitlink entries of an index of version 2, 3 or 4, see
+/// https://git-scm.com/docs/index-format
+fn gitlinks(index: &[u8]) -> Option<HashMap<String, String>> {
+    if index.get(..4)? != b"DIRC" {
+        return None;
+    }
+    let version = u32::from_be_bytes(index.get(4..8)?.try_into().ok()?);
+    let count = u32::from_be_bytes(index.get(8..12)?.try_into().ok()?);
+    let mut gitlinks = HashMap::new();
+    let mut position = 12;
+    let mut previous_path: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let entry = index.get(position..position + ENTRY_LENGTH)?;
+        let mode = u32::from_be_bytes(entry[24..28].try_into().ok()?);
+        let flags = u16::from_be_bytes(entry[60..62].try_into().ok()?);
+        let mut offset = position + ENTRY_LENGTH;
+        if version >= 3 && flags & EXTENDED_FLAG != 0 {
+            offset += 2;
+        }
+        let path = match version {
+            // Paths are prefix compressed, i.e. start with the number of bytes to remove from
+            // the end of the previous path
+            4 => {
+                let (removed, length) = varint(index.get(offset..)?)?;
+                offset += length;
+                let end = offset + index.get(offset..)?.iter().position(|byte| *byte == 0)?;
+                let mut path = previous_path
+                    .get(..previous_path.len().checked_sub(removed)?)?
+                    .to_vec();
+                path.extend_from_slice(&index[offset..end]);
+                position = end + 1;
+                path
+            }
+            _ => {
+                let end = offset + index.get(offset..)?.iter().position(|byte| *byte == 0)?;
+                // Entries are padded with one to eight NUL bytes to a multiple of eight bytes
+                position += (end - position + 8) & !7;
+                index[offset..end].to_vec()
+            }
+        };
+        if mode == GITLINK_MODE {
+            let commit = entry[40..60]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>();
+            gitlinks.insert(String::from_utf8_lossy(&path).into_owned(), commit);
+        }
+        previous_path = path;
+    }
+    Some(gitlinks)
+}
+
+/// Variable width integer as used for offsets by git, along with the number of bytes it spans
+fn varint(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut byte = *bytes.first()?;
+    let mut value = (byte & 0x7f) as usize;
+    let mut length = 1;
+    while byte & 0x80 != 0 {
+        byte = *bytes.get(length)?;
+        length += 1;
+        value = ((value + 1) << 7) | (byte & 0x7f) as usize;
+    }
+    Some((value, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const COMMIT: [u8; 20] = [
+        0x69, 0x29, 0x73, 0xe3, 0xd9, 0x37, 0x12, 0x9b, 0xcb, 0xf4, 0x06, 0x52, 0xeb, 0x9f, 0x2f,
+        0x61, 0xbe, 0xca, 0xd3, 0x32,
+    ];
+
+    fn entry(mode: u32, path: &[u8]) -> Vec<u8> {
+        let mut entry = vec![0; 24];
+        entry.extend_from_slice(&mode.to_be_bytes());
+        entry.extend_from_slice(&[0; 12]);
+        entry.extend_from_slice(&COMMIT);
+        entry.extend_from_slice(&(path.len() as u16).to_be_bytes());
+        entry
+    }
+
+    fn index(version: u32, entries: &[(u32, &str)]) -> Vec<u8> {
+        let mut index = b"DIRC".to_vec();
+        index.extend_from_slice(&version.to_be_bytes());
+        index.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        let mut previous_path = "";
+        for (mode, path) in entries {
+            let mut entry = entry(*mode, path.as_bytes());
+            match version {
+                4 => {
+                    let common = previous_path
+                        .bytes()
+                        .zip(path.bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    entry.push((previous_path.len() - common) as u8);
+                    entry.extend_from_slice(&path.as_bytes()[common..]);
+                    entry.push(0);
+                }
+                _ => {
+                    entry.extend_from_slice(path.as_bytes());
+                    let padding = 8 - entry.len() % 8;
+                    entry.extend(std::iter::repeat_n(0, padding));
+                }
+            }
+            index.extend(entry);
+            previous_path = path;
+        }
+        index
+    }
+
+    #[test_case(2)]
+    #[test_case(4)]
+    fn gitlink_commits(version: u32) {
+        let index = index(
+            version,
+            &[
+                (0o100644, ".gitmodules"),
+                (0o100644, "src/main.c"),
+                (GITLINK_MODE, "third_party/mbedtls"),
+                (GITLINK_MODE, "third_party/unity"),
+            ],
+        );
+        let commit = "692973e3d937129bcbf40652eb9f2f61becad332".to_owned();
+        assert_eq!(
+            Some(HashMap::from([
+                ("third_party/mbedtls".to_owned(), commit.to_owned()),
+                ("third_party/unity".to_owned(), commit),
+            ])),
+            gitlinks(&index)
+        );
+    }
+
+    #[test]
+    fn invalid_index() {
+        assert_eq!(None, gitlinks(b"DIRC\0\0\0\x02\0\0\0\x01"));
+    }
+
+    #[test_case(&[0x05], (5, 1))]
+    #[test_case(&[0x80, 0x00], (128, 2))]
+    fn varints(bytes: &[u8], expected: (usize, usize)) {
+        assert_eq!(Some(expected), varint(bytes));
+    }
+}
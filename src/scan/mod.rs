@@ -1 +1,2 @@
 pub mod dir_scan;
+pub mod git;
@@ -35,11 +35,7 @@ fn combine_soups(base: BTreeSet<Soup>, other: BTreeSet<Soup>) -> BTreeSet<Soup>
                 Some(self_meta) => combine_meta(self_meta, other_soup.meta),
                 None => other_soup.meta,
             };
-            Soup {
-                name: other_soup.name,
-                version: other_soup.version,
-                meta,
-            }
+            Soup { meta, ..other_soup }
         })
         .collect::<BTreeSet<Soup>>()
 }
@@ -57,6 +53,7 @@ fn combine_meta(mut base: Map<String, Value>, other: Map<String, Value>) -> Map<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::soup::model::Ecosystem;
     use std::collections::BTreeMap;
 
     fn create_contexts(path: &str, soups: Vec<Soup>) -> SoupContexts {
@@ -89,6 +86,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
 
@@ -105,6 +103,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
         let other = empty_contexts();
@@ -121,6 +120,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
         let other = create_contexts(
@@ -130,11 +130,13 @@ mod tests {
                     name: "some-dep".to_owned(),
                     version: "1.0.0".to_owned(),
                     meta: meta(vec![]),
+                    ..Default::default()
                 },
                 Soup {
                     name: "some-other-dep".to_owned(),
                     version: "1.0.0".to_owned(),
                     meta: meta(vec![]),
+                    ..Default::default()
                 },
             ],
         );
@@ -154,11 +156,13 @@ mod tests {
                     name: "some-dep".to_owned(),
                     version: "1.0.0".to_owned(),
                     meta: meta(vec![]),
+                    ..Default::default()
                 },
                 Soup {
                     name: "some-other-dep".to_owned(),
                     version: "1.0.0".to_owned(),
                     meta: meta(vec![]),
+                    ..Default::default()
                 },
             ],
         );
@@ -168,6 +172,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
 
@@ -185,6 +190,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("some-meta", "some-value")]),
+                ..Default::default()
             }],
         );
         let other = create_contexts(
@@ -193,6 +199,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
 
@@ -213,6 +220,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("some-meta", "some-value")]),
+                ..Default::default()
             }],
         );
         let other = create_contexts(
@@ -221,6 +229,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.2.0".to_owned(),
                 meta: meta(vec![]),
+                ..Default::default()
             }],
         );
 
@@ -241,6 +250,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("some-meta", "some-value")]),
+                ..Default::default()
             }],
         );
         let other = create_contexts(
@@ -249,6 +259,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("requirements", "")]),
+                ..Default::default()
             }],
         );
         base.apply(other);
@@ -271,6 +282,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("requirements", "a-requirement")]),
+                ..Default::default()
             }],
         );
         let other = create_contexts(
@@ -279,6 +291,7 @@ mod tests {
                 name: "some-dep".to_owned(),
                 version: "1.0.0".to_owned(),
                 meta: meta(vec![("requirements", "")]),
+                ..Default::default()
             }],
         );
 
@@ -290,4 +303,33 @@ mod tests {
         assert_eq!("1.0.0", soup.version);
         assert_eq!(meta(vec![("requirements", "a-requirement")]), soup.meta);
     }
+
+    #[test]
+    fn update_soup_ecosystem() {
+        let mut base = create_contexts(
+            "src/package.json",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                meta: meta(vec![("some-meta", "some-value")]),
+                ..Default::default()
+            }],
+        );
+        let other = create_contexts(
+            "src/package.json",
+            vec![Soup {
+                name: "some-dep".to_owned(),
+                version: "1.0.0".to_owned(),
+                ecosystem: Some(Ecosystem::Npm),
+                meta: meta(vec![]),
+                ..Default::default()
+            }],
+        );
+
+        base.apply(other);
+        let soups = base.contexts.get("src/package.json").unwrap();
+        let soup = soups.iter().find(|s| s.name == "some-dep").unwrap();
+        assert_eq!(Some(Ecosystem::Npm), soup.ecosystem);
+        assert_eq!(meta(vec![("some-meta", "some-value")]), soup.meta);
+    }
 }
@@ -0,0 +1,198 @@
+use crate::soup::model::{Soup, SoupContexts};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+pub struct ContextsDiff {
+    pub contexts: BTreeMap<String, SoupsDiff>,
+}
+
+#[derive(Default)]
+pub struct SoupsDiff {
+    pub added: Vec<Soup>,
+    pub removed: Vec<Soup>,
+    pub changed: Vec<(Soup, Soup)>,
+}
+
+impl SoupContexts {
+    pub fn diff(&self, other: &SoupContexts) -> ContextsDiff {
+        let empty = BTreeSet::new();
+        let paths = self
+            .contexts
+            .keys()
+            .chain(other.contexts.keys())
+            .collect::<BTreeSet<&String>>();
+        let contexts = paths
+            .into_iter()
+            .map(|path| {
+                let base = self.contexts.get(path).unwrap_or(&empty);
+                let other = other.contexts.get(path).unwrap_or(&empty);
+                (path.to_owned(), diff_soups(base, other))
+            })
+            .filter(|(_, diff)| !diff.is_empty())
+            .collect::<BTreeMap<String, SoupsDiff>>();
+        ContextsDiff { contexts }
+    }
+}
+
+impl ContextsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+}
+
+impl SoupsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn diff_soups(base: &BTreeSet<Soup>, other: &BTreeSet<Soup>) -> SoupsDiff {
+    let mut removed_by_name: BTreeMap<&String, Vec<&Soup>> = BTreeMap::new();
+    for soup in base.difference(other) {
+        removed_by_name.entry(&soup.name).or_default().push(soup);
+    }
+    let mut added_by_name: BTreeMap<&String, Vec<&Soup>> = BTreeMap::new();
+    for soup in other.difference(base) {
+        added_by_name.entry(&soup.name).or_default().push(soup);
+    }
+
+    let mut diff = SoupsDiff::default();
+    for (name, removed) in removed_by_name {
+        match added_by_name.remove(name) {
+            Some(added) if removed.len() == 1 && added.len() == 1 => {
+                diff.changed.push((removed[0].clone(), added[0].clone()));
+            }
+            Some(added) => {
+                diff.removed.extend(removed.into_iter().cloned());
+                diff.added.extend(added.into_iter().cloned());
+            }
+            None => diff.removed.extend(removed.into_iter().cloned()),
+        }
+    }
+    for (_, added) in added_by_name {
+        diff.added.extend(added.into_iter().cloned());
+    }
+    diff.added.sort();
+    diff.removed.sort();
+    diff
+}
+
+impl fmt::Display for ContextsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, diff) in &self.contexts {
+            writeln!(f, "{}", path)?;
+            for soup in &diff.added {
+                writeln!(f, "  + {} {}", soup.name, soup.version)?;
+            }
+            for soup in &diff.removed {
+                writeln!(f, "  - {} {}", soup.name, soup.version)?;
+            }
+            for (from, to) in &diff.changed {
+                writeln!(f, "  ~ {} {} -> {}", from.name, from.version, to.version)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Map;
+
+    fn soup(name: &str, version: &str) -> Soup {
+        Soup {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            meta: Map::new(),
+            ..Default::default()
+        }
+    }
+
+    fn create_contexts(contexts: Vec<(&str, Vec<Soup>)>) -> SoupContexts {
+        SoupContexts {
+            contexts: contexts
+                .into_iter()
+                .map(|(path, soups)| (path.to_owned(), soups.into_iter().collect()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn no_changes() {
+        let base = create_contexts(vec![("src/package.json", vec![soup("some-dep", "1.0.0")])]);
+        let other = create_contexts(vec![("src/package.json", vec![soup("some-dep", "1.0.0")])]);
+
+        let diff = base.diff(&other);
+        assert_eq!(true, diff.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_soups() {
+        let base = create_contexts(vec![("src/package.json", vec![soup("old-dep", "1.0.0")])]);
+        let other = create_contexts(vec![("src/package.json", vec![soup("new-dep", "2.0.0")])]);
+
+        let diff = base.diff(&other);
+        let soups_diff = diff.contexts.get("src/package.json").unwrap();
+        assert_eq!(vec![soup("new-dep", "2.0.0")], soups_diff.added);
+        assert_eq!(vec![soup("old-dep", "1.0.0")], soups_diff.removed);
+        assert_eq!(true, soups_diff.changed.is_empty());
+    }
+
+    #[test]
+    fn changed_version() {
+        let base = create_contexts(vec![("src/package.json", vec![soup("some-dep", "1.0.0")])]);
+        let other = create_contexts(vec![("src/package.json", vec![soup("some-dep", "1.2.0")])]);
+
+        let diff = base.diff(&other);
+        let soups_diff = diff.contexts.get("src/package.json").unwrap();
+        assert_eq!(
+            vec![(soup("some-dep", "1.0.0"), soup("some-dep", "1.2.0"))],
+            soups_diff.changed
+        );
+        assert_eq!(true, soups_diff.added.is_empty());
+        assert_eq!(true, soups_diff.removed.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_contexts() {
+        let base = create_contexts(vec![("src/Dockerfile", vec![soup("postgres", "14.4")])]);
+        let other = create_contexts(vec![
+            ("src/Dockerfile", vec![]),
+            ("src/package.json", vec![soup("some-dep", "1.0.0")]),
+        ]);
+
+        let diff = base.diff(&other);
+        assert_eq!(2, diff.contexts.len());
+        assert_eq!(
+            vec![soup("postgres", "14.4")],
+            diff.contexts.get("src/Dockerfile").unwrap().removed
+        );
+        assert_eq!(
+            vec![soup("some-dep", "1.0.0")],
+            diff.contexts.get("src/package.json").unwrap().added
+        );
+    }
+
+    #[test]
+    fn display() {
+        let base = create_contexts(vec![(
+            "src/package.json",
+            vec![soup("old-dep", "1.0.0"), soup("some-dep", "1.0.0")],
+        )]);
+        let other = create_contexts(vec![(
+            "src/package.json",
+            vec![soup("new-dep", "2.0.0"), soup("some-dep", "1.2.0")],
+        )]);
+
+        let diff = base.diff(&other);
+        assert_eq!(
+            r#"src/package.json
+  + new-dep 2.0.0
+  - old-dep 1.0.0
+  ~ some-dep 1.0.0 -> 1.2.0
+"#,
+            diff.to_string()
+        );
+    }
+}
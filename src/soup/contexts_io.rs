@@ -46,6 +46,23 @@ impl SoupContexts {
         self.write(&mut output_file)
     }
 
+    pub fn is_written_to_file(&self, file_path: &PathBuf) -> Result<bool, SouperIoError> {
+        if !file_path.is_file() {
+            return Ok(false);
+        }
+        let file_content = match fs::read_to_string(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                return Err(SouperIoError {
+                    message: format!("Not able to read file: {} ({})", file_path.display(), e),
+                });
+            }
+        };
+        let mut buffer = Vec::<u8>::new();
+        self.write(&mut buffer)?;
+        Ok(file_content.trim_end().as_bytes() == buffer.trim_ascii_end())
+    }
+
     fn write<W>(&self, writer: &mut W) -> Result<(), SouperIoError>
     where
         W: Write,
@@ -114,7 +131,8 @@ mod tests {
                 Soup {
                     name: "some-dependency".to_owned(),
                     version: "6.6.6".to_owned(),
-                    meta: Map::new()
+                    meta: Map::new(),
+                    ..Default::default()
                 },
                 Soup {
                     name: "another-dependency".to_owned(),
@@ -122,7 +140,8 @@ mod tests {
                     meta: serde_json::json!({ "rationale": "Do this and that" })
                         .as_object()
                         .unwrap()
-                        .clone()
+                        .clone(),
+                    ..Default::default()
                 }
             ]
             .into_iter()
@@ -134,7 +153,8 @@ mod tests {
             vec![Soup {
                 name: "some-image".to_owned(),
                 version: "6.0-jammy".to_owned(),
-                meta: Map::new()
+                meta: Map::new(),
+                ..Default::default()
             }]
             .into_iter()
             .collect::<BTreeSet<Soup>>(),
@@ -152,6 +172,7 @@ mod tests {
                         name: "some-dependency".to_owned(),
                         version: "6.6.6".to_owned(),
                         meta: Map::new(),
+                        ..Default::default()
                     }]
                     .into_iter()
                     .collect::<BTreeSet<Soup>>(),
@@ -165,6 +186,7 @@ mod tests {
                             .as_object()
                             .unwrap()
                             .clone(),
+                        ..Default::default()
                     }]
                     .into_iter()
                     .collect::<BTreeSet<Soup>>(),
@@ -215,4 +237,32 @@ mod tests {
         input.write(&mut buffer).unwrap();
         assert_eq!("{}".to_owned(), String::from_utf8(buffer).unwrap())
     }
+
+    #[test]
+    fn is_written_to_file() {
+        let input = SoupContexts {
+            contexts: vec![(
+                "src/package.json".to_owned(),
+                vec![Soup {
+                    name: "some-dependency".to_owned(),
+                    version: "6.6.6".to_owned(),
+                    meta: Map::new(),
+                    ..Default::default()
+                }]
+                .into_iter()
+                .collect::<BTreeSet<Soup>>(),
+            )]
+            .into_iter()
+            .collect::<BTreeMap<String, BTreeSet<Soup>>>(),
+        };
+        let file_path = std::env::temp_dir().join("souper_is_written_to_file.json");
+        input.write_to_file(&file_path).unwrap();
+        assert_eq!(true, input.is_written_to_file(&file_path).unwrap());
+
+        fs::write(&file_path, "{}").unwrap();
+        assert_eq!(false, input.is_written_to_file(&file_path).unwrap());
+
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(false, input.is_written_to_file(&file_path).unwrap());
+    }
 }
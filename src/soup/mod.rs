@@ -1,3 +1,4 @@
 pub mod contexts_apply;
+pub mod contexts_diff;
 pub mod contexts_io;
 pub mod model;
@@ -5,13 +5,72 @@ use std::{
     fmt,
 };
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct Soup {
     pub name: String,
     pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecosystem: Option<Ecosystem>,
+    /// Where the SOUP is used, e.g. `runtime`, `dev` or `build`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Details found by the parser, updated on every scan as opposed to the meta property
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
     pub meta: Map<String, Value>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+    NuGet,
+    Docker,
+    Apt,
+    PyPI,
+    Go,
+    Maven,
+    Apk,
+    Rpm,
+    Gem,
+    Helm,
+    GitHubActions,
+    Terraform,
+    Vcpkg,
+    Conan,
+    Composer,
+    Pub,
+    Hex,
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::NuGet => "nuget",
+            Ecosystem::Docker => "docker",
+            Ecosystem::Apt => "apt",
+            Ecosystem::PyPI => "pypi",
+            Ecosystem::Go => "go",
+            Ecosystem::Maven => "maven",
+            Ecosystem::Apk => "apk",
+            Ecosystem::Rpm => "rpm",
+            Ecosystem::Gem => "gem",
+            Ecosystem::Helm => "helm",
+            Ecosystem::GitHubActions => "githubactions",
+            Ecosystem::Terraform => "terraform",
+            Ecosystem::Vcpkg => "vcpkg",
+            Ecosystem::Conan => "conan",
+            Ecosystem::Composer => "composer",
+            Ecosystem::Pub => "pub",
+            Ecosystem::Hex => "hex",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 impl PartialEq for Soup {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name && self.version == other.version
@@ -33,7 +92,7 @@ impl Ord for Soup {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SoupContexts {
     pub contexts: BTreeMap<String, BTreeSet<Soup>>,
 }
@@ -93,6 +152,7 @@ mod tests {
             name: "some-dependency".to_owned(),
             version: "1.0.0".to_owned(),
             meta: Map::new(),
+            ..Default::default()
         };
         let s2 = Soup {
             name: "some-dependency".to_owned(),
@@ -101,6 +161,7 @@ mod tests {
                 .as_object()
                 .unwrap()
                 .clone(),
+            ..Default::default()
         };
         assert_eq!(s1, s2);
     }
@@ -111,11 +172,13 @@ mod tests {
             name: "some-dependency".to_owned(),
             version: "1.0.0".to_owned(),
             meta: Map::new(),
+            ..Default::default()
         };
         let s2 = Soup {
             name: "some-dependency".to_owned(),
             version: "1.0.1".to_owned(),
             meta: Map::new(),
+            ..Default::default()
         };
         assert_ne!(s1, s2);
     }
@@ -1,4 +1,5 @@
 use std::path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn relative_path<P: AsRef<path::Path>>(
     full_path: P,
@@ -10,10 +11,69 @@ pub fn relative_path<P: AsRef<path::Path>>(
     }
 }
 
+/// Whether the name matches a pattern where `*` matches any sequence of characters, e.g. `pkg-*`.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<&str>>();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Formats the given time as an ISO 8601 UTC timestamp with second precision, e.g. `2022-08-01T12:30:00Z`.
+pub fn utc_timestamp(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs(),
+        Err(_e) => 0,
+    };
+    let days = (seconds / 86_400) as i64;
+    let seconds_of_day = seconds % 86_400;
+
+    // Conversion from days since epoch to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use path::{Path, PathBuf};
+    use std::time::Duration;
+    use test_case::test_case;
 
     #[test]
     fn some_test() {
@@ -26,4 +86,25 @@ mod tests {
 
         assert_eq!(expected_path, result.unwrap());
     }
+
+    #[test_case("*", "some-lib", true)]
+    #[test_case("some-*", "some-lib", true)]
+    #[test_case("*-lib", "some-lib", true)]
+    #[test_case("s*e*b", "some-lib", true)]
+    #[test_case("some-lib", "some-lib", true)]
+    #[test_case("some-*", "other-lib", false)]
+    #[test_case("some*lib*", "some-app", false)]
+    #[test_case("ab*ba", "aba", false)]
+    fn wildcard_matching(pattern: &str, name: &str, expected: bool) {
+        assert_eq!(expected, wildcard_match(pattern, name));
+    }
+
+    #[test_case(0, "1970-01-01T00:00:00Z")]
+    #[test_case(951_827_696, "2000-02-29T12:34:56Z")]
+    #[test_case(1_659_357_000, "2022-08-01T12:30:00Z")]
+    #[test_case(4_102_444_799, "2099-12-31T23:59:59Z")]
+    fn utc_timestamp_formatting(seconds: u64, expected: &str) {
+        let time = UNIX_EPOCH + Duration::from_secs(seconds);
+        assert_eq!(expected, utc_timestamp(time));
+    }
 }